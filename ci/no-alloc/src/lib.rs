@@ -0,0 +1,30 @@
+#![no_std]
+
+use core::panic::PanicInfo;
+use rs_merkle::{algorithms::Sha256, MerkleProofRef};
+
+/// Verifies a single leaf proof of a Sha256 tree
+///
+/// # Safety
+///
+/// `root` and `leaf` must point to 32 bytes, `proof` must point to `proof_len` bytes
+#[no_mangle]
+pub unsafe extern "C" fn rs_merkle_verify_sha256(
+    root: *const [u8; 32],
+    leaf_index: usize,
+    leaf: *const [u8; 32],
+    total_leaves_count: usize,
+    proof: *const u8,
+    proof_len: usize,
+) -> bool {
+    let proof_bytes = core::slice::from_raw_parts(proof, proof_len);
+    match MerkleProofRef::<Sha256>::from_bytes(proof_bytes) {
+        Ok(proof) => proof.verify(*root, leaf_index, *leaf, total_leaves_count),
+        Err(_) => false,
+    }
+}
+
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    loop {}
+}
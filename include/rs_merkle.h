@@ -0,0 +1,77 @@
+/*
+ * C API of rs_merkle, available with the `ffi` feature. See src/ffi.rs for details.
+ *
+ * Build the shared or the static library with:
+ *     cargo rustc --release --features ffi --crate-type cdylib
+ *     cargo rustc --release --features ffi --crate-type staticlib
+ */
+
+#ifndef RS_MERKLE_H
+#define RS_MERKLE_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Status codes */
+#define RS_MERKLE_OK 0
+#define RS_MERKLE_INVALID_ARGUMENT -1
+#define RS_MERKLE_BUFFER_TOO_SMALL -2
+#define RS_MERKLE_EMPTY_TREE -3
+#define RS_MERKLE_PROOF_ERROR -4
+
+/* Hashing algorithms */
+#define RS_MERKLE_SHA256 0
+#define RS_MERKLE_SHA384 1
+#define RS_MERKLE_KECCAK256 2
+
+typedef struct RsMerkleTree RsMerkleTree;
+
+size_t rs_merkle_hash_size(uint32_t algorithm_code);
+
+int32_t rs_merkle_hash(uint32_t algorithm_code,
+                       const uint8_t *data,
+                       size_t data_len,
+                       uint8_t *out,
+                       size_t out_len);
+
+RsMerkleTree *rs_merkle_tree_new(uint32_t algorithm_code);
+
+void rs_merkle_tree_free(RsMerkleTree *tree);
+
+int32_t rs_merkle_tree_append(RsMerkleTree *tree, const uint8_t *leaves, size_t leaves_len);
+
+int32_t rs_merkle_tree_commit(RsMerkleTree *tree);
+
+int32_t rs_merkle_tree_rollback(RsMerkleTree *tree);
+
+size_t rs_merkle_tree_leaves_len(const RsMerkleTree *tree);
+
+int32_t rs_merkle_tree_root(const RsMerkleTree *tree, uint8_t *out, size_t out_len);
+
+int32_t rs_merkle_tree_proof(const RsMerkleTree *tree,
+                             const size_t *leaf_indices,
+                             size_t leaf_indices_len,
+                             uint8_t *out,
+                             size_t out_len,
+                             size_t *written);
+
+int32_t rs_merkle_verify(uint32_t algorithm_code,
+                         const uint8_t *proof,
+                         size_t proof_len,
+                         const uint8_t *root,
+                         size_t root_len,
+                         const size_t *leaf_indices,
+                         size_t leaf_indices_len,
+                         const uint8_t *leaves,
+                         size_t leaves_len,
+                         size_t total_leaves_count);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* RS_MERKLE_H */
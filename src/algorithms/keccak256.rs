@@ -1,5 +1,9 @@
 #[cfg(feature = "keccak256")]
-use crate::{prelude::*, Hasher};
+use crate::prelude::*;
+#[cfg(all(feature = "keccak256", feature = "alloc"))]
+use crate::Hasher;
+#[cfg(all(feature = "keccak256", not(feature = "alloc")))]
+use crate::NodeHasher;
 
 #[cfg(feature = "keccak256")]
 use tiny_keccak::{Hasher as KeccakHasher, Keccak};
@@ -8,15 +12,52 @@ use tiny_keccak::{Hasher as KeccakHasher, Keccak};
 #[derive(Clone)]
 pub struct Keccak256Algorithm {}
 
-#[cfg(feature = "keccak256")]
+#[cfg(all(feature = "keccak256", feature = "alloc"))]
 impl Hasher for Keccak256Algorithm {
     type Hash = [u8; 32];
 
-    fn hash(data: &[u8]) -> Self::Hash {
-        let mut hasher = Keccak::v256();
-        let mut output = [0u8; 32];
-        hasher.update(data);
-        hasher.finalize(&mut output);
-        output
+    fn hash(data: &[u8]) -> [u8; 32] {
+        hash(data)
+    }
+
+    fn concat_and_hash(left: &[u8; 32], right: Option<&[u8; 32]>) -> [u8; 32] {
+        concat_and_hash(left, right)
+    }
+}
+
+#[cfg(all(feature = "keccak256", not(feature = "alloc")))]
+impl NodeHasher for Keccak256Algorithm {
+    type Hash = [u8; 32];
+
+    fn hash(data: &[u8]) -> [u8; 32] {
+        hash(data)
+    }
+
+    fn concat_and_hash(left: &[u8; 32], right: Option<&[u8; 32]>) -> [u8; 32] {
+        concat_and_hash(left, right)
+    }
+}
+
+#[cfg(feature = "keccak256")]
+fn hash(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let mut output = [0u8; 32];
+    hasher.update(data);
+    hasher.finalize(&mut output);
+    output
+}
+
+#[cfg(feature = "keccak256")]
+fn concat_and_hash(left: &[u8; 32], right: Option<&[u8; 32]>) -> [u8; 32] {
+    match right {
+        Some(right_node) => {
+            let mut hasher = Keccak::v256();
+            let mut output = [0u8; 32];
+            hasher.update(left);
+            hasher.update(right_node);
+            hasher.finalize(&mut output);
+            output
+        }
+        None => *left,
     }
 }
@@ -1,4 +1,8 @@
-use crate::{prelude::*, Hasher};
+use crate::prelude::*;
+#[cfg(feature = "alloc")]
+use crate::Hasher;
+#[cfg(not(feature = "alloc"))]
+use crate::NodeHasher;
 use sha2::{digest::FixedOutput, Digest, Sha256};
 
 /// Sha256 implementation of the [`Hasher`] trait.
@@ -31,13 +35,48 @@ use sha2::{digest::FixedOutput, Digest, Sha256};
 #[derive(Clone)]
 pub struct Sha256Algorithm {}
 
+#[cfg(feature = "alloc")]
 impl Hasher for Sha256Algorithm {
     type Hash = [u8; 32];
 
     fn hash(data: &[u8]) -> [u8; 32] {
-        let mut hasher = Sha256::new();
+        hash(data)
+    }
+
+    fn concat_and_hash(left: &[u8; 32], right: Option<&[u8; 32]>) -> [u8; 32] {
+        concat_and_hash(left, right)
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+impl NodeHasher for Sha256Algorithm {
+    type Hash = [u8; 32];
+
+    fn hash(data: &[u8]) -> [u8; 32] {
+        hash(data)
+    }
+
+    fn concat_and_hash(left: &[u8; 32], right: Option<&[u8; 32]>) -> [u8; 32] {
+        concat_and_hash(left, right)
+    }
+}
+
+fn hash(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+
+    hasher.update(data);
+    <[u8; 32]>::from(hasher.finalize_fixed())
+}
+
+fn concat_and_hash(left: &[u8; 32], right: Option<&[u8; 32]>) -> [u8; 32] {
+    match right {
+        Some(right_node) => {
+            let mut hasher = Sha256::new();
 
-        hasher.update(data);
-        <[u8; 32]>::from(hasher.finalize_fixed())
+            hasher.update(left);
+            hasher.update(right_node);
+            <[u8; 32]>::from(hasher.finalize_fixed())
+        }
+        None => *left,
     }
 }
@@ -2,7 +2,11 @@
 // sha384.rs
 // Author imotai <codego.me@gmail.com>
 //
-use crate::{prelude::*, Hasher};
+use crate::prelude::*;
+#[cfg(feature = "alloc")]
+use crate::Hasher;
+#[cfg(not(feature = "alloc"))]
+use crate::NodeHasher;
 use sha2::{digest::FixedOutput, Digest, Sha384};
 
 /// Sha384 implementation of the [`Hasher`] trait.
@@ -35,12 +39,47 @@ use sha2::{digest::FixedOutput, Digest, Sha384};
 #[derive(Clone)]
 pub struct Sha384Algorithm {}
 
+#[cfg(feature = "alloc")]
 impl Hasher for Sha384Algorithm {
     type Hash = [u8; 48];
 
     fn hash(data: &[u8]) -> [u8; 48] {
-        let mut hasher = Sha384::new();
-        hasher.update(data);
-        <[u8; 48]>::from(hasher.finalize_fixed())
+        hash(data)
+    }
+
+    fn concat_and_hash(left: &[u8; 48], right: Option<&[u8; 48]>) -> [u8; 48] {
+        concat_and_hash(left, right)
+    }
+}
+
+#[cfg(not(feature = "alloc"))]
+impl NodeHasher for Sha384Algorithm {
+    type Hash = [u8; 48];
+
+    fn hash(data: &[u8]) -> [u8; 48] {
+        hash(data)
+    }
+
+    fn concat_and_hash(left: &[u8; 48], right: Option<&[u8; 48]>) -> [u8; 48] {
+        concat_and_hash(left, right)
+    }
+}
+
+fn hash(data: &[u8]) -> [u8; 48] {
+    let mut hasher = Sha384::new();
+    hasher.update(data);
+    <[u8; 48]>::from(hasher.finalize_fixed())
+}
+
+fn concat_and_hash(left: &[u8; 48], right: Option<&[u8; 48]>) -> [u8; 48] {
+    match right {
+        Some(right_node) => {
+            let mut hasher = Sha384::new();
+
+            hasher.update(left);
+            hasher.update(right_node);
+            <[u8; 48]>::from(hasher.finalize_fixed())
+        }
+        None => *left,
     }
 }
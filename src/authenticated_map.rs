@@ -0,0 +1,419 @@
+use crate::prelude::*;
+use crate::{utils, Error, Hasher, MerkleProof, MerkleTree, NonMembershipProof};
+use alloc::collections::BTreeMap;
+use core::cmp::Ordering;
+
+/// Previous values of the keys changed by a commit, `None` for keys that didn't exist
+type Undo<K, V> = Vec<(K, Option<V>)>;
+
+/// Committed value of a key, `None` if the key is absent, and the proof of it
+type ValueWithProof<'a, K, V, H> = (Option<&'a V>, MapProof<K, V, H>);
+
+/// [`AuthenticatedMap`] is a key-value map committed by the root of a [`MerkleTree`].
+///
+/// Leaves of the tree are [`AuthenticatedMap::leaf_hash`]es of the entries, ordered by key, so
+/// the same entries always produce the same root, and a missing key can be proven absent by
+/// the two entries around it. Changes made with [`AuthenticatedMap::insert`] and
+/// [`AuthenticatedMap::remove`] work like changes to a [`MerkleTree`]: they don't affect the
+/// map until [`AuthenticatedMap::commit`] is called, and every commit can be reverted with
+/// [`AuthenticatedMap::rollback`].
+///
+/// A commit rehashes only the changed entries and the entries after the first inserted or
+/// removed key, since those move to other leaves.
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{AuthenticatedMap, algorithms::Sha256};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// type Map = AuthenticatedMap<&'static str, &'static str, Sha256>;
+///
+/// let mut map = Map::new();
+/// map.insert("alice", "10").insert("bob", "20").commit();
+///
+/// let root = map.root().ok_or("couldn't get the merkle root")?;
+/// let (value, proof) = map.get_with_proof(&"bob")?;
+/// assert_eq!(value, Some(&"20"));
+/// assert!(Map::verify(root, 2, &"bob", value, &proof));
+///
+/// // Missing keys are proven absent
+/// let (value, proof) = map.get_with_proof(&"carol")?;
+/// assert_eq!(value, None);
+/// assert!(Map::verify(root, 2, &"carol", None, &proof));
+///
+/// // Updates and removals are staged until they're committed
+/// map.insert("bob", "25").remove("alice");
+/// assert_eq!(map.get(&"bob"), Some(&"20"));
+///
+/// map.commit();
+/// assert_eq!(map.get(&"bob"), Some(&"25"));
+/// assert_eq!(map.get(&"alice"), None);
+///
+/// map.rollback();
+/// assert_eq!(map.root(), Some(root));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct AuthenticatedMap<K, V, H: Hasher> {
+    entries: Vec<(K, V)>,
+    uncommitted: BTreeMap<K, Option<V>>,
+    /// The tree keeps only hashes, so previous values of the changed keys are kept to restore
+    /// the entries when the tree is rolled back
+    undo_history: Vec<Undo<K, V>>,
+    merkle_tree: MerkleTree<H>,
+}
+
+/// Proof that a key is in an [`AuthenticatedMap`] with some value, or that it is not in the
+/// map. Made by [`AuthenticatedMap::get_with_proof`] and checked with
+/// [`AuthenticatedMap::verify`].
+pub enum MapProof<K, V, H: Hasher> {
+    /// The key is in the map
+    Membership {
+        /// Position of the entry among the entries ordered by key
+        index: usize,
+        /// Proof of the leaf of the entry
+        proof: MerkleProof<H>,
+    },
+    /// The key is not in the map
+    NonMembership {
+        /// The entry with the greatest key that is less than the missing key
+        left: Option<(K, V)>,
+        /// The entry with the least key that is greater than the missing key
+        right: Option<(K, V)>,
+        /// Proof that the leaves of `left` and `right` are next to each other
+        proof: NonMembershipProof<H>,
+    },
+}
+
+impl<K, V, H> Default for AuthenticatedMap<K, V, H>
+where
+    K: Ord + Clone + AsRef<[u8]>,
+    V: AsRef<[u8]>,
+    H: Hasher,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, H> AuthenticatedMap<K, V, H>
+where
+    K: Ord + Clone + AsRef<[u8]>,
+    V: AsRef<[u8]>,
+    H: Hasher,
+{
+    /// Creates an empty map
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            uncommitted: BTreeMap::new(),
+            undo_history: Vec::new(),
+            merkle_tree: MerkleTree::new(),
+        }
+    }
+
+    /// Builds a map from the entries and commits them. If a key is repeated, the last value
+    /// is kept.
+    pub fn from_entries<I: IntoIterator<Item = (K, V)>>(entries: I) -> Self {
+        let mut map = Self::new();
+        for (key, value) in entries {
+            map.insert(key, value);
+        }
+        map.commit();
+        map
+    }
+
+    /// Returns the leaf of an entry: the hash of the key length as a little-endian `u64`, the
+    /// key bytes and the value bytes
+    pub fn leaf_hash(key: &K, value: &V) -> H::Hash {
+        let key = key.as_ref();
+        let mut data = (key.len() as u64).to_le_bytes().to_vec();
+        data.extend_from_slice(key);
+        data.extend_from_slice(value.as_ref());
+        H::hash(&data)
+    }
+
+    /// Verifies a proof made by [`AuthenticatedMap::get_with_proof`] against the root and the
+    /// number of entries of the map. The number of entries has to come from the verifier, the
+    /// same as the root. `value` is the value the key is expected to have, or `None` if the
+    /// key is expected to be absent.
+    pub fn verify(
+        root: H::Hash,
+        entries_count: usize,
+        key: &K,
+        value: Option<&V>,
+        proof: &MapProof<K, V, H>,
+    ) -> bool {
+        match (proof, value) {
+            (MapProof::Membership { index, proof }, Some(value)) => proof.verify_strict(
+                root,
+                &[*index],
+                &[Self::leaf_hash(key, value)],
+                entries_count,
+            ),
+            (MapProof::NonMembership { left, right, proof }, None) => {
+                let left_matches = match (left, proof.left()) {
+                    (Some((left_key, left_value)), Some((_, leaf))) => {
+                        left_key < key && Self::leaf_hash(left_key, left_value) == leaf
+                    }
+                    (None, None) => true,
+                    _ => false,
+                };
+                let right_matches = match (right, proof.right()) {
+                    (Some((right_key, right_value)), Some((_, leaf))) => {
+                        key < right_key && Self::leaf_hash(right_key, right_value) == leaf
+                    }
+                    (None, None) => true,
+                    _ => false,
+                };
+
+                left_matches && right_matches && proof.verify_neighbours(root, entries_count)
+            }
+            _ => false,
+        }
+    }
+
+    /// Inserts or updates an entry. The change is applied by [`AuthenticatedMap::commit`].
+    pub fn insert(&mut self, key: K, value: V) -> &mut Self {
+        self.uncommitted.insert(key, Some(value));
+        self
+    }
+
+    /// Removes an entry. The change is applied by [`AuthenticatedMap::commit`].
+    pub fn remove(&mut self, key: K) -> &mut Self {
+        self.uncommitted.insert(key, None);
+        self
+    }
+
+    /// Applies the changes made by [`AuthenticatedMap::insert`] and
+    /// [`AuthenticatedMap::remove`] and commits them to the tree, see [`MerkleTree::commit`].
+    /// Commits are saved to the history, so the map can be rolled back to any previous commit.
+    pub fn commit(&mut self) {
+        if self.uncommitted.is_empty() {
+            return;
+        }
+
+        let (replaced_leaves, entries_count) = self.uncommitted_leaves();
+        let mut undo = Vec::with_capacity(self.uncommitted.len());
+        for (key, change) in core::mem::take(&mut self.uncommitted) {
+            let previous_value = match (self.search(&key), change) {
+                (Ok(index), Some(value)) => {
+                    Some(core::mem::replace(&mut self.entries[index].1, value))
+                }
+                (Ok(index), None) => Some(self.entries.remove(index).1),
+                (Err(index), Some(value)) => {
+                    self.entries.insert(index, (key.clone(), value));
+                    None
+                }
+                (Err(_), None) => continue,
+            };
+            undo.push((key, previous_value));
+        }
+
+        self.undo_history.push(undo);
+        self.merkle_tree
+            .commit_replacing(&replaced_leaves, entries_count);
+    }
+
+    /// Reverts the most recent commit, see [`MerkleTree::rollback`]. Uncommitted changes are
+    /// kept.
+    pub fn rollback(&mut self) {
+        if let Some(undo) = self.undo_history.pop() {
+            for (key, previous_value) in undo {
+                match (self.search(&key), previous_value) {
+                    (Ok(index), Some(value)) => self.entries[index].1 = value,
+                    (Ok(index), None) => {
+                        self.entries.remove(index);
+                    }
+                    (Err(index), Some(value)) => self.entries.insert(index, (key, value)),
+                    (Err(_), None) => {}
+                }
+            }
+            self.merkle_tree.rollback();
+        }
+    }
+
+    /// Clears the changes that weren't committed
+    pub fn abort_uncommitted(&mut self) {
+        self.uncommitted.clear()
+    }
+
+    /// Returns the committed value of the key
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let index = self.search(key).ok()?;
+        Some(&self.entries[index].1)
+    }
+
+    /// Returns the committed value of the key and a proof of it, which is checked with
+    /// [`AuthenticatedMap::verify`]. If the key is not in the map, the value is `None` and the
+    /// proof shows that the key is absent: it contains the entries right before and after
+    /// the position the key would have.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::EmptyTree`] if the map has no committed entries
+    ///
+    /// [`ErrorKind::EmptyTree`]: crate::ErrorKind::EmptyTree
+    pub fn get_with_proof(&self, key: &K) -> Result<ValueWithProof<'_, K, V, H>, Error>
+    where
+        V: Clone,
+    {
+        if self.entries.is_empty() {
+            return Err(Error::empty_tree());
+        }
+
+        let position = match self.search(key) {
+            Ok(index) => {
+                let proof = MapProof::Membership {
+                    index,
+                    proof: self.merkle_tree.proof(&[index]),
+                };
+                return Ok((Some(&self.entries[index].1), proof));
+            }
+            Err(position) => position,
+        };
+
+        let left = position.checked_sub(1);
+        let right = Some(position).filter(|index| *index < self.entries.len());
+        let indices: Vec<usize> = left.iter().chain(right.iter()).copied().collect();
+        let neighbour = |index: usize| {
+            let (key, value) = &self.entries[index];
+            (index, Self::leaf_hash(key, value))
+        };
+        let proof = NonMembershipProof::new(
+            left.map(neighbour),
+            right.map(neighbour),
+            self.merkle_tree.proof(&indices),
+            self.entries.len(),
+        );
+
+        Ok((
+            None,
+            MapProof::NonMembership {
+                left: left.map(|index| self.entries[index].clone()),
+                right: right.map(|index| self.entries[index].clone()),
+                proof,
+            },
+        ))
+    }
+
+    /// Returns `true` if the key is committed to the map
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.search(key).is_ok()
+    }
+
+    /// Returns the number of committed entries
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no committed entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over the committed entries in the order of the tree leaves
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> + '_ {
+        self.entries.iter().map(|(key, value)| (key, value))
+    }
+
+    /// Returns the root of the map, or `None` if the map is empty
+    pub fn root(&self) -> Option<H::Hash> {
+        self.merkle_tree.root()
+    }
+
+    /// Same as [`AuthenticatedMap::root`], but serialized as a hex string
+    pub fn root_hex(&self) -> Option<String> {
+        self.merkle_tree.root_hex()
+    }
+
+    /// Calculates the root as if the uncommitted changes were committed. Returns `None` if
+    /// there are no uncommitted changes or if the map would be empty.
+    pub fn uncommitted_root(&self) -> Option<H::Hash> {
+        if self.uncommitted.is_empty() {
+            return None;
+        }
+
+        let (replaced_leaves, entries_count) = self.uncommitted_leaves();
+        self.merkle_tree
+            .replacement_root(&replaced_leaves, entries_count)
+    }
+
+    /// Same as [`AuthenticatedMap::uncommitted_root`], but serialized as a hex string
+    pub fn uncommitted_root_hex(&self) -> Option<String> {
+        let root = self.uncommitted_root()?;
+        Some(utils::collections::to_hex_string(&root))
+    }
+
+    /// Returns the underlying tree. Its leaves are the leaf hashes of the committed entries.
+    pub fn merkle_tree(&self) -> &MerkleTree<H> {
+        &self.merkle_tree
+    }
+
+    /// Finds the position of the key among the committed entries, or the position it would
+    /// be inserted at
+    fn search(&self, key: &K) -> Result<usize, usize> {
+        self.entries
+            .binary_search_by(|(entry_key, _)| entry_key.cmp(key))
+    }
+
+    /// Returns the leaves that change when the uncommitted changes are committed, with their
+    /// positions, and the number of entries after the commit. Updated values before the first
+    /// inserted or removed key keep their positions, every entry after it is moved.
+    fn uncommitted_leaves(&self) -> (Vec<(usize, H::Hash)>, usize) {
+        let mut replaced_leaves = Vec::new();
+        let mut first_move = None;
+        for (key, change) in &self.uncommitted {
+            match (self.search(key), change) {
+                (Ok(index), Some(value)) => {
+                    replaced_leaves.push((index, Self::leaf_hash(key, value)));
+                }
+                (Ok(index), None) | (Err(index), Some(_)) => {
+                    first_move = Some((index, key));
+                    break;
+                }
+                (Err(_), None) => {}
+            }
+        }
+
+        let (first_index, first_key) = match first_move {
+            Some(first_move) => first_move,
+            None => return (replaced_leaves, self.entries.len()),
+        };
+
+        // Merging the entries after the first move with the changes after it
+        let mut entries = self.entries[first_index..].iter().peekable();
+        let mut changes = self.uncommitted.range(first_key..).peekable();
+        let mut position = first_index;
+        loop {
+            let order = match (entries.peek(), changes.peek()) {
+                (Some((entry_key, _)), Some((change_key, _))) => entry_key.cmp(change_key),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => break,
+            };
+
+            let leaf = match order {
+                Ordering::Less => entries
+                    .next()
+                    .map(|(key, value)| Self::leaf_hash(key, value)),
+                Ordering::Equal | Ordering::Greater => {
+                    if order == Ordering::Equal {
+                        entries.next();
+                    }
+                    changes.next().and_then(|(key, change)| {
+                        change.as_ref().map(|value| Self::leaf_hash(key, value))
+                    })
+                }
+            };
+
+            if let Some(leaf) = leaf {
+                replaced_leaves.push((position, leaf));
+                position += 1;
+            }
+        }
+
+        (replaced_leaves, position)
+    }
+}
@@ -0,0 +1,210 @@
+use crate::prelude::*;
+use crate::{utils, Hasher, MerkleProof};
+use alloc::collections::BTreeMap;
+
+/// Nodes that are known to be a part of the tree, keyed by (layer index, node index)
+type VerifiedNodes<H> = BTreeMap<(usize, usize), H>;
+
+struct BatchEntry<'a, T: Hasher> {
+    proof: &'a MerkleProof<T>,
+    leaf_indices: &'a [usize],
+    leaf_hashes: &'a [T::Hash],
+}
+
+/// [`BatchVerifier`] verifies many Merkle proofs against the same root at once.
+///
+/// Every node computed while verifying an entry is remembered once the entry is verified.
+/// When a later entry reaches a node that is already known, its verification stops there
+/// instead of hashing all the way up to the root, so paths shared by many proofs are hashed
+/// only once. Unlike [`MerkleProof::verify`], the result tells which entries have failed.
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{MerkleTree, algorithms::Sha256, Hasher, BatchVerifier};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f"]
+///     .iter()
+///     .map(|x| Sha256::hash(x.as_bytes()))
+///     .collect();
+///
+/// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+/// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+/// let indices: Vec<usize> = (0..leaves.len()).collect();
+/// let proofs: Vec<_> = indices.iter().map(|i| merkle_tree.proof(&[*i])).collect();
+///
+/// let mut batch = BatchVerifier::<Sha256>::new(root, leaves.len());
+/// for (i, proof) in proofs.iter().enumerate() {
+///     batch.add(proof, &indices[i..i + 1], &leaves[i..i + 1]);
+/// }
+/// // A proof for the leaf "a" doesn't prove "b"
+/// batch.add(&proofs[0], &indices[0..1], &leaves[1..2]);
+///
+/// assert_eq!(batch.verify(), Err(vec![6]));
+/// # Ok(())
+/// # }
+/// ```
+pub struct BatchVerifier<'a, T: Hasher> {
+    root: T::Hash,
+    total_leaves_count: usize,
+    entries: Vec<BatchEntry<'a, T>>,
+}
+
+impl<'a, T: Hasher> BatchVerifier<'a, T> {
+    /// Creates an empty batch for proofs of a tree with the given root and leaves count
+    pub fn new(root: T::Hash, total_leaves_count: usize) -> Self {
+        Self {
+            root,
+            total_leaves_count,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Adds a proof to the batch. Arguments are the same as the ones
+    /// [`MerkleProof::verify`] takes.
+    pub fn add(
+        &mut self,
+        proof: &'a MerkleProof<T>,
+        leaf_indices: &'a [usize],
+        leaf_hashes: &'a [T::Hash],
+    ) -> &mut Self {
+        self.entries.push(BatchEntry {
+            proof,
+            leaf_indices,
+            leaf_hashes,
+        });
+        self
+    }
+
+    /// Verifies all entries of the batch. If some entries have failed, returns their positions
+    /// in the order they were added to the batch. An entry with empty, duplicate or out of
+    /// range leaf indices fails the same way as an entry with a wrong proof.
+    pub fn verify(&self) -> Result<(), Vec<usize>> {
+        let failed_entries = self.failed_entries(&self.entries, 0);
+
+        if failed_entries.is_empty() {
+            return Ok(());
+        }
+        Err(failed_entries)
+    }
+
+    /// Same as [`BatchVerifier::verify`], but splits entries between the threads of the
+    /// [`rayon`] thread pool. Each thread keeps its own set of verified nodes.
+    #[cfg(feature = "rayon")]
+    pub fn par_verify(&self) -> Result<(), Vec<usize>>
+    where
+        T: Sync,
+        T::Hash: Send + Sync,
+    {
+        use rayon::prelude::*;
+
+        let chunk_size = utils::indices::div_ceil(self.entries.len(), rayon::current_num_threads());
+        if chunk_size == 0 {
+            return Ok(());
+        }
+
+        let failed_entries: Vec<usize> = self
+            .entries
+            .par_chunks(chunk_size)
+            .enumerate()
+            .flat_map_iter(|(chunk_index, chunk)| {
+                self.failed_entries(chunk, chunk_index * chunk_size)
+            })
+            .collect();
+
+        if failed_entries.is_empty() {
+            return Ok(());
+        }
+        Err(failed_entries)
+    }
+
+    /// Verifies entries sharing one set of verified nodes and returns positions of the failed
+    /// ones, shifted by `offset`
+    fn failed_entries(&self, entries: &[BatchEntry<'a, T>], offset: usize) -> Vec<usize> {
+        let depth = utils::indices::tree_depth(self.total_leaves_count);
+        let mut verified_nodes = VerifiedNodes::new();
+        verified_nodes.insert((depth, 0), self.root);
+
+        entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| !self.verify_entry(entry, &mut verified_nodes))
+            .map(|(position, _)| offset + position)
+            .collect()
+    }
+
+    /// Climbs up the tree from the entry leaves until all nodes of a layer are already
+    /// known. The root is always known, so an entry is verified at the latest when the root
+    /// is reached. The same as in [`PartialTree`], nodes of a layer are hashed in pairs.
+    ///
+    /// [`PartialTree`]: crate::PartialTree
+    fn verify_entry(
+        &self,
+        entry: &BatchEntry<'a, T>,
+        verified_nodes: &mut VerifiedNodes<T::Hash>,
+    ) -> bool {
+        // Empty, duplicate and out of range indices would let an entry pass without proving
+        // every leaf it claims, so such entries fail before touching the verified nodes
+        if utils::indices::sorted_leaf_indices(entry.leaf_indices, self.total_leaves_count).is_err()
+        {
+            return false;
+        }
+
+        let layers = match entry.proof.proof_layers(
+            entry.leaf_indices,
+            entry.leaf_hashes,
+            self.total_leaves_count,
+        ) {
+            Ok((layers, _unused_hashes_count)) => layers,
+            Err(_) => return false,
+        };
+
+        let depth = utils::indices::tree_depth(self.total_leaves_count);
+        let mut reversed_layers: Vec<Vec<(usize, T::Hash)>> = layers.into_iter().rev().collect();
+        let mut current_layer: Vec<(usize, T::Hash)> = Vec::new();
+        let mut computed_nodes: Vec<((usize, usize), T::Hash)> = Vec::new();
+
+        for layer_index in 0..=depth {
+            if let Some(mut nodes) = reversed_layers.pop() {
+                current_layer.append(&mut nodes);
+            }
+            current_layer.sort_by_key(|(index, _)| *index);
+
+            let mut all_nodes_verified = true;
+            for (index, hash) in &current_layer {
+                match verified_nodes.get(&(layer_index, *index)) {
+                    Some(verified_hash) if verified_hash == hash => {}
+                    // The node is a part of the tree, but the entry has a different hash there
+                    Some(_) => return false,
+                    None => all_nodes_verified = false,
+                }
+            }
+
+            if all_nodes_verified {
+                verified_nodes.extend(computed_nodes);
+                return true;
+            }
+
+            computed_nodes.extend(
+                current_layer
+                    .iter()
+                    .map(|(index, hash)| ((layer_index, *index), *hash)),
+            );
+
+            let (indices, nodes): (Vec<usize>, Vec<T::Hash>) = current_layer.drain(..).unzip();
+            let parent_layer_indices = utils::indices::parent_indices(&indices);
+
+            for (i, parent_node_index) in parent_layer_indices.iter().enumerate() {
+                match nodes.get(i * 2) {
+                    Some(left_node) => current_layer.push((
+                        *parent_node_index,
+                        T::concat_and_hash(left_node, nodes.get(i * 2 + 1)),
+                    )),
+                    None => return false,
+                }
+            }
+        }
+
+        false
+    }
+}
@@ -0,0 +1,222 @@
+//! `rs-merkle` builds Merkle trees from files of leaves, issues proofs and verifies them.
+//!
+//! ```text
+//! rs-merkle root   --leaves FILE [--algorithm ALG] [--raw]
+//! rs-merkle proof  --leaves FILE --indices 3,4 [--algorithm ALG] [--format FORMAT] [--raw]
+//! rs-merkle verify --root HEX --proof FILE --indices 3,4 --leaf VALUE... --total N
+//!                  [--algorithm ALG] [--format FORMAT] [--raw]
+//! ```
+//!
+//! Every line of the leaves file is a hex encoded leaf hash, or, with `--raw`, a value that is
+//! hashed with the chosen algorithm. Values passed with `--leaf` are read the same way. Proofs
+//! are printed and read as hex. `ALG` is one of `sha256` (default), `sha384` and `keccak256`.
+//! `FORMAT` is one of `direct` (default), `reverse` and `compact`; compact proofs carry leaf
+//! indices and the tree size, so `verify` doesn't need `--indices` and `--total` for them.
+//!
+//! `verify` exits with 0 if the proof is valid, 1 if it isn't and 2 on errors.
+
+use rs_merkle::{
+    algorithms::{Keccak256, Sha256, Sha384},
+    proof_serializers::{CompactPositions, CompactProof, DirectHashesOrder, ReverseHashesOrder},
+    utils, Hasher, MerkleProof, MerkleTree,
+};
+use std::{convert::TryFrom, env, fs, process};
+
+const USAGE: &str = "usage:
+    rs-merkle root   --leaves FILE [--algorithm ALG] [--raw]
+    rs-merkle proof  --leaves FILE --indices 3,4 [--algorithm ALG] [--format FORMAT] [--raw]
+    rs-merkle verify --root HEX --proof FILE --indices 3,4 --leaf VALUE... --total N
+                     [--algorithm ALG] [--format FORMAT] [--raw]
+
+ALG: sha256 (default), sha384, keccak256
+FORMAT: direct (default), reverse, compact";
+
+#[derive(Default)]
+struct Options {
+    command: String,
+    algorithm: Option<String>,
+    format: Option<String>,
+    raw: bool,
+    leaves_file: Option<String>,
+    proof_file: Option<String>,
+    root: Option<String>,
+    indices: Option<Vec<usize>>,
+    leaves: Vec<String>,
+    total: Option<usize>,
+}
+
+enum Format {
+    Direct,
+    Reverse,
+    Compact,
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = parse_options(&args).and_then(|options| {
+        match options.algorithm.as_deref().unwrap_or("sha256") {
+            "sha256" => run::<Sha256>(&options),
+            "sha384" => run::<Sha384>(&options),
+            "keccak256" => run::<Keccak256>(&options),
+            algorithm => Err(format!("unknown algorithm: {}", algorithm)),
+        }
+    });
+
+    match result {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        }
+    }
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        command: args.first().cloned().ok_or("no command given")?,
+        ..Options::default()
+    };
+
+    let mut args = args.iter().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--raw" {
+            options.raw = true;
+            continue;
+        }
+
+        let value = args
+            .next()
+            .ok_or_else(|| format!("{} needs a value", arg))?
+            .clone();
+        match arg.as_str() {
+            "--algorithm" => options.algorithm = Some(value),
+            "--format" => options.format = Some(value),
+            "--leaves" => options.leaves_file = Some(value),
+            "--proof" => options.proof_file = Some(value),
+            "--root" => options.root = Some(value),
+            "--leaf" => options.leaves.push(value),
+            "--indices" => {
+                let indices = value
+                    .split(',')
+                    .map(|index| index.trim().parse::<usize>())
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| format!("invalid indices: {}", value))?;
+                options.indices = Some(indices);
+            }
+            "--total" => {
+                let total = value
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid leaves count: {}", value))?;
+                options.total = Some(total);
+            }
+            _ => return Err(format!("unknown option: {}", arg)),
+        }
+    }
+
+    Ok(options)
+}
+
+/// Runs the command and returns whether it has succeeded. Only `verify` can fail without an
+/// error, when the proof is invalid.
+fn run<T: Hasher>(options: &Options) -> Result<bool, String> {
+    let format = match options.format.as_deref().unwrap_or("direct") {
+        "direct" => Format::Direct,
+        "reverse" => Format::Reverse,
+        "compact" => Format::Compact,
+        format => return Err(format!("unknown format: {}", format)),
+    };
+
+    match options.command.as_str() {
+        "root" => {
+            let merkle_tree = MerkleTree::<T>::from_leaves(&read_leaves::<T>(options)?);
+            let root = merkle_tree.root_hex().ok_or("the leaves file is empty")?;
+            println!("{}", root);
+            Ok(true)
+        }
+        "proof" => {
+            let leaves = read_leaves::<T>(options)?;
+            let indices = options.indices.as_ref().ok_or("--indices is required")?;
+            let merkle_tree = MerkleTree::<T>::from_leaves(&leaves);
+            let proof = merkle_tree.try_proof(indices).map_err(|e| e.to_string())?;
+
+            let proof_bytes = match format {
+                Format::Direct => proof.serialize::<DirectHashesOrder>(),
+                Format::Reverse => proof.serialize::<ReverseHashesOrder>(),
+                Format::Compact => {
+                    let proof = CompactProof::new(proof, indices, leaves.len())
+                        .map_err(|e| e.to_string())?;
+                    CompactPositions::serialize(&proof)
+                }
+            };
+            println!("{}", utils::collections::to_hex_string(&proof_bytes));
+            Ok(true)
+        }
+        "verify" => verify::<T>(options, format),
+        command => Err(format!("unknown command: {}", command)),
+    }
+}
+
+fn verify<T: Hasher>(options: &Options, format: Format) -> Result<bool, String> {
+    let root = parse_hash::<T>(options.root.as_ref().ok_or("--root is required")?)?;
+    let proof_file = options.proof_file.as_ref().ok_or("--proof is required")?;
+    let proof_hex =
+        fs::read_to_string(proof_file).map_err(|e| format!("can't read {}: {}", proof_file, e))?;
+    let proof_bytes =
+        utils::collections::from_hex_string(proof_hex.trim()).map_err(|e| e.to_string())?;
+    let leaf_hashes = options
+        .leaves
+        .iter()
+        .map(|leaf| leaf_hash::<T>(leaf, options.raw))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let is_valid = match (format, &options.indices, options.total) {
+        (Format::Compact, None, None) => CompactPositions::deserialize::<T>(&proof_bytes)
+            .map_err(|e| e.to_string())?
+            .verify(root, &leaf_hashes),
+        (format, Some(indices), Some(total)) => {
+            let proof = match format {
+                Format::Direct => MerkleProof::<T>::deserialize::<DirectHashesOrder>(&proof_bytes),
+                Format::Reverse => {
+                    MerkleProof::<T>::deserialize::<ReverseHashesOrder>(&proof_bytes)
+                }
+                Format::Compact => {
+                    CompactPositions::deserialize::<T>(&proof_bytes).map(CompactProof::into_proof)
+                }
+            }
+            .map_err(|e| e.to_string())?;
+            proof.verify(root, indices, &leaf_hashes, total)
+        }
+        _ => return Err("--indices and --total are required".into()),
+    };
+
+    println!("{}", if is_valid { "valid" } else { "invalid" });
+    Ok(is_valid)
+}
+
+fn read_leaves<T: Hasher>(options: &Options) -> Result<Vec<T::Hash>, String> {
+    let leaves_file = options.leaves_file.as_ref().ok_or("--leaves is required")?;
+    let contents = fs::read_to_string(leaves_file)
+        .map_err(|e| format!("can't read {}: {}", leaves_file, e))?;
+
+    contents
+        .lines()
+        .filter(|line| options.raw || !line.trim().is_empty())
+        .map(|line| leaf_hash::<T>(line, options.raw))
+        .collect()
+}
+
+fn leaf_hash<T: Hasher>(value: &str, raw: bool) -> Result<T::Hash, String> {
+    if raw {
+        return Ok(T::hash(value.as_bytes()));
+    }
+    parse_hash::<T>(value)
+}
+
+fn parse_hash<T: Hasher>(hex: &str) -> Result<T::Hash, String> {
+    let bytes = utils::collections::from_hex_string(hex.trim()).map_err(|e| e.to_string())?;
+    if bytes.len() != T::hash_size() {
+        return Err(format!("{} is not a {} byte hash", hex, T::hash_size()));
+    }
+    T::Hash::try_from(bytes).map_err(|_| format!("{} is not a valid hash", hex))
+}
@@ -0,0 +1,383 @@
+//! Merkleization of files, byte streams and directories.
+//!
+//! [`ChunkedTree`] splits data into chunks and builds a [`MerkleTree`] over chunk hashes, so
+//! a downloader that knows the root can verify every chunk as soon as it arrives, using the
+//! chunk proof and [`ChunkedTree::verify_chunk`].
+//!
+//! [`DirectoryTree`] builds a tree with a leaf per file of a directory. Files are ordered by
+//! their paths relative to the directory, with `/` as a separator, so the same directory gives
+//! the same root on every platform. A file leaf commits to the file path and to the root of
+//! the file's [`ChunkedTree`], see [`DirectoryTree::file_leaf`]. Symbolic links aren't
+//! followed, a link leaf commits to the link path and to the path the link points to, see
+//! [`DirectoryTree::symlink_leaf`].
+//!
+//! ## Examples
+//!
+//! ```
+//! # use rs_merkle::{algorithms::Sha256, chunking::{Chunking, ChunkedTree}};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let data = vec![7u8; 10_000];
+//! let chunked_tree = ChunkedTree::<Sha256>::from_reader(data.as_slice(), Chunking::Fixed(4096))?;
+//! let root = chunked_tree.root().ok_or("couldn't get the merkle root")?;
+//! assert_eq!(chunked_tree.chunks_count(), 3);
+//!
+//! // The downloader receives the root from a trusted source, and chunks with their proofs
+//! // from anywhere
+//! let proof = chunked_tree.chunk_proof(2)?;
+//! assert!(ChunkedTree::<Sha256>::verify_chunk(root, &proof, 2, &data[8192..], 3));
+//! assert!(!ChunkedTree::<Sha256>::verify_chunk(root, &proof, 2, &data[8191..], 3));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`MerkleTree`]: crate::MerkleTree
+
+use crate::{prelude::*, Error, Hasher, MerkleProof, MerkleTree};
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+/// Defines how data is split into chunks
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Chunking {
+    /// Every chunk but the last one has the given size in bytes
+    Fixed(usize),
+    /// Chunk boundaries are defined by the content, using a gear rolling hash, so inserting
+    /// or removing bytes only changes the chunks around the change. Chunks are at least
+    /// `min_size` and at most `max_size` bytes long, `average_size` must be a power of two.
+    ContentDefined {
+        min_size: usize,
+        average_size: usize,
+        max_size: usize,
+    },
+}
+
+impl Chunking {
+    fn validate(&self) -> Result<(), Error> {
+        match *self {
+            Chunking::Fixed(0) => Err(Error::invalid_chunking("chunk size must not be zero")),
+            Chunking::Fixed(_) => Ok(()),
+            Chunking::ContentDefined {
+                min_size,
+                average_size,
+                max_size,
+            } => {
+                if min_size == 0 || min_size > average_size || average_size > max_size {
+                    return Err(Error::invalid_chunking(
+                        "chunk sizes must be 0 < min_size <= average_size <= max_size",
+                    ));
+                }
+                if !average_size.is_power_of_two() {
+                    return Err(Error::invalid_chunking(
+                        "average chunk size must be a power of two",
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Maximum length of a chunk, i.e. how much data has to be buffered to find its end
+    fn max_chunk_len(&self) -> usize {
+        match *self {
+            Chunking::Fixed(size) => size,
+            Chunking::ContentDefined { max_size, .. } => max_size,
+        }
+    }
+
+    /// Returns the length of the first chunk of `data`. `data` must hold at least
+    /// [`Chunking::max_chunk_len`] bytes, unless it's the end of the input.
+    fn chunk_len(&self, data: &[u8], gear: &[u64; 256]) -> usize {
+        match *self {
+            Chunking::Fixed(size) => size.min(data.len()),
+            Chunking::ContentDefined {
+                min_size,
+                average_size,
+                max_size,
+            } => {
+                let mask = (average_size - 1) as u64;
+                let mut hash: u64 = 0;
+
+                for (position, byte) in data.iter().enumerate().take(max_size) {
+                    hash = (hash << 1).wrapping_add(gear[*byte as usize]);
+                    if position + 1 >= min_size && hash & mask == 0 {
+                        return position + 1;
+                    }
+                }
+                data.len().min(max_size)
+            }
+        }
+    }
+
+    /// Splits data into chunks
+    pub fn split<'a>(&self, data: &'a [u8]) -> Result<Vec<&'a [u8]>, Error> {
+        self.validate()?;
+        let gear = gear_table();
+        let mut chunks = Vec::new();
+        let mut rest = data;
+
+        while !rest.is_empty() {
+            let (chunk, tail) = rest.split_at(self.chunk_len(rest, &gear));
+            chunks.push(chunk);
+            rest = tail;
+        }
+        Ok(chunks)
+    }
+}
+
+/// Returns the table of random values for the gear rolling hash. The table is generated with
+/// SplitMix64 from a fixed seed, so chunk boundaries never change between versions.
+fn gear_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut state: u64 = 0x5241_4e44_4f4d_4745;
+
+    for value in table.iter_mut() {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        *value = z ^ (z >> 31);
+    }
+    table
+}
+
+/// [`MerkleTree`] over the hashes of data chunks, see the [module documentation](self)
+pub struct ChunkedTree<T: Hasher> {
+    merkle_tree: MerkleTree<T>,
+    chunk_lengths: Vec<usize>,
+}
+
+impl<T: Hasher> ChunkedTree<T> {
+    /// Reads the data to the end and builds a tree over its chunks. Empty data gives a tree
+    /// without leaves.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidChunking`] if the chunking parameters are
+    /// invalid, and [`ErrorKind::IoError`] if reading fails
+    ///
+    /// [`ErrorKind::InvalidChunking`]: crate::ErrorKind::InvalidChunking
+    /// [`ErrorKind::IoError`]: crate::ErrorKind::IoError
+    pub fn from_reader<R: Read>(mut reader: R, chunking: Chunking) -> Result<Self, Error> {
+        chunking.validate()?;
+        let gear = gear_table();
+        let max_chunk_len = chunking.max_chunk_len();
+        let mut buffer: Vec<u8> = Vec::with_capacity(max_chunk_len);
+        let mut chunk_hashes = Vec::new();
+        let mut chunk_lengths = Vec::new();
+        let mut is_end = false;
+
+        loop {
+            while !is_end && buffer.len() < max_chunk_len {
+                let mut limited = (&mut reader).take((max_chunk_len - buffer.len()) as u64);
+                match limited.read_to_end(&mut buffer) {
+                    Ok(0) => is_end = true,
+                    Ok(_) => {}
+                    Err(error) => return Err(Error::io_error(&error.to_string())),
+                }
+            }
+            if buffer.is_empty() {
+                break;
+            }
+
+            let chunk_len = chunking.chunk_len(&buffer, &gear);
+            chunk_hashes.push(T::hash(&buffer[..chunk_len]));
+            chunk_lengths.push(chunk_len);
+            buffer.drain(..chunk_len);
+        }
+
+        Ok(Self {
+            merkle_tree: MerkleTree::from_leaves(&chunk_hashes),
+            chunk_lengths,
+        })
+    }
+
+    /// Builds a tree over the chunks of a file
+    pub fn from_file<P: AsRef<Path>>(path: P, chunking: Chunking) -> Result<Self, Error> {
+        let file = fs::File::open(path).map_err(|error| Error::io_error(&error.to_string()))?;
+        Self::from_reader(io::BufReader::new(file), chunking)
+    }
+
+    /// Returns the root of the tree, or `None` if the data was empty
+    pub fn root(&self) -> Option<T::Hash> {
+        self.merkle_tree.root()
+    }
+
+    /// Returns the number of chunks
+    pub fn chunks_count(&self) -> usize {
+        self.chunk_lengths.len()
+    }
+
+    /// Returns the lengths of the chunks in bytes
+    pub fn chunk_lengths(&self) -> &[usize] {
+        &self.chunk_lengths
+    }
+
+    /// Returns the underlying tree. Its leaves are chunk hashes.
+    pub fn merkle_tree(&self) -> &MerkleTree<T> {
+        &self.merkle_tree
+    }
+
+    /// Returns a proof for the chunk at the given index
+    ///
+    /// ## Errors
+    ///
+    /// The same as [`MerkleTree::try_proof`]
+    pub fn chunk_proof(&self, chunk_index: usize) -> Result<MerkleProof<T>, Error> {
+        self.merkle_tree.try_proof(&[chunk_index])
+    }
+
+    /// Verifies that the chunk is a part of the data with the given root
+    pub fn verify_chunk(
+        root: T::Hash,
+        proof: &MerkleProof<T>,
+        chunk_index: usize,
+        chunk: &[u8],
+        chunks_count: usize,
+    ) -> bool {
+        proof.verify(root, &[chunk_index], &[T::hash(chunk)], chunks_count)
+    }
+}
+
+/// What a leaf of a [`DirectoryTree`] is made from
+enum DirectoryEntry {
+    File(PathBuf),
+    /// Target of the link, as it is stored in the link
+    Symlink(String),
+}
+
+/// [`MerkleTree`] with a leaf per file of a directory, see the [module documentation](self)
+pub struct DirectoryTree<T: Hasher> {
+    merkle_tree: MerkleTree<T>,
+    paths: Vec<String>,
+}
+
+impl<T: Hasher> DirectoryTree<T> {
+    /// Walks the directory recursively and builds a tree over its files. Symbolic links aren't
+    /// followed, so links that form a loop can't make the walk endless. Instead, every link
+    /// gets a leaf of its own, see [`DirectoryTree::symlink_leaf`]. Empty directories don't
+    /// produce leaves.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::IoError`] if the directory can't be read or if a
+    /// path or a link target isn't valid UTF-8, and [`ErrorKind::InvalidChunking`] if the chunking parameters
+    /// are invalid
+    ///
+    /// [`ErrorKind::InvalidChunking`]: crate::ErrorKind::InvalidChunking
+    /// [`ErrorKind::IoError`]: crate::ErrorKind::IoError
+    pub fn from_path<P: AsRef<Path>>(path: P, chunking: Chunking) -> Result<Self, Error> {
+        chunking.validate()?;
+        let mut files = Vec::new();
+        Self::collect_files(path.as_ref(), "", &mut files)?;
+        files.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut leaves = Vec::with_capacity(files.len());
+        for (relative_path, entry) in &files {
+            let leaf = match entry {
+                DirectoryEntry::File(full_path) => {
+                    let chunked_tree = ChunkedTree::from_file(full_path, chunking)?;
+                    Self::file_leaf(relative_path, Self::content_root(&chunked_tree))
+                }
+                DirectoryEntry::Symlink(target) => Self::symlink_leaf(relative_path, target),
+            };
+            leaves.push(leaf);
+        }
+
+        Ok(Self {
+            merkle_tree: MerkleTree::from_leaves(&leaves),
+            paths: files.into_iter().map(|(path, _)| path).collect(),
+        })
+    }
+
+    fn collect_files(
+        directory: &Path,
+        prefix: &str,
+        files: &mut Vec<(String, DirectoryEntry)>,
+    ) -> Result<(), Error> {
+        let to_error = |error: io::Error| Error::io_error(&error.to_string());
+
+        for entry in fs::read_dir(directory).map_err(to_error)? {
+            let entry = entry.map_err(to_error)?;
+            let name = entry
+                .file_name()
+                .into_string()
+                .map_err(|name| Error::io_error(&format!("{:?} is not valid UTF-8", name)))?;
+            let relative_path = format!("{}{}", prefix, name);
+            let full_path = entry.path();
+
+            let file_type = fs::symlink_metadata(&full_path)
+                .map_err(to_error)?
+                .file_type();
+            if file_type.is_symlink() {
+                let target = fs::read_link(&full_path)
+                    .map_err(to_error)?
+                    .into_os_string()
+                    .into_string()
+                    .map_err(|target| {
+                        Error::io_error(&format!("{:?} is not valid UTF-8", target))
+                    })?;
+                files.push((relative_path, DirectoryEntry::Symlink(target)));
+            } else if file_type.is_dir() {
+                Self::collect_files(&full_path, &format!("{}/", relative_path), files)?;
+            } else {
+                files.push((relative_path, DirectoryEntry::File(full_path)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the root of the file's chunk tree, or the hash of empty data for an empty file
+    pub fn content_root(chunked_tree: &ChunkedTree<T>) -> T::Hash {
+        chunked_tree.root().unwrap_or_else(|| T::hash(&[]))
+    }
+
+    /// Returns the leaf of a file: the hash of the path length as a little-endian `u64`, the
+    /// path bytes and the content root
+    pub fn file_leaf(relative_path: &str, content_root: T::Hash) -> T::Hash {
+        let mut data = (relative_path.len() as u64).to_le_bytes().to_vec();
+        data.extend_from_slice(relative_path.as_bytes());
+        data.append(&mut content_root.into());
+        T::hash(&data)
+    }
+
+    /// Returns the leaf of a symbolic link: the hash of the path length as a little-endian
+    /// `u64` with the highest bit set, the path bytes, the target length as a little-endian
+    /// `u64` and the target bytes. The highest bit tells a link from a file with the same path.
+    pub fn symlink_leaf(relative_path: &str, target: &str) -> T::Hash {
+        let mut data = (relative_path.len() as u64 | 1 << 63)
+            .to_le_bytes()
+            .to_vec();
+        data.extend_from_slice(relative_path.as_bytes());
+        data.extend_from_slice(&(target.len() as u64).to_le_bytes());
+        data.extend_from_slice(target.as_bytes());
+        T::hash(&data)
+    }
+
+    /// Returns the root of the tree, or `None` if the directory has no files
+    pub fn root(&self) -> Option<T::Hash> {
+        self.merkle_tree.root()
+    }
+
+    /// Returns relative paths of the files and links in the order of the tree leaves
+    pub fn paths(&self) -> &[String] {
+        &self.paths
+    }
+
+    /// Returns the underlying tree. Its leaves are file leaves.
+    pub fn merkle_tree(&self) -> &MerkleTree<T> {
+        &self.merkle_tree
+    }
+
+    /// Returns the index of the file or link leaf and a proof for it, or `None` if there's no
+    /// such file
+    pub fn file_proof(&self, relative_path: &str) -> Option<(usize, MerkleProof<T>)> {
+        let index = self
+            .paths
+            .binary_search_by(|path| path.as_str().cmp(relative_path))
+            .ok()?;
+        Some((index, self.merkle_tree.proof(&[index])))
+    }
+}
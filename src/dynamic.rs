@@ -0,0 +1,229 @@
+//! Trees and proofs with the hashing algorithm chosen at runtime, for bindings to other
+//! languages. Hashes are passed as bytes, several hashes are concatenated, proofs are
+//! serialized with [`DirectHashesOrder`](crate::proof_serializers::DirectHashesOrder).
+
+use crate::{
+    algorithms::{Keccak256, Sha256, Sha384},
+    prelude::*,
+    Error, Hasher, MerkleProof, MerkleTree,
+};
+use core::convert::TryFrom;
+
+/// Built-in hashing algorithm
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Algorithm {
+    Sha256,
+    Sha384,
+    Keccak256,
+}
+
+/// Calls a generic function with the hasher of the algorithm
+macro_rules! with_hasher {
+    ($algorithm:expr, $function:ident($($argument:expr),*)) => {
+        match $algorithm {
+            Algorithm::Sha256 => $function::<Sha256>($($argument),*),
+            Algorithm::Sha384 => $function::<Sha384>($($argument),*),
+            Algorithm::Keccak256 => $function::<Keccak256>($($argument),*),
+        }
+    };
+}
+
+/// Evaluates the same expression with the tree, whatever its hasher is
+macro_rules! with_tree {
+    ($tree:expr, $name:ident => $body:expr) => {
+        match $tree {
+            AnyTree::Sha256($name) => $body,
+            AnyTree::Sha384($name) => $body,
+            AnyTree::Keccak256($name) => $body,
+        }
+    };
+}
+
+impl Algorithm {
+    /// Parses the name the wasm and Python bindings take, e.g. `sha256`
+    #[cfg(any(feature = "wasm", feature = "python"))]
+    pub(crate) fn from_name(name: &str) -> Result<Self, Error> {
+        match name {
+            "sha256" => Ok(Algorithm::Sha256),
+            "sha384" => Ok(Algorithm::Sha384),
+            "keccak256" => Ok(Algorithm::Keccak256),
+            name => Err(Error::unknown_algorithm(name)),
+        }
+    }
+
+    pub(crate) fn hash_size(self) -> usize {
+        with_hasher!(self, hash_size())
+    }
+
+    pub(crate) fn hash(self, data: &[u8]) -> Vec<u8> {
+        with_hasher!(self, hash_with(data))
+    }
+
+    // Only the Python bindings expose hashing of node pairs and proof internals
+    #[cfg(feature = "python")]
+    pub(crate) fn concat_and_hash(
+        self,
+        left: &[u8],
+        right: Option<&[u8]>,
+    ) -> Result<Vec<u8>, Error> {
+        with_hasher!(self, concat_and_hash_with(left, right))
+    }
+
+    /// Splits a serialized proof into its hashes
+    #[cfg(feature = "python")]
+    pub(crate) fn proof_hashes(self, proof: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
+        with_hasher!(self, proof_hashes_with(proof))
+    }
+
+    #[cfg(feature = "python")]
+    pub(crate) fn proof_root(
+        self,
+        proof: &[u8],
+        leaf_indices: &[usize],
+        leaves: &[u8],
+        total_leaves_count: usize,
+    ) -> Result<Vec<u8>, Error> {
+        with_hasher!(
+            self,
+            proof_root_with(proof, leaf_indices, leaves, total_leaves_count)
+        )
+    }
+
+    pub(crate) fn verify(
+        self,
+        proof: &[u8],
+        root: &[u8],
+        leaf_indices: &[usize],
+        leaves: &[u8],
+        total_leaves_count: usize,
+    ) -> Result<bool, Error> {
+        with_hasher!(
+            self,
+            verify_with(proof, root, leaf_indices, leaves, total_leaves_count)
+        )
+    }
+}
+
+/// [`MerkleTree`] with one of the built-in hashers
+pub(crate) enum AnyTree {
+    Sha256(MerkleTree<Sha256>),
+    Sha384(MerkleTree<Sha384>),
+    Keccak256(MerkleTree<Keccak256>),
+}
+
+impl AnyTree {
+    pub(crate) fn new(algorithm: Algorithm) -> Self {
+        match algorithm {
+            Algorithm::Sha256 => AnyTree::Sha256(MerkleTree::new()),
+            Algorithm::Sha384 => AnyTree::Sha384(MerkleTree::new()),
+            Algorithm::Keccak256 => AnyTree::Keccak256(MerkleTree::new()),
+        }
+    }
+
+    /// Appends concatenated leaf hashes without committing them
+    pub(crate) fn append(&mut self, leaves: &[u8]) -> Result<(), Error> {
+        with_tree!(self, tree => append_to(tree, leaves))
+    }
+
+    pub(crate) fn commit(&mut self) {
+        with_tree!(self, tree => tree.commit())
+    }
+
+    // Only the C API and the Python bindings expose uncommitted changes
+    #[cfg(any(feature = "ffi", feature = "python"))]
+    pub(crate) fn rollback(&mut self) {
+        with_tree!(self, tree => tree.rollback())
+    }
+
+    pub(crate) fn root(&self) -> Option<Vec<u8>> {
+        with_tree!(self, tree => tree.root().map(Into::into))
+    }
+
+    // The C API returns raw roots only
+    #[cfg(any(feature = "wasm", feature = "python"))]
+    pub(crate) fn root_hex(&self) -> Option<String> {
+        with_tree!(self, tree => tree.root_hex())
+    }
+
+    pub(crate) fn leaves_len(&self) -> usize {
+        with_tree!(self, tree => tree.leaves_len())
+    }
+
+    pub(crate) fn proof(&self, leaf_indices: &[usize]) -> Result<Vec<u8>, Error> {
+        with_tree!(self, tree => Ok(tree.try_proof(leaf_indices)?.to_bytes()))
+    }
+}
+
+fn append_to<T: Hasher>(tree: &mut MerkleTree<T>, leaves: &[u8]) -> Result<(), Error> {
+    tree.append(&mut split_hashes::<T>(leaves)?);
+    Ok(())
+}
+
+fn hash_size<T: Hasher>() -> usize {
+    T::hash_size()
+}
+
+fn hash_with<T: Hasher>(data: &[u8]) -> Vec<u8> {
+    T::hash(data).into()
+}
+
+fn verify_with<T: Hasher>(
+    proof: &[u8],
+    root: &[u8],
+    leaf_indices: &[usize],
+    leaves: &[u8],
+    total_leaves_count: usize,
+) -> Result<bool, Error> {
+    let proof = MerkleProof::<T>::from_bytes(proof)?;
+    let root = to_hash::<T>(root)?;
+    let leaf_hashes = split_hashes::<T>(leaves)?;
+
+    Ok(proof.verify(root, leaf_indices, &leaf_hashes, total_leaves_count))
+}
+
+#[cfg(feature = "python")]
+fn concat_and_hash_with<T: Hasher>(left: &[u8], right: Option<&[u8]>) -> Result<Vec<u8>, Error> {
+    let left = to_hash::<T>(left)?;
+    let right = right.map(to_hash::<T>).transpose()?;
+
+    Ok(T::concat_and_hash(&left, right.as_ref()).into())
+}
+
+#[cfg(feature = "python")]
+fn proof_hashes_with<T: Hasher>(proof: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
+    let proof = MerkleProof::<T>::from_bytes(proof)?;
+    Ok(proof
+        .proof_hashes()
+        .iter()
+        .map(|&hash| hash.into())
+        .collect())
+}
+
+#[cfg(feature = "python")]
+fn proof_root_with<T: Hasher>(
+    proof: &[u8],
+    leaf_indices: &[usize],
+    leaves: &[u8],
+    total_leaves_count: usize,
+) -> Result<Vec<u8>, Error> {
+    let proof = MerkleProof::<T>::from_bytes(proof)?;
+    let leaf_hashes = split_hashes::<T>(leaves)?;
+
+    Ok(proof
+        .root(leaf_indices, &leaf_hashes, total_leaves_count)?
+        .into())
+}
+
+fn to_hash<T: Hasher>(bytes: &[u8]) -> Result<T::Hash, Error> {
+    T::Hash::try_from(bytes.to_vec()).map_err(|_| Error::vec_to_hash_conversion_error())
+}
+
+/// Splits concatenated hashes
+fn split_hashes<T: Hasher>(bytes: &[u8]) -> Result<Vec<T::Hash>, Error> {
+    let hash_size = T::hash_size();
+    if bytes.len() % hash_size != 0 {
+        return Err(Error::wrong_proof_size(bytes.len(), hash_size));
+    }
+
+    bytes.chunks(hash_size).map(to_hash::<T>).collect()
+}
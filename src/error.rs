@@ -1,4 +1,6 @@
 use crate::prelude::*;
+#[cfg(feature = "alloc")]
+use crate::utils;
 use core::fmt::{Debug, Display, Formatter};
 
 /// A list specifying general categories of tree traversals/parsing errors.
@@ -21,6 +23,54 @@ pub enum ErrorKind {
     HashConversionError,
     NotEnoughHashesToCalculateRoot,
     LeavesIndicesCountMismatch,
+    /// A proof was requested from a tree that has no committed leaves
+    EmptyTree,
+    /// A proof was requested for an empty set of leaf indices
+    EmptyLeafIndices,
+    /// A leaf index is greater than or equal to the number of leaves in the tree
+    LeafIndexOutOfRange,
+    /// The same leaf index was passed more than once
+    DuplicateLeafIndex,
+    /// The proof contains more hashes than needed to calculate the root
+    TooManyProofHashes,
+    /// The proof is verified against a tree with zero leaves
+    ZeroLeavesCount,
+    /// The number of proofs doesn't match the number of leaf indices
+    ProofsIndicesCountMismatch,
+    /// The leaf is not one of the leaves proven by the proof
+    LeafNotInProof,
+    /// A proof update was requested for a tree that has less leaves than before
+    LeavesCountDecreased,
+    /// There's no node with the given layer and index in the tree
+    NodeOutOfRange,
+    /// Serialized proof is not consistent with its own header, for example, it has a wrong
+    /// number of hashes for the encoded leaf positions
+    MalformedProof,
+    /// Streamed proof is longer than the allowed maximum
+    ProofTooLong,
+    /// Reading or writing a streamed proof or merkleized data has failed
+    IoError,
+    /// Chunking parameters can't be used to split data into chunks
+    InvalidChunking,
+    /// Hashing algorithm passed by name is not one of the built-in algorithms
+    UnknownAlgorithm,
+    /// A leaf breaks the order of a tree that keeps its leaves sorted
+    UnsortedLeaves,
+    /// Absence of a leaf can't be proven, because the leaf is in the tree
+    LeafInTree,
+    /// A tree can't have the given number of children per node
+    InvalidArity,
+    /// A fixed-depth tree can't have the given depth, or a padding limit is too large
+    InvalidDepth,
+    /// A leaf is appended to a fixed-depth tree that has no empty positions left, or a tree
+    /// has more leaves than its padding limit
+    TreeIsFull,
+    /// The number of passed leaves doesn't match the number of leaves in the tree
+    LeavesCountMismatch,
+    /// There's no node with the given generalized index in the tree
+    InvalidGeneralizedIndex,
+    /// Merged proofs contain different hashes for the same node of the tree
+    ConflictingProofHashes,
 }
 
 /// The error type for tree traversals/parsing errors of the [`MerkleProof`] and [`PartialTree`].
@@ -29,12 +79,14 @@ pub enum ErrorKind {
 ///
 /// [`MerkleProof`]: crate::MerkleProof
 /// [`PartialTree`]: crate::PartialTree
+#[cfg(feature = "alloc")]
 #[derive(Clone, Debug)]
 pub struct Error {
     kind: ErrorKind,
     message: String,
 }
 
+#[cfg(feature = "alloc")]
 impl Error {
     pub fn new(kind: ErrorKind, message: String) -> Self {
         Self { kind, message }
@@ -81,6 +133,218 @@ impl Error {
         )
     }
 
+    pub fn empty_tree() -> Self {
+        Self::new(
+            ErrorKind::EmptyTree,
+            "can't make a proof for a tree without leaves".to_string(),
+        )
+    }
+
+    pub fn empty_leaf_indices() -> Self {
+        Self::new(
+            ErrorKind::EmptyLeafIndices,
+            "at least one leaf index is required to make a proof".to_string(),
+        )
+    }
+
+    pub fn leaf_index_out_of_range(index: usize, leaves_len: usize) -> Self {
+        Self::new(
+            ErrorKind::LeafIndexOutOfRange,
+            format!(
+                "leaf index {} is out of range for a tree of {} leaves",
+                index, leaves_len
+            ),
+        )
+    }
+
+    pub fn duplicate_leaf_index(index: usize) -> Self {
+        Self::new(
+            ErrorKind::DuplicateLeafIndex,
+            format!("leaf index {} is passed more than once", index),
+        )
+    }
+
+    pub fn too_many_proof_hashes(unused_hashes_count: usize) -> Self {
+        Self::new(
+            ErrorKind::TooManyProofHashes,
+            format!(
+                "proof contains {} hashes that are not needed to calculate the root",
+                unused_hashes_count
+            ),
+        )
+    }
+
+    pub fn zero_leaves_count() -> Self {
+        Self::new(
+            ErrorKind::ZeroLeavesCount,
+            "total leaves count of the tree must be greater than zero".to_string(),
+        )
+    }
+
+    pub fn proofs_indices_count_mismatch(proofs_len: usize, indices_len: usize) -> Self {
+        Self::new(
+            ErrorKind::ProofsIndicesCountMismatch,
+            format!(
+                "proofs count doesn't match leaf indices count: {} and {}",
+                proofs_len, indices_len
+            ),
+        )
+    }
+
+    pub fn leaf_not_in_proof(index: usize) -> Self {
+        Self::new(
+            ErrorKind::LeafNotInProof,
+            format!("leaf {} is not proven by the proof", index),
+        )
+    }
+
+    pub fn leaves_count_decreased(old_leaves_count: usize, new_leaves_count: usize) -> Self {
+        Self::new(
+            ErrorKind::LeavesCountDecreased,
+            format!(
+                "proof can only be updated after appending leaves, but leaves count went from {} to {}",
+                old_leaves_count, new_leaves_count
+            ),
+        )
+    }
+
+    pub fn node_out_of_range(layer_index: usize, node_index: usize, leaves_len: usize) -> Self {
+        Self::new(
+            ErrorKind::NodeOutOfRange,
+            format!(
+                "node {} of layer {} doesn't exist in a tree of {} leaves",
+                node_index, layer_index, leaves_len
+            ),
+        )
+    }
+
+    pub fn malformed_proof(reason: &str) -> Self {
+        Self::new(
+            ErrorKind::MalformedProof,
+            format!("serialized proof is malformed: {}", reason),
+        )
+    }
+
+    pub fn proof_too_long(max_proof_len: usize) -> Self {
+        Self::new(
+            ErrorKind::ProofTooLong,
+            format!(
+                "proof is longer than the maximum of {} bytes",
+                max_proof_len
+            ),
+        )
+    }
+
+    pub fn io_error(message: &str) -> Self {
+        Self::new(ErrorKind::IoError, format!("i/o error: {}", message))
+    }
+
+    pub fn invalid_chunking(message: &str) -> Self {
+        Self::new(ErrorKind::InvalidChunking, message.into())
+    }
+
+    pub fn unknown_algorithm(algorithm: &str) -> Self {
+        Self::new(
+            ErrorKind::UnknownAlgorithm,
+            format!("unknown hashing algorithm: {}", algorithm),
+        )
+    }
+
+    pub fn invalid_hex_string(hex: &str) -> Self {
+        Self::new(
+            ErrorKind::HashConversionError,
+            format!("{} is not a valid hex string", hex),
+        )
+    }
+
+    pub fn unsorted_leaf(position: usize) -> Self {
+        Self::new(
+            ErrorKind::UnsortedLeaves,
+            format!(
+                "leaf at position {} is not greater than the previous leaf",
+                position
+            ),
+        )
+    }
+
+    pub fn leaf_in_tree(index: usize) -> Self {
+        Self::new(
+            ErrorKind::LeafInTree,
+            format!("leaf is in the tree at position {}", index),
+        )
+    }
+
+    pub fn invalid_arity(arity: usize) -> Self {
+        Self::new(
+            ErrorKind::InvalidArity,
+            format!("arity must be at least 2, got {}", arity),
+        )
+    }
+
+    pub fn invalid_depth(depth: usize) -> Self {
+        Self::new(
+            ErrorKind::InvalidDepth,
+            format!("tree of depth {} is not supported", depth),
+        )
+    }
+
+    pub fn padding_limit_too_large(limit: usize) -> Self {
+        Self::new(
+            ErrorKind::InvalidDepth,
+            format!(
+                "padding limit {} can't be rounded up to a power of two",
+                limit
+            ),
+        )
+    }
+
+    pub fn list_limit_too_large(elements_count: usize, element_size: usize) -> Self {
+        Self::new(
+            ErrorKind::InvalidDepth,
+            format!(
+                "list of {} elements of {} bytes takes more bytes than the platform can address",
+                elements_count, element_size
+            ),
+        )
+    }
+
+    pub fn tree_is_full(capacity: u64) -> Self {
+        Self::new(
+            ErrorKind::TreeIsFull,
+            format!("tree is full, it can hold only {} leaves", capacity),
+        )
+    }
+
+    pub fn leaves_count_mismatch(expected_len: u64, leaves_len: u64) -> Self {
+        Self::new(
+            ErrorKind::LeavesCountMismatch,
+            format!(
+                "tree has {} leaves, but {} leaves were passed",
+                expected_len, leaves_len
+            ),
+        )
+    }
+
+    pub fn invalid_generalized_index(index: usize) -> Self {
+        Self::new(
+            ErrorKind::InvalidGeneralizedIndex,
+            format!(
+                "there's no node with generalized index {} in the tree",
+                index
+            ),
+        )
+    }
+
+    pub fn conflicting_proof_hashes(layer_index: usize, node_index: usize) -> Self {
+        Self::new(
+            ErrorKind::ConflictingProofHashes,
+            format!(
+                "proofs contain different hashes for node {} of layer {}",
+                node_index, layer_index
+            ),
+        )
+    }
+
     pub fn kind(&self) -> ErrorKind {
         self.kind
     }
@@ -90,11 +354,102 @@ impl Error {
     }
 }
 
-#[cfg(feature = "std")]
-impl std::error::Error for Error {}
+#[cfg(feature = "alloc")]
+impl core::error::Error for Error {}
 
+#[cfg(feature = "alloc")]
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "{}", self.message)
     }
 }
+
+/// The error type returned by [`MerkleProofRef::from_bytes`] when proof bytes can't be split
+/// into hashes. Unlike [`Error`], it doesn't allocate a message, so parsing a borrowed proof
+/// doesn't need a heap.
+///
+/// [`MerkleProofRef::from_bytes`]: crate::MerkleProofRef::from_bytes
+/// [`Error`]: crate::Error
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ProofSizeError {
+    proof_len: usize,
+    hash_size: usize,
+}
+
+impl ProofSizeError {
+    pub fn new(proof_len: usize, hash_size: usize) -> Self {
+        Self {
+            proof_len,
+            hash_size,
+        }
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        ErrorKind::SerializedProofSizeIsIncorrect
+    }
+
+    pub fn proof_len(&self) -> usize {
+        self.proof_len
+    }
+
+    pub fn hash_size(&self) -> usize {
+        self.hash_size
+    }
+}
+
+impl core::error::Error for ProofSizeError {}
+
+impl Display for ProofSizeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "proof of size {} bytes can not be divided into chunks of {} bytes",
+            self.proof_len, self.hash_size,
+        )
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<ProofSizeError> for Error {
+    fn from(error: ProofSizeError) -> Self {
+        Self::wrong_proof_size(error.proof_len, error.hash_size)
+    }
+}
+
+/// The error type returned by [`MerkleProof::verify_detailed`]. Tells apart a proof that
+/// can't be traversed from a proof that is well-formed, but leads to a different root.
+///
+/// [`MerkleProof::verify_detailed`]: crate::MerkleProof::verify_detailed
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub enum VerificationError<H> {
+    /// The root was calculated, but it doesn't match the expected one
+    RootMismatch { expected: H, computed: H },
+    /// The root couldn't be calculated from the proof
+    Proof(Error),
+}
+
+#[cfg(feature = "alloc")]
+impl<H> From<Error> for VerificationError<H> {
+    fn from(error: Error) -> Self {
+        Self::Proof(error)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<H: Clone + Into<Vec<u8>> + Debug> core::error::Error for VerificationError<H> {}
+
+#[cfg(feature = "alloc")]
+impl<H: Clone + Into<Vec<u8>>> Display for VerificationError<H> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::RootMismatch { expected, computed } => write!(
+                f,
+                "root mismatch: expected {}, computed {}",
+                utils::collections::to_hex_string(expected),
+                utils::collections::to_hex_string(computed)
+            ),
+            Self::Proof(error) => write!(f, "{}", error),
+        }
+    }
+}
@@ -0,0 +1,282 @@
+//! C API for using the library from other languages.
+//!
+//! The API is declared in `include/rs_merkle.h`.
+//! `cargo rustc --release --features ffi --crate-type cdylib` builds a shared library for C,
+//! and `--crate-type staticlib` builds a static one.
+//!
+//! Hashes are passed as byte buffers, several hashes are concatenated into one buffer.
+//! Functions that fill a buffer take its capacity and fail with
+//! [`RS_MERKLE_BUFFER_TOO_SMALL`] if it's not enough. Proofs are serialized with
+//! [`DirectHashesOrder`](crate::proof_serializers::DirectHashesOrder). A pointer may be null
+//! if the length passed with it is zero.
+
+use crate::{
+    dynamic::{Algorithm, AnyTree},
+    prelude::*,
+};
+use alloc::boxed::Box;
+use core::slice;
+
+/// The call has succeeded
+pub const RS_MERKLE_OK: i32 = 0;
+/// A pointer is null, the algorithm is unknown or the data has a wrong size
+pub const RS_MERKLE_INVALID_ARGUMENT: i32 = -1;
+/// The output buffer is too small
+pub const RS_MERKLE_BUFFER_TOO_SMALL: i32 = -2;
+/// The tree has no committed leaves
+pub const RS_MERKLE_EMPTY_TREE: i32 = -3;
+/// A proof can't be made for the given leaf indices
+pub const RS_MERKLE_PROOF_ERROR: i32 = -4;
+
+/// [`Sha256`](crate::algorithms::Sha256)
+pub const RS_MERKLE_SHA256: u32 = 0;
+/// [`Sha384`](crate::algorithms::Sha384)
+pub const RS_MERKLE_SHA384: u32 = 1;
+/// [`Keccak256`](crate::algorithms::Keccak256)
+pub const RS_MERKLE_KECCAK256: u32 = 2;
+
+/// Opaque handle of a [`MerkleTree`](crate::MerkleTree)
+pub struct RsMerkleTree {
+    tree: AnyTree,
+}
+
+fn algorithm(code: u32) -> Option<Algorithm> {
+    match code {
+        RS_MERKLE_SHA256 => Some(Algorithm::Sha256),
+        RS_MERKLE_SHA384 => Some(Algorithm::Sha384),
+        RS_MERKLE_KECCAK256 => Some(Algorithm::Keccak256),
+        _ => None,
+    }
+}
+
+/// Makes a slice from a pointer and a length, allowing a null pointer for an empty slice
+unsafe fn input<'a, T>(pointer: *const T, len: usize) -> Option<&'a [T]> {
+    if len == 0 {
+        return Some(&[]);
+    }
+    if pointer.is_null() {
+        return None;
+    }
+    Some(slice::from_raw_parts(pointer, len))
+}
+
+/// Copies bytes to the output buffer
+unsafe fn output(bytes: &[u8], out: *mut u8, out_len: usize) -> i32 {
+    if bytes.len() > out_len {
+        return RS_MERKLE_BUFFER_TOO_SMALL;
+    }
+    if bytes.is_empty() {
+        return RS_MERKLE_OK;
+    }
+    if out.is_null() {
+        return RS_MERKLE_INVALID_ARGUMENT;
+    }
+    slice::from_raw_parts_mut(out, bytes.len()).copy_from_slice(bytes);
+    RS_MERKLE_OK
+}
+
+/// Returns the hash size of the algorithm in bytes, or 0 if the algorithm is unknown
+#[no_mangle]
+pub extern "C" fn rs_merkle_hash_size(algorithm_code: u32) -> usize {
+    algorithm(algorithm_code).map_or(0, Algorithm::hash_size)
+}
+
+/// Hashes `data` and writes the hash to `out`
+///
+/// # Safety
+///
+/// `data` must point to `data_len` readable bytes and `out` to `out_len` writable bytes
+#[no_mangle]
+pub unsafe extern "C" fn rs_merkle_hash(
+    algorithm_code: u32,
+    data: *const u8,
+    data_len: usize,
+    out: *mut u8,
+    out_len: usize,
+) -> i32 {
+    match (algorithm(algorithm_code), input(data, data_len)) {
+        (Some(algorithm), Some(data)) => output(&algorithm.hash(data), out, out_len),
+        _ => RS_MERKLE_INVALID_ARGUMENT,
+    }
+}
+
+/// Creates an empty tree. Returns null if the algorithm is unknown. The tree must be freed
+/// with [`rs_merkle_tree_free`].
+#[no_mangle]
+pub extern "C" fn rs_merkle_tree_new(algorithm_code: u32) -> *mut RsMerkleTree {
+    match algorithm(algorithm_code) {
+        Some(algorithm) => Box::into_raw(Box::new(RsMerkleTree {
+            tree: AnyTree::new(algorithm),
+        })),
+        None => core::ptr::null_mut(),
+    }
+}
+
+/// Frees a tree created with [`rs_merkle_tree_new`]. Does nothing if `tree` is null.
+///
+/// # Safety
+///
+/// `tree` must be null or a pointer returned by [`rs_merkle_tree_new`] that wasn't freed yet
+#[no_mangle]
+pub unsafe extern "C" fn rs_merkle_tree_free(tree: *mut RsMerkleTree) {
+    if !tree.is_null() {
+        drop(Box::from_raw(tree));
+    }
+}
+
+/// Appends concatenated leaf hashes to the tree. They are not a part of the tree until
+/// [`rs_merkle_tree_commit`] is called.
+///
+/// # Safety
+///
+/// `tree` must be a valid tree, `leaves` must point to `leaves_len` readable bytes
+#[no_mangle]
+pub unsafe extern "C" fn rs_merkle_tree_append(
+    tree: *mut RsMerkleTree,
+    leaves: *const u8,
+    leaves_len: usize,
+) -> i32 {
+    match (tree.as_mut(), input(leaves, leaves_len)) {
+        (Some(tree), Some(leaves)) => match tree.tree.append(leaves) {
+            Ok(()) => RS_MERKLE_OK,
+            Err(_) => RS_MERKLE_INVALID_ARGUMENT,
+        },
+        _ => RS_MERKLE_INVALID_ARGUMENT,
+    }
+}
+
+/// Commits appended leaves, see [`MerkleTree::commit`](crate::MerkleTree::commit)
+///
+/// # Safety
+///
+/// `tree` must be a valid tree
+#[no_mangle]
+pub unsafe extern "C" fn rs_merkle_tree_commit(tree: *mut RsMerkleTree) -> i32 {
+    match tree.as_mut() {
+        Some(tree) => {
+            tree.tree.commit();
+            RS_MERKLE_OK
+        }
+        None => RS_MERKLE_INVALID_ARGUMENT,
+    }
+}
+
+/// Rolls back the last commit, see [`MerkleTree::rollback`](crate::MerkleTree::rollback)
+///
+/// # Safety
+///
+/// `tree` must be a valid tree
+#[no_mangle]
+pub unsafe extern "C" fn rs_merkle_tree_rollback(tree: *mut RsMerkleTree) -> i32 {
+    match tree.as_mut() {
+        Some(tree) => {
+            tree.tree.rollback();
+            RS_MERKLE_OK
+        }
+        None => RS_MERKLE_INVALID_ARGUMENT,
+    }
+}
+
+/// Returns the number of committed leaves, or 0 if `tree` is null
+///
+/// # Safety
+///
+/// `tree` must be null or a valid tree
+#[no_mangle]
+pub unsafe extern "C" fn rs_merkle_tree_leaves_len(tree: *const RsMerkleTree) -> usize {
+    tree.as_ref().map_or(0, |tree| tree.tree.leaves_len())
+}
+
+/// Writes the root of the tree to `out`
+///
+/// # Safety
+///
+/// `tree` must be a valid tree, `out` must point to `out_len` writable bytes
+#[no_mangle]
+pub unsafe extern "C" fn rs_merkle_tree_root(
+    tree: *const RsMerkleTree,
+    out: *mut u8,
+    out_len: usize,
+) -> i32 {
+    match tree.as_ref() {
+        Some(tree) => match tree.tree.root() {
+            Some(root) => output(&root, out, out_len),
+            None => RS_MERKLE_EMPTY_TREE,
+        },
+        None => RS_MERKLE_INVALID_ARGUMENT,
+    }
+}
+
+/// Writes a serialized proof for the leaves at the given indices to `out`, and its length to
+/// `written`. If `out` is too small, only the length is written, so the call can be repeated
+/// with a big enough buffer.
+///
+/// # Safety
+///
+/// `tree` must be a valid tree, `leaf_indices` must point to `leaf_indices_len` indices,
+/// `out` to `out_len` writable bytes and `written` must be a valid pointer
+#[no_mangle]
+pub unsafe extern "C" fn rs_merkle_tree_proof(
+    tree: *const RsMerkleTree,
+    leaf_indices: *const usize,
+    leaf_indices_len: usize,
+    out: *mut u8,
+    out_len: usize,
+    written: *mut usize,
+) -> i32 {
+    let (tree, leaf_indices, written) = match (
+        tree.as_ref(),
+        input(leaf_indices, leaf_indices_len),
+        written.as_mut(),
+    ) {
+        (Some(tree), Some(leaf_indices), Some(written)) => (tree, leaf_indices, written),
+        _ => return RS_MERKLE_INVALID_ARGUMENT,
+    };
+
+    match tree.tree.proof(leaf_indices) {
+        Ok(proof) => {
+            *written = proof.len();
+            output(&proof, out, out_len)
+        }
+        Err(_) if tree.tree.leaves_len() == 0 => RS_MERKLE_EMPTY_TREE,
+        Err(_) => RS_MERKLE_PROOF_ERROR,
+    }
+}
+
+/// Verifies a serialized proof, see [`MerkleProof::verify`](crate::MerkleProof::verify).
+/// Returns 1 if the proof is valid, 0 if it isn't, and a negative status on errors.
+///
+/// # Safety
+///
+/// Every pointer must point to as many readable elements as the length passed with it
+#[no_mangle]
+pub unsafe extern "C" fn rs_merkle_verify(
+    algorithm_code: u32,
+    proof: *const u8,
+    proof_len: usize,
+    root: *const u8,
+    root_len: usize,
+    leaf_indices: *const usize,
+    leaf_indices_len: usize,
+    leaves: *const u8,
+    leaves_len: usize,
+    total_leaves_count: usize,
+) -> i32 {
+    let arguments = (
+        algorithm(algorithm_code),
+        input(proof, proof_len),
+        input(root, root_len),
+        input(leaf_indices, leaf_indices_len),
+        input(leaves, leaves_len),
+    );
+
+    match arguments {
+        (Some(algorithm), Some(proof), Some(root), Some(leaf_indices), Some(leaves)) => {
+            match algorithm.verify(proof, root, leaf_indices, leaves, total_leaves_count) {
+                Ok(is_valid) => is_valid as i32,
+                Err(_) => RS_MERKLE_INVALID_ARGUMENT,
+            }
+        }
+        _ => RS_MERKLE_INVALID_ARGUMENT,
+    }
+}
@@ -1,4 +1,5 @@
 use crate::prelude::*;
+#[cfg(feature = "alloc")]
 use core::convert::TryFrom;
 use core::mem;
 
@@ -26,6 +27,7 @@ use core::mem;
 ///     }
 /// }
 /// ```
+#[cfg(feature = "alloc")]
 pub trait Hasher: Clone {
     /// This type is used as a hash type in the library.
     /// It is recommended to use fixed size u8 array as a hash type. For example,
@@ -54,8 +56,15 @@ pub trait Hasher: Clone {
     /// if the left node doesn't have a sibling it is concatenated to itself and
     /// then hashed instead of just being propagated to the next level.
     ///
+    /// The default implementation concatenates the nodes into a vector. Implementations that
+    /// feed both nodes to the hasher one after another instead don't allocate, so
+    /// [`MerkleProofRef`] verifies proofs without a heap. The built-in hashers from the
+    /// [`algorithms`] module do that.
+    ///
     /// [`MerkleTree`]: crate::MerkleTree
     /// [`PartialTree`]: crate::PartialTree
+    /// [`MerkleProofRef`]: crate::MerkleProofRef
+    /// [`algorithms`]: crate::algorithms
     fn concat_and_hash(left: &Self::Hash, right: Option<&Self::Hash>) -> Self::Hash {
         let mut concatenated: Vec<u8> = (*left).into();
 
@@ -69,6 +78,28 @@ pub trait Hasher: Clone {
         }
     }
 
+    /// Used by [`KaryMerkleTree`] to calculate a node from its children, of which there may be
+    /// up to the arity of the tree. There's always at least one child. The provided default
+    /// implementation uses [`Hasher::concat_and_hash`] for one or two children and hashes the
+    /// concatenated children otherwise, so a tree of arity 2 has the same root as
+    /// [`MerkleTree`] as long as a node without a sibling is propagated as is.
+    ///
+    /// [`KaryMerkleTree`]: crate::KaryMerkleTree
+    /// [`MerkleTree`]: crate::MerkleTree
+    fn concat_and_hash_children(children: &[Self::Hash]) -> Self::Hash {
+        match children {
+            [left] => Self::concat_and_hash(left, None),
+            [left, right] => Self::concat_and_hash(left, Some(right)),
+            _ => {
+                let concatenated: Vec<u8> = children
+                    .iter()
+                    .flat_map(|child| Into::<Vec<u8>>::into(*child))
+                    .collect();
+                Self::hash(&concatenated)
+            }
+        }
+    }
+
     /// Returns the byte size of `Self::Hash`. Default implementation returns
     /// `mem::size_of::<Self::Hash>()`. Usually doesn't need to be overridden.
     /// Used internally by `MerkleProof` to parse hashes from a serialized proof.
@@ -76,3 +107,52 @@ pub trait Hasher: Clone {
         mem::size_of::<Self::Hash>()
     }
 }
+
+/// [`NodeHasher`] is the part of [`Hasher`] that [`MerkleProofRef`] needs to verify a proof.
+/// Unlike [`Hasher`], it doesn't require the hash to convert to and from a `Vec<u8>`, so it's
+/// available without the `alloc` feature.
+///
+/// Every [`Hasher`] implements it, so it only needs to be implemented by hand on targets
+/// without a heap. The built-in hashers from the [`algorithms`] module implement it either way.
+///
+/// [`Hasher`]: crate::Hasher
+/// [`MerkleProofRef`]: crate::MerkleProofRef
+/// [`algorithms`]: crate::algorithms
+pub trait NodeHasher {
+    /// This type is used as a hash type by [`MerkleProofRef`].
+    ///
+    /// [`MerkleProofRef`]: crate::MerkleProofRef
+    type Hash: Copy + PartialEq;
+
+    /// This associated function takes a slice of bytes and returns a hash of it.
+    fn hash(data: &[u8]) -> Self::Hash;
+
+    /// Calculates a parent node from the left node and an optional right node, the same way
+    /// [`Hasher::concat_and_hash`] does.
+    ///
+    /// [`Hasher::concat_and_hash`]: crate::Hasher::concat_and_hash
+    fn concat_and_hash(left: &Self::Hash, right: Option<&Self::Hash>) -> Self::Hash;
+
+    /// Returns the byte size of `Self::Hash`. Default implementation returns
+    /// `mem::size_of::<Self::Hash>()`.
+    fn hash_size() -> usize {
+        mem::size_of::<Self::Hash>()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Hasher> NodeHasher for T {
+    type Hash = T::Hash;
+
+    fn hash(data: &[u8]) -> Self::Hash {
+        <T as Hasher>::hash(data)
+    }
+
+    fn concat_and_hash(left: &Self::Hash, right: Option<&Self::Hash>) -> Self::Hash {
+        <T as Hasher>::concat_and_hash(left, right)
+    }
+
+    fn hash_size() -> usize {
+        <T as Hasher>::hash_size()
+    }
+}
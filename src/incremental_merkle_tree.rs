@@ -0,0 +1,264 @@
+use crate::prelude::*;
+use crate::{utils, Error, Hasher, MerkleProof};
+use core::convert::TryFrom;
+
+/// [`IncrementalMerkleTree`] is an append-only Merkle tree of a fixed depth, as used by the
+/// Ethereum 2.0 deposit contract and by Tornado Cash or Semaphore style contracts.
+///
+/// The tree always has `2^depth` leaves. Positions that weren't appended to yet hold a zero
+/// leaf, so the root of an empty subtree of every height is precomputed. Only the left nodes
+/// on the path to the next leaf are stored (the branch), which takes O(depth) memory, and
+/// both [`IncrementalMerkleTree::append`] and [`IncrementalMerkleTree::root`] take O(depth)
+/// hashes. Unlike [`MerkleTree`], the depth doesn't change as the tree grows, so roots of trees
+/// with a different number of leaves are comparable.
+///
+/// The number of leaves is counted in `u64`, so a tree of depth 32, like the deposit contract
+/// tree, works on 32-bit platforms as well. Depths up to 63 are supported.
+///
+/// The tree doesn't store its leaves, so [`IncrementalMerkleTree::proof`] needs all the appended
+/// leaves, kept by the caller in a store of its own, and rebuilds the tree from them in O(n)
+/// hashes. Proofs are regular [`MerkleProof`]s for a tree of
+/// [`IncrementalMerkleTree::capacity`] leaves, and are checked with
+/// [`IncrementalMerkleTree::verify`].
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{IncrementalMerkleTree, algorithms::Sha256, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let leaves: Vec<[u8; 32]> = ["a", "b", "c"]
+///     .iter()
+///     .map(|x| Sha256::hash(x.as_bytes()))
+///     .collect();
+///
+/// let mut merkle_tree = IncrementalMerkleTree::<Sha256>::new(32)?;
+/// for leaf in &leaves {
+///     merkle_tree.append(*leaf)?;
+/// }
+/// let root = merkle_tree.root();
+///
+/// let proof = merkle_tree.proof(&leaves, 1)?;
+/// assert!(IncrementalMerkleTree::verify(root, &proof, 32, 1, leaves[1]));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`MerkleTree`]: crate::MerkleTree
+#[derive(Clone)]
+pub struct IncrementalMerkleTree<T: Hasher> {
+    depth: usize,
+    branch: Vec<T::Hash>,
+    zero_hashes: Vec<T::Hash>,
+    leaves_count: u64,
+}
+
+impl<T: Hasher> IncrementalMerkleTree<T> {
+    /// Creates an empty tree of the given depth where the zero leaf is a hash of all zero
+    /// bytes, as in the deposit contract
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidDepth`] if the depth is zero or greater than
+    /// 63, and [`ErrorKind::HashConversionError`] if the hash type can't be made from zero bytes
+    ///
+    /// [`ErrorKind::InvalidDepth`]: crate::ErrorKind::InvalidDepth
+    /// [`ErrorKind::HashConversionError`]: crate::ErrorKind::HashConversionError
+    pub fn new(depth: usize) -> Result<Self, Error> {
+        let zero_leaf = T::Hash::try_from(vec![0u8; T::hash_size()])
+            .map_err(|_| Error::vec_to_hash_conversion_error())?;
+        Self::with_zero_leaf(depth, zero_leaf)
+    }
+
+    /// Creates an empty tree of the given depth where empty positions hold `zero_leaf`
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidDepth`] if the depth is zero or greater than
+    /// 63
+    ///
+    /// [`ErrorKind::InvalidDepth`]: crate::ErrorKind::InvalidDepth
+    pub fn with_zero_leaf(depth: usize, zero_leaf: T::Hash) -> Result<Self, Error> {
+        if depth == 0 || depth >= u64::BITS as usize {
+            return Err(Error::invalid_depth(depth));
+        }
+
+        let mut zero_hashes = Vec::with_capacity(depth + 1);
+        zero_hashes.push(zero_leaf);
+        for height in 0..depth {
+            let zero_hash = zero_hashes[height];
+            zero_hashes.push(T::concat_and_hash(&zero_hash, Some(&zero_hash)));
+        }
+
+        // The last node of the branch is only set when the tree is full and holds its root
+        Ok(Self {
+            depth,
+            branch: vec![zero_leaf; depth + 1],
+            zero_hashes,
+            leaves_count: 0,
+        })
+    }
+
+    /// Appends a leaf and returns its index
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::TreeIsFull`] if the tree already has
+    /// [`IncrementalMerkleTree::capacity`] leaves
+    ///
+    /// [`ErrorKind::TreeIsFull`]: crate::ErrorKind::TreeIsFull
+    pub fn append(&mut self, leaf: T::Hash) -> Result<u64, Error> {
+        let leaf_index = self.leaves_count;
+        if leaf_index >= self.capacity() {
+            return Err(Error::tree_is_full(self.capacity()));
+        }
+        self.leaves_count += 1;
+
+        let mut node = leaf;
+        let mut size = self.leaves_count;
+        for height in 0..=self.depth {
+            if size % 2 == 1 {
+                self.branch[height] = node;
+                break;
+            }
+            node = T::concat_and_hash(&self.branch[height], Some(&node));
+            size /= 2;
+        }
+
+        Ok(leaf_index)
+    }
+
+    /// Returns the root of the tree. An empty tree has the root of a tree of zero leaves.
+    pub fn root(&self) -> T::Hash {
+        if self.leaves_count == self.capacity() {
+            return self.branch[self.depth];
+        }
+
+        let mut node = self.zero_hashes[0];
+        let mut size = self.leaves_count;
+        for height in 0..self.depth {
+            node = if size % 2 == 1 {
+                T::concat_and_hash(&self.branch[height], Some(&node))
+            } else {
+                T::concat_and_hash(&node, Some(&self.zero_hashes[height]))
+            };
+            size /= 2;
+        }
+        node
+    }
+
+    /// Same as [`IncrementalMerkleTree::root`], but serialized as a hex string
+    pub fn root_hex(&self) -> String {
+        utils::collections::to_hex_string(&self.root())
+    }
+
+    /// Returns the root mixed with the number of leaves: the hash of the root followed by the
+    /// number of leaves as a little-endian `u64` padded with zeros to the hash size. This is
+    /// what the deposit contract's `get_deposit_root` returns.
+    pub fn deposit_root(&self) -> T::Hash {
+        let mut data: Vec<u8> = self.root().into();
+        let mut length = vec![0u8; T::hash_size().max(8)];
+        length[..8].copy_from_slice(&self.leaves_count.to_le_bytes());
+        data.append(&mut length);
+        T::hash(&data)
+    }
+
+    /// Returns a proof for the leaf at the given index. The tree only stores its branch, so all
+    /// the leaves that were appended to the tree have to be passed to make a proof, and the
+    /// proof takes O(n) hashes. Applications that make many proofs keep the leaves, or the
+    /// whole tree, in a store of their own.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::LeavesCountMismatch`] if the number of leaves
+    /// doesn't match the tree, and [`ErrorKind::LeafIndexOutOfRange`] if there's no leaf at
+    /// the index
+    ///
+    /// [`ErrorKind::LeavesCountMismatch`]: crate::ErrorKind::LeavesCountMismatch
+    /// [`ErrorKind::LeafIndexOutOfRange`]: crate::ErrorKind::LeafIndexOutOfRange
+    pub fn proof(&self, leaves: &[T::Hash], leaf_index: usize) -> Result<MerkleProof<T>, Error> {
+        if leaves.len() as u64 != self.leaves_count {
+            return Err(Error::leaves_count_mismatch(
+                self.leaves_count,
+                leaves.len() as u64,
+            ));
+        }
+        if leaf_index >= leaves.len() {
+            return Err(Error::leaf_index_out_of_range(leaf_index, leaves.len()));
+        }
+
+        let mut proof_hashes = Vec::with_capacity(self.depth);
+        let mut layer = leaves.to_vec();
+        let mut index = leaf_index;
+        for height in 0..self.depth {
+            let sibling_index = utils::indices::get_sibling_index(index);
+            proof_hashes.push(
+                layer
+                    .get(sibling_index)
+                    .copied()
+                    .unwrap_or(self.zero_hashes[height]),
+            );
+
+            layer = layer
+                .chunks(2)
+                .map(|pair| {
+                    T::concat_and_hash(
+                        &pair[0],
+                        Some(pair.get(1).unwrap_or(&self.zero_hashes[height])),
+                    )
+                })
+                .collect();
+            index = utils::indices::parent_index(index);
+        }
+
+        Ok(MerkleProof::new(proof_hashes))
+    }
+
+    /// Verifies a proof made by [`IncrementalMerkleTree::proof`] for the leaf at the given index
+    /// of a tree of the given depth. Unlike [`MerkleProof::verify`], it doesn't need the number
+    /// of leaves as a `usize`, so it works for trees that hold more than `usize::MAX` leaves.
+    /// The same as `is_valid_merkle_branch` of the consensus specs.
+    ///
+    /// [`MerkleProof::verify`]: crate::MerkleProof::verify
+    pub fn verify(
+        root: T::Hash,
+        proof: &MerkleProof<T>,
+        depth: usize,
+        leaf_index: u64,
+        leaf: T::Hash,
+    ) -> bool {
+        let proof_hashes = proof.proof_hashes();
+        if proof_hashes.len() != depth || depth >= u64::BITS as usize || leaf_index >> depth != 0 {
+            return false;
+        }
+
+        let mut node = leaf;
+        for (height, sibling) in proof_hashes.iter().enumerate() {
+            node = if (leaf_index >> height) & 1 == 1 {
+                T::concat_and_hash(sibling, Some(&node))
+            } else {
+                T::concat_and_hash(&node, Some(sibling))
+            };
+        }
+        node == root
+    }
+
+    /// Returns the depth of the tree
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the number of leaves the tree can hold, `2^depth`
+    pub fn capacity(&self) -> u64 {
+        1 << self.depth
+    }
+
+    /// Returns the number of appended leaves
+    pub fn leaves_len(&self) -> u64 {
+        self.leaves_count
+    }
+
+    /// Returns the roots of empty subtrees, from the zero leaf up to the root of an empty tree
+    pub fn zero_hashes(&self) -> &[T::Hash] {
+        &self.zero_hashes
+    }
+}
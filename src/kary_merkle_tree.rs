@@ -0,0 +1,117 @@
+use crate::prelude::*;
+use crate::{partial_tree::PartialTree, utils, Error, Hasher, MerkleProof};
+
+/// [`KaryMerkleTree`] is a Merkle tree where every node has up to `arity` children instead of
+/// two. Wider trees are shallower, so their proofs go through fewer layers.
+///
+/// Children of a node are hashed with [`Hasher::concat_and_hash_children`]. A node of the last
+/// group of a layer may have fewer children; a single child is handled the same way
+/// [`MerkleTree`] handles a node without a sibling. Proofs contain the missing siblings of
+/// every layer, ordered by their index, layer by layer, and can be serialized like any other
+/// [`MerkleProof`]. They are verified with [`MerkleProof::verify_with_arity`].
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{KaryMerkleTree, MerkleProof, algorithms::Sha256, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let leaves: Vec<[u8; 32]> = (0..64u8).map(|x| Sha256::hash(&[x])).collect();
+///
+/// let merkle_tree = KaryMerkleTree::<Sha256>::from_leaves(&leaves, 16)?;
+/// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+/// assert_eq!(merkle_tree.depth(), 2);
+///
+/// let proof_bytes = merkle_tree.proof(&[42])?.to_bytes();
+/// let proof = MerkleProof::<Sha256>::from_bytes(&proof_bytes)?;
+/// assert!(proof.verify_with_arity(root, &[42], &[leaves[42]], leaves.len(), 16));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`MerkleTree`]: crate::MerkleTree
+#[derive(Clone)]
+pub struct KaryMerkleTree<T: Hasher> {
+    arity: usize,
+    leaves_count: usize,
+    tree: PartialTree<T>,
+}
+
+impl<T: Hasher> KaryMerkleTree<T> {
+    /// Builds a tree from the leaves where every node has up to `arity` children
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidArity`] if `arity` is less than 2
+    ///
+    /// [`ErrorKind::InvalidArity`]: crate::ErrorKind::InvalidArity
+    pub fn from_leaves(leaves: &[T::Hash], arity: usize) -> Result<Self, Error> {
+        if arity < 2 {
+            return Err(Error::invalid_arity(arity));
+        }
+
+        let leaf_tuples: Vec<(usize, T::Hash)> = leaves.iter().cloned().enumerate().collect();
+        Ok(Self {
+            arity,
+            leaves_count: leaves.len(),
+            tree: PartialTree::build_with_arity(vec![leaf_tuples], leaves.len(), arity)?,
+        })
+    }
+
+    /// Returns the maximum number of children of a node
+    pub fn arity(&self) -> usize {
+        self.arity
+    }
+
+    /// Returns the root of the tree, or `None` if the tree is empty
+    pub fn root(&self) -> Option<T::Hash> {
+        self.tree.root().copied()
+    }
+
+    /// Same as [`KaryMerkleTree::root`], but serialized as a hex string
+    pub fn root_hex(&self) -> Option<String> {
+        let root = self.root()?;
+        Some(utils::collections::to_hex_string(&root))
+    }
+
+    /// Returns how many layers there are between the leaves and the root
+    pub fn depth(&self) -> usize {
+        self.tree.depth()
+    }
+
+    /// Returns a copy of the tree leaves
+    pub fn leaves(&self) -> Option<Vec<T::Hash>> {
+        self.tree.layer_nodes().into_iter().next()
+    }
+
+    /// Returns the number of leaves in the tree
+    pub fn leaves_len(&self) -> usize {
+        self.leaves_count
+    }
+
+    /// Returns a proof for the leaves at the given indices. Indices may be passed in any
+    /// order.
+    ///
+    /// ## Errors
+    ///
+    /// The same as [`MerkleTree::try_proof`]
+    ///
+    /// [`MerkleTree::try_proof`]: crate::MerkleTree::try_proof
+    pub fn proof(&self, leaf_indices: &[usize]) -> Result<MerkleProof<T>, Error> {
+        let sorted_indices = utils::indices::sorted_leaf_indices(leaf_indices, self.leaves_count)?;
+        let proof_indices = utils::indices::proof_indices_by_layers_with_arity(
+            &sorted_indices,
+            self.leaves_count,
+            self.arity,
+        );
+
+        let mut proof_hashes = Vec::new();
+        for (layer, layer_proof_indices) in self.tree.layers().iter().zip(proof_indices) {
+            for index in layer_proof_indices {
+                // Layers of a complete tree have a node at every index
+                proof_hashes.push(layer[index].1);
+            }
+        }
+
+        Ok(MerkleProof::new(proof_hashes))
+    }
+}
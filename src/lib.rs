@@ -128,27 +128,86 @@
 //! ```
 #![no_std]
 
+#[cfg(feature = "alloc")]
 extern crate alloc;
 
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(feature = "alloc")]
+pub use authenticated_map::{AuthenticatedMap, MapProof};
+#[cfg(feature = "alloc")]
+pub use batch_verifier::BatchVerifier;
+#[cfg(feature = "alloc")]
 pub use error::Error;
 pub use error::ErrorKind;
+pub use error::ProofSizeError;
+#[cfg(feature = "alloc")]
+pub use error::VerificationError;
+#[cfg(feature = "alloc")]
 pub use hasher::Hasher;
+pub use hasher::NodeHasher;
+#[cfg(feature = "alloc")]
+pub use incremental_merkle_tree::IncrementalMerkleTree;
+#[cfg(feature = "alloc")]
+pub use kary_merkle_tree::KaryMerkleTree;
+#[cfg(feature = "alloc")]
 pub use merkle_proof::MerkleProof;
+pub use merkle_proof_ref::MerkleProofRef;
+#[cfg(feature = "alloc")]
 pub use merkle_tree::MerkleTree;
+#[cfg(feature = "alloc")]
+pub use non_membership_proof::NonMembershipProof;
+#[cfg(feature = "alloc")]
+pub use padding::Padding;
+#[cfg(feature = "alloc")]
 pub use partial_tree::PartialTree;
+#[cfg(feature = "alloc")]
 pub use proof_serializers::MerkleProofSerializer;
+#[cfg(feature = "alloc")]
+pub use sorted_merkle_tree::SortedMerkleTree;
 
+#[cfg(feature = "alloc")]
+mod authenticated_map;
+#[cfg(feature = "alloc")]
+mod batch_verifier;
+#[cfg(any(feature = "wasm", feature = "ffi", feature = "python"))]
+mod dynamic;
 mod error;
 mod hasher;
+#[cfg(feature = "alloc")]
+mod incremental_merkle_tree;
+#[cfg(feature = "alloc")]
+mod kary_merkle_tree;
+#[cfg(feature = "alloc")]
 mod merkle_proof;
+mod merkle_proof_ref;
+#[cfg(feature = "alloc")]
 mod merkle_tree;
+#[cfg(feature = "alloc")]
+mod non_membership_proof;
+#[cfg(feature = "alloc")]
+mod padding;
+#[cfg(feature = "alloc")]
 mod partial_tree;
 mod prelude;
+#[cfg(feature = "alloc")]
+mod sorted_merkle_tree;
 #[doc(hidden)]
 pub mod utils;
 
 pub mod algorithms;
+#[cfg(feature = "std")]
+pub mod chunking;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "alloc")]
 pub mod proof_serializers;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "solidity")]
+pub mod solidity;
+#[cfg(feature = "alloc")]
+pub mod ssz;
+#[cfg(feature = "wasm")]
+pub mod wasm;
@@ -1,12 +1,18 @@
 use crate::{
-    error::Error,
+    error::{Error, VerificationError},
     partial_tree::PartialTree,
     prelude::*,
-    proof_serializers::{DirectHashesOrder, MerkleProofSerializer},
-    utils, Hasher,
+    proof_serializers::{
+        DirectHashesOrder, MerkleProofSerializer, ProofSink, ProofSource, StreamingProofSerializer,
+    },
+    utils, Hasher, Padding,
 };
+use alloc::collections::BTreeMap;
 use core::convert::TryFrom;
 
+/// Proof hashes grouped by tree layers, together with their indices in a layer
+pub(crate) type ProofLayers<H> = Vec<Vec<(usize, H)>>;
+
 /// [`MerkleProof`] is used to parse, verify, calculate a root for Merkle proofs.
 ///
 /// ## Usage
@@ -55,6 +61,242 @@ impl<T: Hasher> MerkleProof<T> {
         MerkleProof { proof_hashes }
     }
 
+    /// Merges proofs of single leaves into one multiproof for all of them. Siblings that the
+    /// verifier can calculate from the proven leaves are dropped, so the result is the same
+    /// minimal proof [`MerkleTree::proof`] returns for these leaves. `leaf_indices` contains
+    /// the index of the leaf proven by each proof, in the same order as `proofs`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, MerkleProof, algorithms::Sha256, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    ///
+    /// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+    /// let proofs = [merkle_tree.proof(&[3]), merkle_tree.proof(&[4])];
+    ///
+    /// let multiproof = MerkleProof::merge(&proofs, &[3, 4], leaves.len())?;
+    /// assert_eq!(multiproof.proof_hashes(), merkle_tree.proof(&[3, 4]).proof_hashes());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the number of proofs doesn't match the number of indices, or if
+    /// a proof doesn't contain enough hashes for its leaf. Returns an error of kind
+    /// [`ErrorKind::ConflictingProofHashes`] if two proofs have different hashes for the same
+    /// node, since at most one of them can belong to the tree.
+    ///
+    /// [`ErrorKind::ConflictingProofHashes`]: crate::ErrorKind::ConflictingProofHashes
+    /// [`MerkleTree::proof`]: crate::MerkleTree::proof
+    pub fn merge(
+        proofs: &[Self],
+        leaf_indices: &[usize],
+        total_leaves_count: usize,
+    ) -> Result<Self, Error> {
+        if proofs.len() != leaf_indices.len() {
+            return Err(Error::proofs_indices_count_mismatch(
+                proofs.len(),
+                leaf_indices.len(),
+            ));
+        }
+
+        // Siblings from all the proofs, keyed by (layer index, node index)
+        let mut known_nodes: BTreeMap<(usize, usize), T::Hash> = BTreeMap::new();
+        for (proof, leaf_index) in proofs.iter().zip(leaf_indices) {
+            let proof_indices_by_layers =
+                utils::indices::proof_indices_by_layers(&[*leaf_index], total_leaves_count);
+            let mut proof_hashes = proof.proof_hashes.iter();
+
+            for (layer_index, proof_indices) in proof_indices_by_layers.iter().enumerate() {
+                for node_index in proof_indices {
+                    let hash = proof_hashes
+                        .next()
+                        .ok_or_else(Error::not_enough_hashes_to_calculate_root)?;
+                    let known_hash = known_nodes
+                        .entry((layer_index, *node_index))
+                        .or_insert(*hash);
+                    if known_hash != hash {
+                        return Err(Error::conflicting_proof_hashes(layer_index, *node_index));
+                    }
+                }
+            }
+        }
+
+        let mut sorted_indices = leaf_indices.to_vec();
+        sorted_indices.sort_unstable();
+        sorted_indices.dedup();
+
+        let mut proof_hashes = Vec::new();
+        let proof_indices_by_layers =
+            utils::indices::proof_indices_by_layers(&sorted_indices, total_leaves_count);
+        for (layer_index, proof_indices) in proof_indices_by_layers.iter().enumerate() {
+            for node_index in proof_indices {
+                match known_nodes.get(&(layer_index, *node_index)) {
+                    Some(hash) => proof_hashes.push(*hash),
+                    None => return Err(Error::not_enough_hashes_to_calculate_root()),
+                }
+            }
+        }
+
+        Ok(Self::new(proof_hashes))
+    }
+
+    /// Extracts the proof of a single leaf from a multiproof. The proven leaves are required,
+    /// since siblings of the leaf path can be calculated from the other leaves instead of being
+    /// included in the multiproof. Arguments are the same as the ones [`MerkleProof::root`]
+    /// takes, plus the index of the leaf to extract the proof for.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, MerkleProof, algorithms::Sha256, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    ///
+    /// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+    /// let multiproof = merkle_tree.proof(&[2, 3, 4]);
+    ///
+    /// let proof = multiproof.split(&[2, 3, 4], &leaves[2..5], leaves.len(), 3)?;
+    /// assert_eq!(proof.proof_hashes(), merkle_tree.proof(&[3]).proof_hashes());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error if the leaf is not one of the proven leaves, or if the tree can't be
+    /// reconstructed from the multiproof.
+    pub fn split(
+        &self,
+        leaf_indices: &[usize],
+        leaf_hashes: &[T::Hash],
+        total_leaves_count: usize,
+        leaf_index: usize,
+    ) -> Result<Self, Error> {
+        if !leaf_indices.contains(&leaf_index) {
+            return Err(Error::leaf_not_in_proof(leaf_index));
+        }
+
+        let tree_depth = utils::indices::tree_depth(total_leaves_count);
+        let (proof_layers, _unused_hashes_count) =
+            self.proof_layers(leaf_indices, leaf_hashes, total_leaves_count)?;
+        let partial_tree = PartialTree::<T>::build(proof_layers, tree_depth)?;
+
+        let mut proof_hashes = Vec::new();
+        let proof_indices_by_layers =
+            utils::indices::proof_indices_by_layers(&[leaf_index], total_leaves_count);
+        for (layer, proof_indices) in partial_tree.layers().iter().zip(proof_indices_by_layers) {
+            for node_index in proof_indices {
+                match layer.binary_search_by_key(&node_index, |(index, _)| *index) {
+                    Ok(position) => proof_hashes.push(layer[position].1),
+                    Err(_) => return Err(Error::not_enough_hashes_to_calculate_root()),
+                }
+            }
+        }
+
+        Ok(Self::new(proof_hashes))
+    }
+
+    /// Updates a proof of a single leaf after leaves were appended to the tree, so it verifies
+    /// against the new root. Hashes of the nodes that haven't changed are taken from the old
+    /// proof, and the rest are taken from `update_hashes`, which are produced on the server by
+    /// [`MerkleTree::proof_update`].
+    ///
+    /// ## Examples
+    ///
+    /// Check [`MerkleTree::proof_update`] for an example.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`ErrorKind::LeavesCountDecreased`] if `new_leaves_count` is lower than
+    /// `old_leaves_count`, [`ErrorKind::LeafIndexOutOfRange`] if the leaf wasn't in the old tree,
+    /// [`ErrorKind::NotEnoughHashesToCalculateRoot`] if the old proof or the update is too short
+    /// and [`ErrorKind::TooManyProofHashes`] if some of the update hashes weren't used.
+    ///
+    /// [`MerkleTree::proof_update`]: crate::MerkleTree::proof_update
+    /// [`ErrorKind::LeavesCountDecreased`]: crate::ErrorKind::LeavesCountDecreased
+    /// [`ErrorKind::LeafIndexOutOfRange`]: crate::ErrorKind::LeafIndexOutOfRange
+    /// [`ErrorKind::NotEnoughHashesToCalculateRoot`]: crate::ErrorKind::NotEnoughHashesToCalculateRoot
+    /// [`ErrorKind::TooManyProofHashes`]: crate::ErrorKind::TooManyProofHashes
+    pub fn update(
+        &self,
+        leaf_index: usize,
+        old_leaves_count: usize,
+        new_leaves_count: usize,
+        update_hashes: &[T::Hash],
+    ) -> Result<Self, Error> {
+        if new_leaves_count < old_leaves_count {
+            return Err(Error::leaves_count_decreased(
+                old_leaves_count,
+                new_leaves_count,
+            ));
+        }
+        if leaf_index >= old_leaves_count {
+            return Err(Error::leaf_index_out_of_range(leaf_index, old_leaves_count));
+        }
+
+        // A single leaf proof has at most one hash per layer
+        let mut old_proof_hashes = self.proof_hashes.iter();
+        let mut old_layers: Vec<Option<T::Hash>> = Vec::new();
+        for proof_indices in
+            utils::indices::proof_indices_by_layers(&[leaf_index], old_leaves_count)
+        {
+            match proof_indices.first() {
+                Some(_) => old_layers.push(Some(
+                    *old_proof_hashes
+                        .next()
+                        .ok_or_else(Error::not_enough_hashes_to_calculate_root)?,
+                )),
+                None => old_layers.push(None),
+            }
+        }
+
+        let mut update_hashes = update_hashes.iter();
+        let mut proof_hashes = Vec::new();
+        let new_proof_indices_by_layers =
+            utils::indices::proof_indices_by_layers(&[leaf_index], new_leaves_count);
+
+        for (layer_index, proof_indices) in new_proof_indices_by_layers.iter().enumerate() {
+            for index in proof_indices {
+                let unchanged_hash = if utils::indices::is_node_unchanged(
+                    layer_index,
+                    *index,
+                    old_leaves_count,
+                    new_leaves_count,
+                ) {
+                    old_layers.get(layer_index).cloned().flatten()
+                } else {
+                    None
+                };
+
+                match unchanged_hash {
+                    Some(hash) => proof_hashes.push(hash),
+                    None => proof_hashes.push(
+                        *update_hashes
+                            .next()
+                            .ok_or_else(Error::not_enough_hashes_to_calculate_root)?,
+                    ),
+                }
+            }
+        }
+
+        let unused_hashes_count = update_hashes.len();
+        if unused_hashes_count != 0 {
+            return Err(Error::too_many_proof_hashes(unused_hashes_count));
+        }
+
+        Ok(Self::new(proof_hashes))
+    }
+
     /// Creates a proof from a slice of bytes, direct hashes order. If you're looking for
     /// other options of bytes to proof deserialization, take a look at [`MerkleProof::deserialize`]
     ///
@@ -169,6 +411,58 @@ impl<T: Hasher> MerkleProof<T> {
         }
     }
 
+    /// Same as [`MerkleProof::verify`], but returns the reason of a failed verification instead
+    /// of `false`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, MerkleProof, algorithms::Sha256, Hasher, VerificationError};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves = [
+    ///     Sha256::hash("a".as_bytes()),
+    ///     Sha256::hash("b".as_bytes()),
+    ///     Sha256::hash("c".as_bytes()),
+    /// ];
+    ///
+    /// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+    /// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+    /// let proof = merkle_tree.proof(&[0, 1]);
+    ///
+    /// proof.verify_detailed(root, &[0, 1], &leaves[0..2], leaves.len())?;
+    ///
+    /// let result = proof.verify_detailed(root, &[0, 1], &leaves[1..3], leaves.len());
+    /// assert!(matches!(result, Err(VerificationError::RootMismatch { .. })));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`VerificationError::RootMismatch`] with both roots if the calculated root is
+    /// different from `root`, or [`VerificationError::Proof`] if the root can't be calculated.
+    ///
+    /// [`VerificationError::RootMismatch`]: crate::VerificationError::RootMismatch
+    /// [`VerificationError::Proof`]: crate::VerificationError::Proof
+    pub fn verify_detailed(
+        &self,
+        root: T::Hash,
+        leaf_indices: &[usize],
+        leaf_hashes: &[T::Hash],
+        total_leaves_count: usize,
+    ) -> Result<(), VerificationError<T::Hash>> {
+        let computed = self.root(leaf_indices, leaf_hashes, total_leaves_count)?;
+
+        if computed != root {
+            return Err(VerificationError::RootMismatch {
+                expected: root,
+                computed,
+            });
+        }
+
+        Ok(())
+    }
+
     /// Calculates Merkle root based on provided leaves and proof hashes. Used inside the
     /// [`MerkleProof::verify`] method, but sometimes can be used on its own.
     ///
@@ -205,6 +499,302 @@ impl<T: Hasher> MerkleProof<T> {
         leaf_hashes: &[T::Hash],
         total_leaves_count: usize,
     ) -> Result<T::Hash, Error> {
+        let (root, _unused_hashes_count) =
+            self.root_and_unused_hashes_count(leaf_indices, leaf_hashes, total_leaves_count)?;
+        Ok(root)
+    }
+
+    /// Same as [`MerkleProof::verify`], but for a tree where every node has up to `arity`
+    /// children, see [`KaryMerkleTree`].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{KaryMerkleTree, algorithms::Sha256, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    ///
+    /// let merkle_tree = KaryMerkleTree::<Sha256>::from_leaves(&leaves, 4)?;
+    /// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+    /// let proof = merkle_tree.proof(&[1, 4])?;
+    ///
+    /// assert!(proof.verify_with_arity(root, &[1, 4], &[leaves[1], leaves[4]], leaves.len(), 4));
+    /// assert!(!proof.verify(root, &[1, 4], &[leaves[1], leaves[4]], leaves.len()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`KaryMerkleTree`]: crate::KaryMerkleTree
+    pub fn verify_with_arity(
+        &self,
+        root: T::Hash,
+        leaf_indices: &[usize],
+        leaf_hashes: &[T::Hash],
+        total_leaves_count: usize,
+        arity: usize,
+    ) -> bool {
+        match self.root_with_arity(leaf_indices, leaf_hashes, total_leaves_count, arity) {
+            Ok(extracted_root) => extracted_root == root,
+            Err(_) => false,
+        }
+    }
+
+    /// Same as [`MerkleProof::root`], but for a tree where every node has up to `arity`
+    /// children, see [`KaryMerkleTree`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidArity`] if `arity` is less than 2,
+    /// [`ErrorKind::LeafIndexOutOfRange`] if a leaf index is out of range, and the same
+    /// errors as [`MerkleProof::root`] if the root can't be calculated
+    ///
+    /// [`KaryMerkleTree`]: crate::KaryMerkleTree
+    /// [`ErrorKind::InvalidArity`]: crate::ErrorKind::InvalidArity
+    /// [`ErrorKind::LeafIndexOutOfRange`]: crate::ErrorKind::LeafIndexOutOfRange
+    pub fn root_with_arity(
+        &self,
+        leaf_indices: &[usize],
+        leaf_hashes: &[T::Hash],
+        total_leaves_count: usize,
+        arity: usize,
+    ) -> Result<T::Hash, Error> {
+        if arity < 2 {
+            return Err(Error::invalid_arity(arity));
+        }
+        if leaf_indices.len() != leaf_hashes.len() {
+            return Err(Error::leaves_indices_count_mismatch(
+                leaf_indices.len(),
+                leaf_hashes.len(),
+            ));
+        }
+
+        let mut leaf_tuples: Vec<(usize, T::Hash)> = leaf_indices
+            .iter()
+            .cloned()
+            .zip(leaf_hashes.iter().cloned())
+            .collect();
+        leaf_tuples.sort_by_key(|(index, _)| *index);
+        let (sorted_indices, _): (Vec<_>, Vec<_>) = leaf_tuples.iter().cloned().unzip();
+        if let Some(index) = sorted_indices
+            .iter()
+            .find(|index| **index >= total_leaves_count)
+        {
+            return Err(Error::leaf_index_out_of_range(*index, total_leaves_count));
+        }
+
+        let mut proof_layers: ProofLayers<T::Hash> = Vec::new();
+        let mut proof_hashes = self.proof_hashes.iter();
+        for proof_indices in utils::indices::proof_indices_by_layers_with_arity(
+            &sorted_indices,
+            total_leaves_count,
+            arity,
+        ) {
+            let layer = proof_indices
+                .into_iter()
+                .map(|index| Some((index, *proof_hashes.next()?)))
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(Error::not_enough_hashes_to_calculate_root)?;
+            proof_layers.push(layer);
+        }
+
+        match proof_layers.first_mut() {
+            Some(first_layer) => first_layer.append(&mut leaf_tuples),
+            None => proof_layers.push(leaf_tuples),
+        }
+
+        let partial_tree =
+            PartialTree::<T>::build_with_arity(proof_layers, total_leaves_count, arity)?;
+        partial_tree
+            .root()
+            .copied()
+            .ok_or_else(Error::not_enough_hashes_to_calculate_root)
+    }
+
+    /// Same as [`MerkleProof::verify`], but for a tree padded with filler leaves, see
+    /// [`MerkleTree::with_padding`]. `total_leaves_count` is the number of leaves before
+    /// padding, and the padding nodes the proof doesn't contain are calculated from `padding`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, Padding, algorithms::Sha256, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    ///
+    /// let padding = Padding::<Sha256>::to_power_of_two([0; 32]);
+    /// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves).with_padding(padding.clone())?;
+    /// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+    /// let proof = merkle_tree.proof(&[1, 4]);
+    ///
+    /// assert!(proof.verify_padded(root, &[1, 4], &[leaves[1], leaves[4]], leaves.len(), &padding));
+    /// assert!(!proof.verify(root, &[1, 4], &[leaves[1], leaves[4]], leaves.len()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`MerkleTree::with_padding`]: crate::MerkleTree::with_padding
+    pub fn verify_padded(
+        &self,
+        root: T::Hash,
+        leaf_indices: &[usize],
+        leaf_hashes: &[T::Hash],
+        total_leaves_count: usize,
+        padding: &Padding<T>,
+    ) -> bool {
+        match self.root_padded(leaf_indices, leaf_hashes, total_leaves_count, padding) {
+            Ok(extracted_root) => extracted_root == root,
+            Err(_) => false,
+        }
+    }
+
+    /// Same as [`MerkleProof::root`], but for a tree padded with filler leaves, see
+    /// [`MerkleProof::verify_padded`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::TreeIsFull`] if `total_leaves_count` is greater
+    /// than the padding limit, [`ErrorKind::LeafIndexOutOfRange`] if a leaf index is out of
+    /// range, and the same errors as [`MerkleProof::root`] if the root can't be calculated
+    ///
+    /// [`ErrorKind::TreeIsFull`]: crate::ErrorKind::TreeIsFull
+    /// [`ErrorKind::LeafIndexOutOfRange`]: crate::ErrorKind::LeafIndexOutOfRange
+    pub fn root_padded(
+        &self,
+        leaf_indices: &[usize],
+        leaf_hashes: &[T::Hash],
+        total_leaves_count: usize,
+        padding: &Padding<T>,
+    ) -> Result<T::Hash, Error> {
+        let (tree_depth, zero_hashes) = padding.tree_shape(total_leaves_count)?;
+        if let Some(index) = leaf_indices
+            .iter()
+            .find(|index| **index >= total_leaves_count)
+        {
+            return Err(Error::leaf_index_out_of_range(*index, total_leaves_count));
+        }
+
+        let (proof_layers, _unused_hashes_count) =
+            self.proof_layers(leaf_indices, leaf_hashes, total_leaves_count)?;
+        let partial_tree = PartialTree::<T>::build_padded(proof_layers, tree_depth, &zero_hashes)?;
+
+        partial_tree
+            .root()
+            .copied()
+            .ok_or_else(Error::not_enough_hashes_to_calculate_root)
+    }
+
+    /// Same as [`MerkleProof::verify`], but also rejects proofs that [`MerkleProof::verify`]
+    /// tolerates. Every valid proof has exactly one byte encoding that passes strict
+    /// verification, so strictly verified proofs can be used as canonical commitments.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, MerkleProof, algorithms::Sha256, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves = [
+    ///     Sha256::hash("a".as_bytes()),
+    ///     Sha256::hash("b".as_bytes()),
+    ///     Sha256::hash("c".as_bytes()),
+    /// ];
+    ///
+    /// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+    /// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+    /// let proof = merkle_tree.proof(&[0]);
+    /// assert!(proof.verify_strict(root, &[0], &leaves[..1], leaves.len()));
+    ///
+    /// // Appending an unused hash doesn't change the root, but breaks strict verification
+    /// let mut padded_hashes = proof.proof_hashes().to_vec();
+    /// padded_hashes.push(leaves[2]);
+    /// let padded_proof = MerkleProof::<Sha256>::new(padded_hashes);
+    /// assert!(padded_proof.verify(root, &[0], &leaves[..1], leaves.len()));
+    /// assert!(!padded_proof.verify_strict(root, &[0], &leaves[..1], leaves.len()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn verify_strict(
+        &self,
+        root: T::Hash,
+        leaf_indices: &[usize],
+        leaf_hashes: &[T::Hash],
+        total_leaves_count: usize,
+    ) -> bool {
+        match self.root_strict(leaf_indices, leaf_hashes, total_leaves_count) {
+            Ok(extracted_root) => extracted_root == root,
+            Err(_) => false,
+        }
+    }
+
+    /// Same as [`MerkleProof::root`], but fails on proofs and arguments that
+    /// [`MerkleProof::root`] silently accepts. Used inside [`MerkleProof::verify_strict`].
+    ///
+    /// ## Errors
+    ///
+    /// In addition to errors returned by [`MerkleProof::root`], returns
+    /// [`ErrorKind::ZeroLeavesCount`] if `total_leaves_count` is zero,
+    /// [`ErrorKind::EmptyLeafIndices`] if no leaves were passed,
+    /// [`ErrorKind::LeafIndexOutOfRange`] if an index is not less than `total_leaves_count`,
+    /// [`ErrorKind::DuplicateLeafIndex`] if an index was passed more than once and
+    /// [`ErrorKind::TooManyProofHashes`] if some proof hashes weren't used to calculate the root.
+    ///
+    /// [`ErrorKind::ZeroLeavesCount`]: crate::ErrorKind::ZeroLeavesCount
+    /// [`ErrorKind::EmptyLeafIndices`]: crate::ErrorKind::EmptyLeafIndices
+    /// [`ErrorKind::LeafIndexOutOfRange`]: crate::ErrorKind::LeafIndexOutOfRange
+    /// [`ErrorKind::DuplicateLeafIndex`]: crate::ErrorKind::DuplicateLeafIndex
+    /// [`ErrorKind::TooManyProofHashes`]: crate::ErrorKind::TooManyProofHashes
+    pub fn root_strict(
+        &self,
+        leaf_indices: &[usize],
+        leaf_hashes: &[T::Hash],
+        total_leaves_count: usize,
+    ) -> Result<T::Hash, Error> {
+        if total_leaves_count == 0 {
+            return Err(Error::zero_leaves_count());
+        }
+        utils::indices::sorted_leaf_indices(leaf_indices, total_leaves_count)?;
+
+        let (root, unused_hashes_count) =
+            self.root_and_unused_hashes_count(leaf_indices, leaf_hashes, total_leaves_count)?;
+        if unused_hashes_count != 0 {
+            return Err(Error::too_many_proof_hashes(unused_hashes_count));
+        }
+
+        Ok(root)
+    }
+
+    /// Calculates the root and counts proof hashes that were left over after the calculation
+    fn root_and_unused_hashes_count(
+        &self,
+        leaf_indices: &[usize],
+        leaf_hashes: &[T::Hash],
+        total_leaves_count: usize,
+    ) -> Result<(T::Hash, usize), Error> {
+        let tree_depth = utils::indices::tree_depth(total_leaves_count);
+        let (proof_layers, unused_hashes_count) =
+            self.proof_layers(leaf_indices, leaf_hashes, total_leaves_count)?;
+
+        let partial_tree = PartialTree::<T>::build(proof_layers, tree_depth)?;
+
+        match partial_tree.root() {
+            Some(root) => Ok((*root, unused_hashes_count)),
+            None => Err(Error::not_enough_hashes_to_calculate_root()),
+        }
+    }
+
+    /// Groups proof hashes by tree layers and adds the leaves to the first layer, so the result
+    /// can be used to build a [`PartialTree`]. Also returns how many proof hashes were left over.
+    pub(crate) fn proof_layers(
+        &self,
+        leaf_indices: &[usize],
+        leaf_hashes: &[T::Hash],
+        total_leaves_count: usize,
+    ) -> Result<(ProofLayers<T::Hash>, usize), Error> {
         if leaf_indices.len() != leaf_hashes.len() {
             return Err(Error::leaves_indices_count_mismatch(
                 leaf_indices.len(),
@@ -220,7 +810,7 @@ impl<T: Hasher> MerkleProof<T> {
             .zip(leaf_hashes.iter().cloned())
             .collect();
         // Sorting leaves by indexes in case they weren't sorted already
-        leaf_tuples.sort_by(|(a, _), (b, _)| a.cmp(b));
+        leaf_tuples.sort_by_key(|(index, _)| *index);
         // Getting back _sorted_ indices
         let (sorted_indices, _): (Vec<_>, Vec<_>) = leaf_tuples.iter().cloned().unzip();
 
@@ -228,7 +818,7 @@ impl<T: Hasher> MerkleProof<T> {
             utils::indices::proof_indices_by_layers(&sorted_indices, total_leaves_count);
 
         // The next lines copy hashes from proof hashes and group them by layer index
-        let mut proof_layers: Vec<Vec<(usize, T::Hash)>> = Vec::with_capacity(tree_depth + 1);
+        let mut proof_layers: ProofLayers<T::Hash> = Vec::with_capacity(tree_depth + 1);
         let mut proof_copy = self.proof_hashes.clone();
 
         for proof_indices in proof_indices_by_layers {
@@ -242,19 +832,211 @@ impl<T: Hasher> MerkleProof<T> {
         match proof_layers.first_mut() {
             Some(first_layer) => {
                 first_layer.append(&mut leaf_tuples);
-                first_layer.sort_by(|(a, _), (b, _)| a.cmp(b));
+                first_layer.sort_by_key(|(index, _)| *index);
             }
             None => proof_layers.push(leaf_tuples),
         }
 
-        let partial_tree = PartialTree::<T>::build(proof_layers, tree_depth)?;
+        Ok((proof_layers, proof_copy.len()))
+    }
 
-        match partial_tree.root() {
+    /// Uses a range proof made by [`MerkleTree::range_proof`] to verify that `leaf_hashes` are
+    /// consecutive leaves of the tree, starting at the `range_start` index.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, algorithms::Sha256, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    ///
+    /// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+    /// let merkle_root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+    ///
+    /// let proof = merkle_tree.range_proof(2..6)?;
+    /// assert!(proof.verify_range(merkle_root, 2, &leaves[2..6], leaves.len()));
+    /// assert!(!proof.verify_range(merkle_root, 1, &leaves[1..5], leaves.len()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`MerkleTree::range_proof`]: crate::MerkleTree::range_proof
+    pub fn verify_range(
+        &self,
+        root: T::Hash,
+        range_start: usize,
+        leaf_hashes: &[T::Hash],
+        total_leaves_count: usize,
+    ) -> bool {
+        match self.range_root(range_start, leaf_hashes, total_leaves_count) {
+            Ok(extracted_root) => extracted_root == root,
+            Err(_) => false,
+        }
+    }
+
+    /// Calculates the root from a range proof and consecutive leaves starting at the
+    /// `range_start` index. Used inside the [`MerkleProof::verify_range`] method. Hashes each
+    /// layer of the range from left to right, taking boundary siblings from the proof.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`ErrorKind::ZeroLeavesCount`] if `total_leaves_count` is zero,
+    /// [`ErrorKind::EmptyLeafIndices`] if no leaves were passed,
+    /// [`ErrorKind::LeafIndexOutOfRange`] if the range ends past the last leaf,
+    /// [`ErrorKind::NotEnoughHashesToCalculateRoot`] if the proof is too short and
+    /// [`ErrorKind::TooManyProofHashes`] if some proof hashes weren't used.
+    ///
+    /// [`ErrorKind::ZeroLeavesCount`]: crate::ErrorKind::ZeroLeavesCount
+    /// [`ErrorKind::EmptyLeafIndices`]: crate::ErrorKind::EmptyLeafIndices
+    /// [`ErrorKind::LeafIndexOutOfRange`]: crate::ErrorKind::LeafIndexOutOfRange
+    /// [`ErrorKind::NotEnoughHashesToCalculateRoot`]: crate::ErrorKind::NotEnoughHashesToCalculateRoot
+    /// [`ErrorKind::TooManyProofHashes`]: crate::ErrorKind::TooManyProofHashes
+    pub fn range_root(
+        &self,
+        range_start: usize,
+        leaf_hashes: &[T::Hash],
+        total_leaves_count: usize,
+    ) -> Result<T::Hash, Error> {
+        if total_leaves_count == 0 {
+            return Err(Error::zero_leaves_count());
+        }
+        if leaf_hashes.is_empty() {
+            return Err(Error::empty_leaf_indices());
+        }
+        let range_end = match range_start.checked_add(leaf_hashes.len()) {
+            Some(range_end) if range_end <= total_leaves_count => range_end,
+            _ => {
+                return Err(Error::leaf_index_out_of_range(
+                    range_start.saturating_add(leaf_hashes.len() - 1),
+                    total_leaves_count,
+                ))
+            }
+        };
+
+        let mut proof_hashes = self.proof_hashes.iter();
+        let mut layer_nodes: Vec<T::Hash> = leaf_hashes.to_vec();
+        let (mut start, mut end, mut layer_len) = (range_start, range_end, total_leaves_count);
+
+        for _ in 0..utils::indices::tree_depth(total_leaves_count) {
+            if !utils::indices::is_left_index(start) {
+                let left_sibling = proof_hashes
+                    .next()
+                    .ok_or_else(Error::not_enough_hashes_to_calculate_root)?;
+                layer_nodes.insert(0, *left_sibling);
+            }
+            if !utils::indices::is_left_index(end) && end < layer_len {
+                let right_sibling = proof_hashes
+                    .next()
+                    .ok_or_else(Error::not_enough_hashes_to_calculate_root)?;
+                layer_nodes.push(*right_sibling);
+            }
+
+            layer_nodes = layer_nodes
+                .chunks(2)
+                .map(|pair| T::concat_and_hash(&pair[0], pair.get(1)))
+                .collect();
+            start /= 2;
+            end = utils::indices::div_ceil(end, 2);
+            layer_len = utils::indices::div_ceil(layer_len, 2);
+        }
+
+        let unused_hashes_count = proof_hashes.len();
+        if unused_hashes_count != 0 {
+            return Err(Error::too_many_proof_hashes(unused_hashes_count));
+        }
+
+        match layer_nodes.first() {
             Some(root) => Ok(*root),
             None => Err(Error::not_enough_hashes_to_calculate_root()),
         }
     }
 
+    /// Uses a proof made by [`MerkleTree::node_proof`] to verify that `node_hash` is the node
+    /// at `node_index` of the layer `layer_index` in a tree of `total_leaves_count` leaves. In
+    /// other words, verifies that `node_hash` is the root of a subtree of the tree.
+    ///
+    /// For examples, please check [`MerkleTree::node_proof`]
+    ///
+    /// [`MerkleTree::node_proof`]: crate::MerkleTree::node_proof
+    pub fn verify_node(
+        &self,
+        root: T::Hash,
+        layer_index: usize,
+        node_index: usize,
+        node_hash: T::Hash,
+        total_leaves_count: usize,
+    ) -> bool {
+        match self.node_root(layer_index, node_index, node_hash, total_leaves_count) {
+            Ok(extracted_root) => extracted_root == root,
+            Err(_) => false,
+        }
+    }
+
+    /// Calculates the root from a proof of an internal node. Used inside the
+    /// [`MerkleProof::verify_node`] method.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`ErrorKind::NodeOutOfRange`] if there's no such node in a tree of
+    /// `total_leaves_count` leaves, [`ErrorKind::NotEnoughHashesToCalculateRoot`] if the proof
+    /// is too short and [`ErrorKind::TooManyProofHashes`] if some proof hashes weren't used.
+    ///
+    /// [`ErrorKind::NodeOutOfRange`]: crate::ErrorKind::NodeOutOfRange
+    /// [`ErrorKind::NotEnoughHashesToCalculateRoot`]: crate::ErrorKind::NotEnoughHashesToCalculateRoot
+    /// [`ErrorKind::TooManyProofHashes`]: crate::ErrorKind::TooManyProofHashes
+    pub fn node_root(
+        &self,
+        layer_index: usize,
+        node_index: usize,
+        node_hash: T::Hash,
+        total_leaves_count: usize,
+    ) -> Result<T::Hash, Error> {
+        let tree_depth = utils::indices::tree_depth(total_leaves_count);
+        let node_out_of_range =
+            || Error::node_out_of_range(layer_index, node_index, total_leaves_count);
+        // The depth is checked first, so the layer length is never calculated for an
+        // arbitrary large layer index
+        if layer_index > tree_depth {
+            return Err(node_out_of_range());
+        }
+        let mut layer_len = utils::indices::layer_len(total_leaves_count, layer_index);
+        if node_index >= layer_len {
+            return Err(node_out_of_range());
+        }
+
+        let mut proof_hashes = self.proof_hashes.iter();
+        let (mut current_index, mut current_hash) = (node_index, node_hash);
+
+        for _ in layer_index..tree_depth {
+            let sibling_index = utils::indices::get_sibling_index(current_index);
+            current_hash = if sibling_index >= layer_len {
+                T::concat_and_hash(&current_hash, None)
+            } else {
+                let sibling_hash = proof_hashes
+                    .next()
+                    .ok_or_else(Error::not_enough_hashes_to_calculate_root)?;
+                if utils::indices::is_left_index(current_index) {
+                    T::concat_and_hash(&current_hash, Some(sibling_hash))
+                } else {
+                    T::concat_and_hash(sibling_hash, Some(&current_hash))
+                }
+            };
+
+            current_index = utils::indices::parent_index(current_index);
+            layer_len = utils::indices::div_ceil(layer_len, 2);
+        }
+
+        let unused_hashes_count = proof_hashes.len();
+        if unused_hashes_count != 0 {
+            return Err(Error::too_many_proof_hashes(unused_hashes_count));
+        }
+
+        Ok(current_hash)
+    }
+
     /// Calculates the root and serializes it into a hex string.
     ///
     /// ## Examples
@@ -456,6 +1238,92 @@ impl<T: Hasher> MerkleProof<T> {
     pub fn serialize<S: MerkleProofSerializer>(&self) -> Vec<u8> {
         S::serialize(self)
     }
+
+    /// Writes the proof to a [`ProofSink`] using a streaming serializer. With the `std`
+    /// feature, any [`std::io::Write`] can be used as a sink through
+    /// [`IoSink`](crate::proof_serializers::IoSink).
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, MerkleProof, algorithms::Sha256, Hasher, proof_serializers};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    /// let merkle_proof = MerkleTree::<Sha256>::from_leaves(&leaves).proof(&[3, 4]);
+    ///
+    /// let mut sink = proof_serializers::IoSink(Vec::new());
+    /// merkle_proof.serialize_to::<proof_serializers::ReverseHashesOrder, _>(&mut sink)?;
+    ///
+    /// assert_eq!(
+    ///     sink.0,
+    ///     merkle_proof.serialize::<proof_serializers::ReverseHashesOrder>()
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::IoError`] if the sink fails
+    ///
+    /// [`ErrorKind::IoError`]: crate::ErrorKind::IoError
+    pub fn serialize_to<S: StreamingProofSerializer, W: ProofSink>(
+        &self,
+        sink: &mut W,
+    ) -> Result<(), Error> {
+        S::serialize_to(self, sink)
+    }
+
+    /// Reads a proof from a [`ProofSource`] using a streaming serializer, reading no more than
+    /// `max_proof_len` bytes. With the `std` feature, any [`std::io::Read`] can be used as a
+    /// source through [`IoSource`](crate::proof_serializers::IoSource).
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{
+    /// #   MerkleTree, MerkleProof, algorithms::Sha256, Hasher, ErrorKind, proof_serializers
+    /// # };
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    /// let merkle_proof = MerkleTree::<Sha256>::from_leaves(&leaves).proof(&[3, 4]);
+    /// let proof_bytes = merkle_proof.serialize::<proof_serializers::DirectHashesOrder>();
+    ///
+    /// let mut source = proof_serializers::IoSource(std::io::Cursor::new(&proof_bytes));
+    /// let proof = MerkleProof::<Sha256>
+    ///     ::deserialize_from::<proof_serializers::DirectHashesOrder, _>(&mut source, 1024)?;
+    /// assert_eq!(proof.proof_hashes(), merkle_proof.proof_hashes());
+    ///
+    /// // The proof has three hashes, so two are over the limit
+    /// let error = MerkleProof::<Sha256>
+    ///     ::deserialize_from::<proof_serializers::DirectHashesOrder, _>(&mut &proof_bytes[..], 64)
+    ///     .err()
+    ///     .map(|error| error.kind());
+    /// assert_eq!(error, Some(ErrorKind::ProofTooLong));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::ProofTooLong`] if the source has more than
+    /// `max_proof_len` bytes of the proof, [`ErrorKind::IoError`] if the source fails, and
+    /// other errors the same as [`MerkleProof::deserialize`] does
+    ///
+    /// [`ErrorKind::ProofTooLong`]: crate::ErrorKind::ProofTooLong
+    /// [`ErrorKind::IoError`]: crate::ErrorKind::IoError
+    pub fn deserialize_from<S: StreamingProofSerializer, R: ProofSource>(
+        source: &mut R,
+        max_proof_len: usize,
+    ) -> Result<Self, Error> {
+        S::deserialize_from(source, max_proof_len)
+    }
 }
 
 impl<T: Hasher> TryFrom<Vec<u8>> for MerkleProof<T> {
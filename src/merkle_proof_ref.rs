@@ -0,0 +1,156 @@
+use crate::{prelude::*, utils, NodeHasher, ProofSizeError};
+#[cfg(feature = "alloc")]
+use crate::{Hasher, MerkleProof};
+use core::convert::TryFrom;
+use core::marker::PhantomData;
+
+/// [`MerkleProofRef`] is a view of a serialized single leaf Merkle proof that borrows the
+/// proof bytes instead of copying them.
+///
+/// Bytes are expected in the same format [`MerkleProof::from_bytes`] takes, i.e. proof hashes
+/// in [`DirectHashesOrder`]. Hashes are read from the bytes only when they are needed.
+/// Unlike [`MerkleProof::root`], which builds a [`PartialTree`], [`MerkleProofRef::root`]
+/// walks from the leaf to the root keeping only the current node, so verification does no heap
+/// allocations, as long as [`NodeHasher::concat_and_hash`] doesn't allocate. The built-in
+/// hashers from the [`algorithms`] module don't. It only needs a [`NodeHasher`], so it's also
+/// available without the `alloc` feature.
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{MerkleTree, MerkleProofRef, algorithms::Sha256, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f"]
+///     .iter()
+///     .map(|x| Sha256::hash(x.as_bytes()))
+///     .collect();
+///
+/// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+/// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+/// let proof_bytes = merkle_tree.proof(&[3]).to_bytes();
+///
+/// let proof = MerkleProofRef::<Sha256>::from_bytes(&proof_bytes)?;
+/// assert!(proof.verify(root, 3, leaves[3], leaves.len()));
+/// assert!(!proof.verify(root, 3, leaves[4], leaves.len()));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`DirectHashesOrder`]: crate::proof_serializers::DirectHashesOrder
+/// [`PartialTree`]: crate::PartialTree
+/// [`algorithms`]: crate::algorithms
+pub struct MerkleProofRef<'a, T: NodeHasher> {
+    proof_bytes: &'a [u8],
+    hasher: PhantomData<T>,
+}
+
+impl<'a, T: NodeHasher> MerkleProofRef<'a, T>
+where
+    T::Hash: TryFrom<&'a [u8]>,
+{
+    /// Creates a view of the proof bytes. Only checks that the bytes can be split into hashes.
+    ///
+    /// ## Errors
+    ///
+    /// Returns a [`ProofSizeError`] if the length of the bytes isn't a multiple of the hash size.
+    /// It converts into an [`Error`] of kind [`ErrorKind::SerializedProofSizeIsIncorrect`].
+    ///
+    /// [`Error`]: crate::Error
+    /// [`ErrorKind::SerializedProofSizeIsIncorrect`]: crate::ErrorKind::SerializedProofSizeIsIncorrect
+    pub fn from_bytes(proof_bytes: &'a [u8]) -> Result<Self, ProofSizeError> {
+        let hash_size = T::hash_size();
+
+        if proof_bytes.len() % hash_size != 0 {
+            return Err(ProofSizeError::new(proof_bytes.len(), hash_size));
+        }
+
+        Ok(Self {
+            proof_bytes,
+            hasher: PhantomData,
+        })
+    }
+
+    /// Returns the number of hashes in the proof
+    pub fn proof_hashes_count(&self) -> usize {
+        self.proof_bytes.len() / T::hash_size()
+    }
+
+    /// Returns the proof hash at the given position, or `None` if the proof has fewer hashes
+    pub fn proof_hash(&self, position: usize) -> Option<T::Hash> {
+        let hash_size = T::hash_size();
+        let start = position.checked_mul(hash_size)?;
+        let bytes = self.proof_bytes.get(start..start.checked_add(hash_size)?)?;
+
+        T::Hash::try_from(bytes).ok()
+    }
+
+    /// Calculates the root of a tree with `total_leaves_count` leaves from the leaf at
+    /// `leaf_index`. Returns `None` if the leaf index is out of range, or if the proof doesn't
+    /// have enough hashes. Extra hashes at the end of the proof are ignored, the same as
+    /// [`MerkleProof::root`] does.
+    pub fn root(
+        &self,
+        leaf_index: usize,
+        leaf_hash: T::Hash,
+        total_leaves_count: usize,
+    ) -> Option<T::Hash> {
+        if leaf_index >= total_leaves_count {
+            return None;
+        }
+
+        let mut node_index = leaf_index;
+        let mut node_hash = leaf_hash;
+        let mut layer_len = total_leaves_count;
+        let mut used_hashes_count = 0;
+
+        // Goes through the same number of layers as the PartialTree does, so custom hashers
+        // that don't propagate a lonely node as it is get the same root
+        for _ in 0..utils::indices::tree_depth(total_leaves_count) {
+            let sibling_index = utils::indices::get_sibling_index(node_index);
+            let sibling_hash = if sibling_index < layer_len {
+                let sibling_hash = self.proof_hash(used_hashes_count)?;
+                used_hashes_count += 1;
+                Some(sibling_hash)
+            } else {
+                None
+            };
+
+            node_hash = match sibling_hash {
+                Some(sibling_hash) if sibling_index < node_index => {
+                    T::concat_and_hash(&sibling_hash, Some(&node_hash))
+                }
+                sibling_hash => T::concat_and_hash(&node_hash, sibling_hash.as_ref()),
+            };
+            node_index /= 2;
+            layer_len = utils::indices::div_ceil(layer_len, 2);
+        }
+
+        Some(node_hash)
+    }
+
+    /// Verifies that the leaf at `leaf_index` is a part of the tree with the given root
+    pub fn verify(
+        &self,
+        root: T::Hash,
+        leaf_index: usize,
+        leaf_hash: T::Hash,
+        total_leaves_count: usize,
+    ) -> bool {
+        self.root(leaf_index, leaf_hash, total_leaves_count) == Some(root)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T: Hasher> MerkleProofRef<'a, T>
+where
+    T::Hash: TryFrom<&'a [u8]>,
+{
+    /// Copies the proof hashes into an owned [`MerkleProof`]
+    pub fn to_proof(&self) -> MerkleProof<T> {
+        let proof_hashes: Vec<T::Hash> = (0..self.proof_hashes_count())
+            .filter_map(|position| self.proof_hash(position))
+            .collect();
+
+        MerkleProof::new(proof_hashes)
+    }
+}
@@ -1,5 +1,24 @@
 use crate::prelude::*;
-use crate::{partial_tree::PartialTree, utils, utils::indices, Hasher, MerkleProof};
+use crate::{
+    partial_tree::PartialTree, utils, utils::indices, Error, Hasher, MerkleProof, Padding,
+};
+use alloc::collections::BTreeMap;
+use core::ops::Range;
+
+/// Maps serialized leaf hashes to all positions they occupy in the tree. Hashes are keyed by
+/// their byte representation, since [`Hasher::Hash`] isn't required to be `Ord`.
+type LeafIndex = BTreeMap<Vec<u8>, Vec<usize>>;
+
+/// A commit saved to the history of a [`MerkleTree`]
+#[derive(Clone)]
+struct Commit<T: Hasher> {
+    /// Nodes changed by the commit
+    diff: PartialTree<T>,
+    /// Number of leaves after the commit
+    leaves_count: usize,
+    /// `true` if the commit changed existing leaves instead of only appending new ones
+    replaced_leaves: bool,
+}
 
 /// [`MerkleTree`] is a Merkle Tree that is well suited for both basic and advanced usage.
 ///
@@ -12,8 +31,10 @@ use crate::{partial_tree::PartialTree, utils, utils::indices, Hasher, MerkleProo
 #[derive(Clone)]
 pub struct MerkleTree<T: Hasher> {
     current_working_tree: PartialTree<T>,
-    history: Vec<PartialTree<T>>,
+    history: Vec<Commit<T>>,
     uncommitted_leaves: Vec<T::Hash>,
+    leaf_index: Option<LeafIndex>,
+    padding: Option<Padding<T>>,
 }
 
 impl<T: Hasher> Default for MerkleTree<T> {
@@ -39,6 +60,8 @@ impl<T: Hasher> MerkleTree<T> {
             current_working_tree: PartialTree::new(),
             history: Vec::new(),
             uncommitted_leaves: Vec::new(),
+            leaf_index: None,
+            padding: None,
         }
     }
 
@@ -66,6 +89,191 @@ impl<T: Hasher> MerkleTree<T> {
         tree
     }
 
+    /// Enables an index from leaf hashes to their positions in the tree. The index is built
+    /// from the committed leaves and is kept up to date by [`MerkleTree::commit`] and
+    /// [`MerkleTree::rollback`]. It speeds up [`MerkleTree::position_of`],
+    /// [`MerkleTree::contains_leaf`] and [`MerkleTree::proof_for_leaves`], which otherwise have
+    /// to scan all the leaves.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, algorithms::Sha256, Hasher};
+    /// let leaves = [
+    ///     Sha256::hash("a".as_bytes()),
+    ///     Sha256::hash("b".as_bytes()),
+    ///     Sha256::hash("c".as_bytes()),
+    /// ];
+    ///
+    /// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves).with_leaf_index();
+    /// assert_eq!(merkle_tree.position_of(&leaves[2]), Some(2));
+    /// ```
+    pub fn with_leaf_index(mut self) -> Self {
+        self.leaf_index = Some(self.build_leaf_index());
+        self
+    }
+
+    /// Pads the leaves of the tree with a filler leaf, to the next power of two or to a fixed
+    /// limit, see [`Padding`]. The filler leaves aren't stored, and the tree is rebuilt with the
+    /// padding, history included, so it can still be rolled back.
+    ///
+    /// Proofs of a padded tree don't contain the padding nodes, since anyone who knows the
+    /// padding can calculate them. They are verified with [`MerkleProof::verify_padded`].
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::TreeIsFull`] if the tree, uncommitted leaves
+    /// included, has more leaves than the padding limit
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, Padding, algorithms::Sha256, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    ///
+    /// let padding = Padding::<Sha256>::to_limit([0; 32], 1024)?;
+    /// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves).with_padding(padding.clone())?;
+    /// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+    ///
+    /// let proof = merkle_tree.proof(&[2]);
+    /// assert!(proof.verify_padded(root, &[2], &[leaves[2]], leaves.len(), &padding));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ErrorKind::TreeIsFull`]: crate::ErrorKind::TreeIsFull
+    /// [`MerkleProof::verify_padded`]: crate::MerkleProof::verify_padded
+    pub fn with_padding(mut self, padding: Padding<T>) -> Result<Self, Error> {
+        padding.padded_leaves_count(self.leaves_len() + self.uncommitted_leaves.len())?;
+
+        // Number of leaves after each commit, to replay the commits with the padding
+        let mut commit_leaves_counts = Vec::with_capacity(self.history.len());
+        let mut replayed_tree = PartialTree::<T>::new();
+        for commit in &self.history {
+            replayed_tree.merge_unverified(commit.diff.clone());
+            commit_leaves_counts.push(commit.leaves_count);
+        }
+
+        let leaves = self.leaves().unwrap_or_default();
+        let uncommitted_leaves = core::mem::take(&mut self.uncommitted_leaves);
+        let leaf_index = self.leaf_index.take();
+        self.current_working_tree.clear();
+        self.history.clear();
+        self.padding = Some(padding);
+
+        let mut committed_leaves_count = 0;
+        for leaves_count in commit_leaves_counts {
+            self.uncommitted_leaves = leaves[committed_leaves_count..leaves_count].to_vec();
+            self.commit();
+            committed_leaves_count = leaves_count;
+        }
+
+        self.uncommitted_leaves = uncommitted_leaves;
+        self.leaf_index = leaf_index;
+        Ok(self)
+    }
+
+    /// Returns the padding of the tree, if it was set with [`MerkleTree::with_padding`]
+    pub fn padding(&self) -> Option<&Padding<T>> {
+        self.padding.as_ref()
+    }
+
+    /// Returns the position of a committed leaf in the tree. If the same hash was inserted more
+    /// than once, the lowest position is returned. Uses the leaf index if it was enabled
+    /// with [`MerkleTree::with_leaf_index`], otherwise scans the leaves.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, algorithms::Sha256, Hasher};
+    /// let leaves = [
+    ///     Sha256::hash("a".as_bytes()),
+    ///     Sha256::hash("b".as_bytes()),
+    ///     Sha256::hash("c".as_bytes()),
+    /// ];
+    ///
+    /// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+    /// assert_eq!(merkle_tree.position_of(&leaves[1]), Some(1));
+    /// assert_eq!(merkle_tree.position_of(&Sha256::hash("d".as_bytes())), None);
+    /// ```
+    pub fn position_of(&self, leaf: &T::Hash) -> Option<usize> {
+        match &self.leaf_index {
+            Some(leaf_index) => {
+                let key: Vec<u8> = (*leaf).into();
+                leaf_index.get(&key)?.first().cloned()
+            }
+            None => self
+                .leaves_tuples()?
+                .iter()
+                .find(|(_, hash)| hash == leaf)
+                .map(|(index, _)| *index),
+        }
+    }
+
+    /// Returns `true` if the hash is one of the committed leaves of the tree.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, algorithms::Sha256, Hasher};
+    /// let leaves = [Sha256::hash("a".as_bytes()), Sha256::hash("b".as_bytes())];
+    ///
+    /// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves).with_leaf_index();
+    /// assert!(merkle_tree.contains_leaf(&leaves[0]));
+    /// assert!(!merkle_tree.contains_leaf(&Sha256::hash("c".as_bytes())));
+    /// ```
+    pub fn contains_leaf(&self, leaf: &T::Hash) -> bool {
+        self.position_of(leaf).is_some()
+    }
+
+    /// Looks up the positions of the given leaves and returns them together with the Merkle
+    /// proof for them. Returns `None` if any of the leaves is not in the tree or if a leaf is
+    /// passed more than once, since a proof can't have a duplicate index. The returned
+    /// indices follow the order of the `leaves` argument and can be passed to
+    /// [`MerkleProof::verify`] along with the leaves.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, algorithms::Sha256, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    ///
+    /// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves).with_leaf_index();
+    /// let leaves_to_prove = [leaves[4], leaves[1]];
+    /// let (indices, proof) = merkle_tree
+    ///     .proof_for_leaves(&leaves_to_prove)
+    ///     .ok_or("leaves are not in the tree")?;
+    /// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+    ///
+    /// assert_eq!(indices, vec![4, 1]);
+    /// assert!(proof.verify(root, &indices, &leaves_to_prove, leaves.len()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn proof_for_leaves(&self, leaves: &[T::Hash]) -> Option<(Vec<usize>, MerkleProof<T>)> {
+        let indices = leaves
+            .iter()
+            .map(|leaf| self.position_of(leaf))
+            .collect::<Option<Vec<usize>>>()?;
+
+        let mut sorted_indices = indices.clone();
+        sorted_indices.sort_unstable();
+        sorted_indices.dedup();
+        if sorted_indices.len() != indices.len() {
+            return None;
+        }
+
+        Some((indices, self.proof(&sorted_indices)))
+    }
+
     /// Returns the tree root - the top hash of the tree. Used in the inclusion proof verification.
     ///
     /// ## Examples
@@ -93,7 +301,11 @@ impl<T: Hasher> MerkleTree<T> {
     /// # }
     /// ```
     pub fn root(&self) -> Option<T::Hash> {
-        Some(self.layer_tuples().last()?.first()?.1)
+        match self.layer_tuples().last().and_then(|layer| layer.first()) {
+            Some((_, root)) => Some(*root),
+            // A padded tree without leaves consists only of padding
+            None => self.padding.as_ref().map(Padding::empty_root),
+        }
     }
 
     /// Similar to [`MerkleTree::root`], but returns a hex encoded string instead of
@@ -129,9 +341,17 @@ impl<T: Hasher> MerkleTree<T> {
     /// Returns helper nodes required to build a partial tree for the given indices
     /// to be able to extract a root from it. Useful in constructing Merkle proofs
     fn helper_nodes(&self, leaf_indices: &[usize]) -> Vec<T::Hash> {
+        Self::collect_helper_nodes(self.layer_tuples(), leaf_indices)
+    }
+
+    /// Flattens helper nodes of the given tree layers into a vector of hashes, layer by layer
+    fn collect_helper_nodes(
+        layers: &[Vec<(usize, T::Hash)>],
+        leaf_indices: &[usize],
+    ) -> Vec<T::Hash> {
         let mut helper_nodes = Vec::<T::Hash>::new();
 
-        for layer in self.helper_node_tuples(leaf_indices) {
+        for layer in Self::layers_helper_node_tuples(layers, leaf_indices) {
             for (_index, hash) in layer {
                 helper_nodes.push(hash)
             }
@@ -143,10 +363,19 @@ impl<T: Hasher> MerkleTree<T> {
     /// Gets all helper nodes required to build a partial merkle tree for the given indices,
     /// cloning all required hashes into the resulting vector.
     fn helper_node_tuples(&self, leaf_indices: &[usize]) -> Vec<Vec<(usize, T::Hash)>> {
+        Self::layers_helper_node_tuples(self.layer_tuples(), leaf_indices)
+    }
+
+    /// Same as [`MerkleTree::helper_node_tuples`], but for arbitrary tree layers, for example
+    /// for a tree with uncommitted changes applied
+    fn layers_helper_node_tuples(
+        layers: &[Vec<(usize, T::Hash)>],
+        leaf_indices: &[usize],
+    ) -> Vec<Vec<(usize, T::Hash)>> {
         let mut current_layer_indices = leaf_indices.to_vec();
         let mut helper_nodes: Vec<Vec<(usize, T::Hash)>> = Vec::new();
 
-        for tree_layer in self.layer_tuples() {
+        for tree_layer in layers {
             let mut helpers_layer = Vec::new();
             let siblings = utils::indices::sibling_indices(&current_layer_indices);
             // Filter all nodes that do not require an additional hash to be calculated
@@ -197,6 +426,256 @@ impl<T: Hasher> MerkleTree<T> {
         MerkleProof::<T>::new(self.helper_nodes(leaf_indices))
     }
 
+    /// Same as [`MerkleTree::proof`], but validates the leaf indices first. Indices may be
+    /// passed in any order; they are sorted before making the proof, so the proof verifies
+    /// with [`MerkleProof::verify`] regardless of the order of indices passed to it.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, algorithms::Sha256, Hasher, ErrorKind};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    ///
+    /// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+    /// let merkle_root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+    ///
+    /// let proof = merkle_tree.try_proof(&[4, 3])?;
+    /// assert!(proof.verify(merkle_root, &[4, 3], &[leaves[4], leaves[3]], leaves.len()));
+    ///
+    /// let error = merkle_tree.try_proof(&[3, 6]).err().ok_or("expected an error")?;
+    /// assert_eq!(error.kind(), ErrorKind::LeafIndexOutOfRange);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`ErrorKind::EmptyTree`] if the tree has no committed leaves,
+    /// [`ErrorKind::EmptyLeafIndices`] if no indices were passed,
+    /// [`ErrorKind::LeafIndexOutOfRange`] if any index doesn't point to a leaf and
+    /// [`ErrorKind::DuplicateLeafIndex`] if an index was passed more than once.
+    ///
+    /// [`ErrorKind::EmptyTree`]: crate::ErrorKind::EmptyTree
+    /// [`ErrorKind::EmptyLeafIndices`]: crate::ErrorKind::EmptyLeafIndices
+    /// [`ErrorKind::LeafIndexOutOfRange`]: crate::ErrorKind::LeafIndexOutOfRange
+    /// [`ErrorKind::DuplicateLeafIndex`]: crate::ErrorKind::DuplicateLeafIndex
+    pub fn try_proof(&self, leaf_indices: &[usize]) -> Result<MerkleProof<T>, Error> {
+        let sorted_indices = indices::sorted_leaf_indices(leaf_indices, self.leaves_len())?;
+        Ok(self.proof(&sorted_indices))
+    }
+
+    /// Returns the Merkle proof for the consecutive leaves in the `range`. The proof contains
+    /// only the siblings of the left and the right boundaries of the range, and is the same as
+    /// the one [`MerkleTree::proof`] returns for all the indices of the range. Range proofs are
+    /// verified with [`MerkleProof::verify_range`], which only needs the first index of the range.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, algorithms::Sha256, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    ///
+    /// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+    /// let merkle_root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+    ///
+    /// let proof = merkle_tree.range_proof(1..5)?;
+    /// assert!(proof.verify_range(merkle_root, 1, &leaves[1..5], leaves.len()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`ErrorKind::EmptyTree`] if the tree has no committed leaves,
+    /// [`ErrorKind::EmptyLeafIndices`] if the range is empty and
+    /// [`ErrorKind::LeafIndexOutOfRange`] if the range ends past the last leaf.
+    ///
+    /// [`ErrorKind::EmptyTree`]: crate::ErrorKind::EmptyTree
+    /// [`ErrorKind::EmptyLeafIndices`]: crate::ErrorKind::EmptyLeafIndices
+    /// [`ErrorKind::LeafIndexOutOfRange`]: crate::ErrorKind::LeafIndexOutOfRange
+    pub fn range_proof(&self, range: Range<usize>) -> Result<MerkleProof<T>, Error> {
+        let leaves_len = self.leaves_len();
+        if leaves_len == 0 {
+            return Err(Error::empty_tree());
+        }
+        if range.is_empty() {
+            return Err(Error::empty_leaf_indices());
+        }
+        if range.end > leaves_len {
+            return Err(Error::leaf_index_out_of_range(range.end - 1, leaves_len));
+        }
+
+        let proof_indices_by_layers =
+            indices::range_proof_indices_by_layers(range.start, range.end, leaves_len);
+        let mut proof_hashes = Vec::new();
+
+        for (tree_layer, proof_indices) in self.layer_tuples().iter().zip(proof_indices_by_layers) {
+            for index in proof_indices {
+                if let Some((_, hash)) = tree_layer.get(index) {
+                    proof_hashes.push(*hash);
+                }
+            }
+        }
+
+        Ok(MerkleProof::<T>::new(proof_hashes))
+    }
+
+    /// Returns hashes a client needs to update a proof of a single leaf made when the tree had
+    /// `old_leaves_count` leaves, so the proof verifies against the current root. Only hashes
+    /// of the nodes that have changed or appeared since then are returned, from bottom to top.
+    /// The update is applied on the client with [`MerkleProof::update`].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, algorithms::Sha256, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    ///
+    /// let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves[..3]);
+    /// let proof = merkle_tree.proof(&[1]);
+    ///
+    /// merkle_tree.append(leaves[3..].to_vec().as_mut()).commit();
+    /// let update_hashes = merkle_tree.proof_update(1, 3)?;
+    /// let updated_proof = proof.update(1, 3, leaves.len(), &update_hashes)?;
+    ///
+    /// let merkle_root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+    /// assert!(updated_proof.verify(merkle_root, &[1], &leaves[1..2], leaves.len()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`ErrorKind::LeavesCountDecreased`] if the tree has less than `old_leaves_count`
+    /// leaves, and [`ErrorKind::LeafIndexOutOfRange`] if the leaf wasn't in the old tree.
+    ///
+    /// [`ErrorKind::LeavesCountDecreased`]: crate::ErrorKind::LeavesCountDecreased
+    /// [`ErrorKind::LeafIndexOutOfRange`]: crate::ErrorKind::LeafIndexOutOfRange
+    pub fn proof_update(
+        &self,
+        leaf_index: usize,
+        old_leaves_count: usize,
+    ) -> Result<Vec<T::Hash>, Error> {
+        let leaves_len = self.leaves_len();
+        if leaves_len < old_leaves_count {
+            return Err(Error::leaves_count_decreased(old_leaves_count, leaves_len));
+        }
+        if leaf_index >= old_leaves_count {
+            return Err(Error::leaf_index_out_of_range(leaf_index, old_leaves_count));
+        }
+
+        let mut update_hashes = Vec::new();
+        let proof_indices_by_layers = indices::proof_indices_by_layers(&[leaf_index], leaves_len);
+
+        for (layer_index, (tree_layer, proof_indices)) in self
+            .layer_tuples()
+            .iter()
+            .zip(proof_indices_by_layers)
+            .enumerate()
+        {
+            for index in proof_indices {
+                if indices::is_node_unchanged(layer_index, index, old_leaves_count, leaves_len) {
+                    continue;
+                }
+                if let Some((_, hash)) = tree_layer.get(index) {
+                    update_hashes.push(*hash);
+                }
+            }
+        }
+
+        Ok(update_hashes)
+    }
+
+    /// Returns the hash of the node at `node_index` of the layer `layer_index`, where layer 0
+    /// is the leaves. The node is the root of the subtree over leaves starting at
+    /// `node_index * 2^layer_index`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, algorithms::Sha256, Hasher};
+    /// let leaves = [Sha256::hash("a".as_bytes()), Sha256::hash("b".as_bytes())];
+    ///
+    /// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+    /// assert_eq!(merkle_tree.node(0, 1), Some(leaves[1]));
+    /// assert_eq!(merkle_tree.node(1, 0), merkle_tree.root());
+    /// assert_eq!(merkle_tree.node(1, 1), None);
+    /// ```
+    pub fn node(&self, layer_index: usize, node_index: usize) -> Option<T::Hash> {
+        let (_, hash) = self.layer_tuples().get(layer_index)?.get(node_index)?;
+        Some(*hash)
+    }
+
+    /// Returns the Merkle proof for an internal node, i.e. for the root of a subtree. The
+    /// proof is made of siblings of the node and its ancestors, and is verified with
+    /// [`MerkleProof::verify_node`]. For nodes of the layer 0 it is the same as the proof
+    /// of a single leaf.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, algorithms::Sha256, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    ///
+    /// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+    /// let merkle_root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+    ///
+    /// // The root of the subtree over leaves "e" and "f"
+    /// let subtree_root = merkle_tree.node(1, 2).ok_or("no such node")?;
+    /// let proof = merkle_tree.node_proof(1, 2)?;
+    ///
+    /// assert!(proof.verify_node(merkle_root, 1, 2, subtree_root, leaves.len()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`ErrorKind::NodeOutOfRange`] if there's no such node in the tree.
+    ///
+    /// [`ErrorKind::NodeOutOfRange`]: crate::ErrorKind::NodeOutOfRange
+    pub fn node_proof(
+        &self,
+        layer_index: usize,
+        node_index: usize,
+    ) -> Result<MerkleProof<T>, Error> {
+        let leaves_len = self.leaves_len();
+        if self.node(layer_index, node_index).is_none() {
+            return Err(Error::node_out_of_range(
+                layer_index,
+                node_index,
+                leaves_len,
+            ));
+        }
+
+        let mut proof_hashes = Vec::new();
+        let mut current_index = node_index;
+        for tree_layer in self.layer_tuples().iter().skip(layer_index) {
+            if let Some((_, hash)) = tree_layer.get(indices::get_sibling_index(current_index)) {
+                proof_hashes.push(*hash);
+            }
+            current_index = indices::parent_index(current_index);
+        }
+
+        Ok(MerkleProof::<T>::new(proof_hashes))
+    }
+
     /// Inserts a new leaf. Please note it won't modify the root just yet; For the changes
     /// to be applied to the root, [`MerkleTree::commit`] method should be called first. To get the
     /// root of the new tree without applying the changes, you can use
@@ -242,11 +721,32 @@ impl<T: Hasher> MerkleTree<T> {
     /// # Ok(())
     /// # }
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tree is padded and is full, see [`MerkleTree::try_insert`]
     pub fn insert(&mut self, leaf: T::Hash) -> &mut Self {
-        self.uncommitted_leaves.push(leaf);
+        if let Err(error) = self.try_insert(leaf) {
+            panic!("{}", error);
+        }
         self
     }
 
+    /// Same as [`MerkleTree::insert`], but returns an error instead of panicking if the tree
+    /// is padded to a limit and already has that many leaves.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::TreeIsFull`] if the tree is full. The leaf is not
+    /// inserted in that case.
+    ///
+    /// [`ErrorKind::TreeIsFull`]: crate::ErrorKind::TreeIsFull
+    pub fn try_insert(&mut self, leaf: T::Hash) -> Result<&mut Self, Error> {
+        self.check_uncommitted_limit(1)?;
+        self.uncommitted_leaves.push(leaf);
+        Ok(self)
+    }
+
     /// Appends leaves to the tree. Behaves similarly to [`MerkleTree::insert`], but for a list of
     /// items. Takes ownership of the elements of the [`std::vec::Vec<T>`],
     /// similarly to [`std::vec::Vec::append`].
@@ -273,11 +773,32 @@ impl<T: Hasher> MerkleTree<T> {
     /// # Ok(())
     /// # }
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tree is padded and the leaves don't fit, see [`MerkleTree::try_append`]
     pub fn append(&mut self, leaves: &mut Vec<T::Hash>) -> &mut Self {
-        self.uncommitted_leaves.append(leaves);
+        if let Err(error) = self.try_append(leaves) {
+            panic!("{}", error);
+        }
         self
     }
 
+    /// Same as [`MerkleTree::append`], but returns an error instead of panicking if the tree
+    /// is padded to a limit and the leaves don't fit.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::TreeIsFull`] if there would be more leaves than
+    /// the padding limit. No leaves are appended in that case.
+    ///
+    /// [`ErrorKind::TreeIsFull`]: crate::ErrorKind::TreeIsFull
+    pub fn try_append(&mut self, leaves: &mut Vec<T::Hash>) -> Result<&mut Self, Error> {
+        self.check_uncommitted_limit(leaves.len())?;
+        self.uncommitted_leaves.append(leaves);
+        Ok(self)
+    }
+
     /// Commits the changes made by [`MerkleTree::insert`] and [`MerkleTree::append`]
     /// and modifies the root.
     /// Commits are saved to the history, so the tree can be rolled back to any previous commit
@@ -310,7 +831,21 @@ impl<T: Hasher> MerkleTree<T> {
     /// ```
     pub fn commit(&mut self) {
         if let Some(diff) = self.uncommitted_diff() {
-            self.history.push(diff.clone());
+            let committed_leaves_count = self.leaves_len();
+            if let Some(leaf_index) = self.leaf_index.as_mut() {
+                for (offset, leaf) in self.uncommitted_leaves.iter().enumerate() {
+                    leaf_index
+                        .entry((*leaf).into())
+                        .or_default()
+                        .push(committed_leaves_count + offset);
+                }
+            }
+
+            self.history.push(Commit {
+                diff: diff.clone(),
+                leaves_count: committed_leaves_count + self.uncommitted_leaves.len(),
+                replaced_leaves: false,
+            });
             self.current_working_tree.merge_unverified(diff);
             self.uncommitted_leaves.clear();
         }
@@ -349,15 +884,73 @@ impl<T: Hasher> MerkleTree<T> {
     /// ```
     pub fn rollback(&mut self) {
         // Remove the most recent commit
-        self.history.pop();
+        let removed_commit = match self.history.pop() {
+            Some(commit) => commit,
+            None => return,
+        };
         // Clear working tree
         self.current_working_tree.clear();
         // Applying all the commits up to the removed one. This is not an
         // efficient way of doing things, but the diff subtraction is not implemented yet on
         // PartialMerkleTree
         for commit in &self.history {
-            self.current_working_tree.merge_unverified(commit.clone());
+            Self::apply_commit(&mut self.current_working_tree, commit);
+        }
+
+        if removed_commit.replaced_leaves {
+            // Positions of any leaf could have changed
+            if self.leaf_index.is_some() {
+                self.leaf_index = Some(self.build_leaf_index());
+            }
+            return;
         }
+
+        // Dropping positions of the leaves that were removed with the commit
+        let leaves_count = self.leaves_len();
+        if let Some(leaf_index) = self.leaf_index.as_mut() {
+            leaf_index.retain(|_, positions| {
+                positions.retain(|position| *position < leaves_count);
+                !positions.is_empty()
+            });
+        }
+    }
+
+    /// Replaces committed leaves and commits the result as a tree of `leaves_count` leaves,
+    /// without rebuilding the leaves that don't change. Leaves are given with their positions
+    /// in the new tree, sorted by position. If the number of leaves changes, every leaf after
+    /// the first changed position has to be replaced. Used by [`AuthenticatedMap`], which
+    /// keeps its leaves ordered by key. Uncommitted leaves are not committed.
+    ///
+    /// [`AuthenticatedMap`]: crate::AuthenticatedMap
+    pub(crate) fn commit_replacing(
+        &mut self,
+        replaced_leaves: &[(usize, T::Hash)],
+        leaves_count: usize,
+    ) {
+        if let Some(diff) = self.replacement_diff(replaced_leaves, leaves_count) {
+            let commit = Commit {
+                diff,
+                leaves_count,
+                replaced_leaves: true,
+            };
+            Self::apply_commit(&mut self.current_working_tree, &commit);
+            self.history.push(commit);
+
+            if self.leaf_index.is_some() {
+                self.leaf_index = Some(self.build_leaf_index());
+            }
+        }
+    }
+
+    /// Calculates the root as if the leaves were replaced with
+    /// [`MerkleTree::commit_replacing`]
+    pub(crate) fn replacement_root(
+        &self,
+        replaced_leaves: &[(usize, T::Hash)],
+        leaves_count: usize,
+    ) -> Option<T::Hash> {
+        let diff = self.replacement_diff(replaced_leaves, leaves_count)?;
+        diff.root().cloned()
     }
 
     /// Calculates the root of the uncommitted changes as if they were committed.
@@ -407,6 +1000,46 @@ impl<T: Hasher> MerkleTree<T> {
         Some(utils::collections::to_hex_string(&root))
     }
 
+    /// Returns the Merkle proof for the given leaves as if the uncommitted changes were
+    /// committed. The proof verifies against [`MerkleTree::uncommitted_root`], and leaf indices
+    /// can point both to committed and uncommitted leaves. Returns `None` if there are no
+    /// uncommitted changes.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{MerkleTree, algorithms::Sha256, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    ///
+    /// let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves[..4]);
+    /// merkle_tree.append(leaves[4..].to_vec().as_mut());
+    ///
+    /// let indices_to_prove = vec![1, 4];
+    /// let leaves_to_prove = [leaves[1], leaves[4]];
+    /// let proof = merkle_tree
+    ///     .uncommitted_proof(&indices_to_prove)
+    ///     .ok_or("no uncommitted changes")?;
+    /// let uncommitted_root = merkle_tree.uncommitted_root().ok_or("no uncommitted changes")?;
+    ///
+    /// assert!(proof.verify(uncommitted_root, &indices_to_prove, &leaves_to_prove, leaves.len()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn uncommitted_proof(&self, leaf_indices: &[usize]) -> Option<MerkleProof<T>> {
+        let diff = self.uncommitted_diff()?;
+        let mut shadow_tree = self.current_working_tree.clone();
+        shadow_tree.merge_unverified(diff);
+
+        Some(MerkleProof::<T>::new(Self::collect_helper_nodes(
+            shadow_tree.layers(),
+            leaf_indices,
+        )))
+    }
+
     /// Clears all uncommitted changes made by [`MerkleTree::insert`] and [`MerkleTree::append`]
     /// operations without applying them to the tree.
     ///
@@ -515,10 +1148,99 @@ impl<T: Hasher> MerkleTree<T> {
         0
     }
 
-    fn leaves_tuples(&self) -> Option<&[(usize, T::Hash)]> {
+    /// Checks that the number of new leaves fits into the padding limit, if there's one
+    fn check_uncommitted_limit(&self, new_leaves_count: usize) -> Result<(), Error> {
+        match &self.padding {
+            Some(padding) => padding
+                .padded_leaves_count(
+                    self.leaves_len() + self.uncommitted_leaves.len() + new_leaves_count,
+                )
+                .map(|_| ()),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn leaves_tuples(&self) -> Option<&[(usize, T::Hash)]> {
         Some(self.layer_tuples().first()?.as_slice())
     }
 
+    /// Returns the leaves inserted since the last commit
+    pub(crate) fn uncommitted_leaves(&self) -> &[T::Hash] {
+        &self.uncommitted_leaves
+    }
+
+    /// Maps the committed leaves to their positions
+    fn build_leaf_index(&self) -> LeafIndex {
+        let mut leaf_index = LeafIndex::new();
+        if let Some(leaves) = self.leaves_tuples() {
+            for (position, leaf) in leaves {
+                leaf_index
+                    .entry((*leaf).into())
+                    .or_default()
+                    .push(*position);
+            }
+        }
+        leaf_index
+    }
+
+    /// Applies a commit to a tree. Nodes that are out of a tree that got smaller are dropped
+    /// first, the nodes that depend on them are replaced by the commit.
+    fn apply_commit(tree: &mut PartialTree<T>, commit: &Commit<T>) {
+        let leaves_count = tree.layers().first().map_or(0, Vec::len);
+        if commit.leaves_count < leaves_count {
+            tree.truncate(commit.leaves_count);
+        }
+        tree.merge_unverified(commit.diff.clone());
+    }
+
+    /// Creates a diff that replaces the leaves, see [`MerkleTree::commit_replacing`]. Helper
+    /// nodes are taken from the committed tree: they are siblings of the changed nodes, so
+    /// none of their leaves is replaced or moved.
+    fn replacement_diff(
+        &self,
+        replaced_leaves: &[(usize, T::Hash)],
+        leaves_count: usize,
+    ) -> Option<PartialTree<T>> {
+        if leaves_count == 0 {
+            return Some(PartialTree::new());
+        }
+
+        let mut leaves = replaced_leaves.to_vec();
+        // Ancestors of the last leaf cover fewer leaves if the tree got smaller. A diff that
+        // doesn't replace anything is made of the path of the last leaf as well.
+        let last_position = leaves_count - 1;
+        if (leaves_count < self.leaves_len() || leaves.is_empty())
+            && leaves.last().map(|(index, _)| *index) != Some(last_position)
+        {
+            leaves.push((last_position, self.node(0, last_position)?));
+        }
+
+        let tree_depth = utils::indices::tree_depth(leaves_count);
+        let mut current_layer_indices: Vec<usize> =
+            leaves.iter().map(|(index, _)| *index).collect();
+        let mut partial_layers = vec![leaves];
+        let mut layer_len = leaves_count;
+
+        for layer_index in 0..tree_depth {
+            let mut helpers_layer = Vec::new();
+            for index in &current_layer_indices {
+                let sibling = utils::indices::get_sibling_index(*index);
+                if sibling < layer_len && current_layer_indices.binary_search(&sibling).is_err() {
+                    helpers_layer.push((sibling, self.node(layer_index, sibling)?));
+                }
+            }
+
+            match partial_layers.get_mut(layer_index) {
+                Some(layer) => layer.append(&mut helpers_layer),
+                None => partial_layers.push(helpers_layer),
+            }
+            current_layer_indices = indices::parent_indices(&current_layer_indices);
+            layer_len = utils::indices::div_ceil(layer_len, 2);
+        }
+
+        PartialTree::<T>::build(partial_layers, tree_depth).ok()
+    }
+
     /// Returns the whole tree, where the first layer is leaves and
     /// consequent layers are nodes.
     fn layers(&self) -> Vec<Vec<T::Hash>> {
@@ -555,17 +1277,21 @@ impl<T: Hasher> MerkleTree<T> {
 
         // Figuring what tree height would be if we've committed the changes
         let leaves_in_new_tree = self.leaves_len() + self.uncommitted_leaves.len();
-        let uncommitted_tree_depth = utils::indices::tree_depth(leaves_in_new_tree);
+        let (uncommitted_tree_depth, zero_hashes) = match &self.padding {
+            Some(padding) => padding.tree_shape(leaves_in_new_tree).ok()?,
+            None => (utils::indices::tree_depth(leaves_in_new_tree), Vec::new()),
+        };
 
         match partial_tree_tuples.first_mut() {
             Some(first_layer) => {
                 first_layer.append(&mut shadow_node_tuples);
-                first_layer.sort_by(|(a, _), (b, _)| a.cmp(b));
+                first_layer.sort_by_key(|(index, _)| *index);
             }
             None => partial_tree_tuples.push(shadow_node_tuples),
         }
 
         // Building a partial tree with the changes that would be needed to the working tree
-        PartialTree::<T>::build(partial_tree_tuples, uncommitted_tree_depth).ok()
+        PartialTree::<T>::build_padded(partial_tree_tuples, uncommitted_tree_depth, &zero_hashes)
+            .ok()
     }
 }
@@ -0,0 +1,129 @@
+use crate::prelude::*;
+use crate::{Hasher, MerkleProof};
+
+/// [`NonMembershipProof`] proves that a leaf is not in a tree that keeps its leaves sorted,
+/// see [`SortedMerkleTree`].
+///
+/// The proof consists of the two adjacent leaves that bracket the absent leaf, their indices
+/// and a [`MerkleProof`] for both of them. If the absent leaf is less than the first leaf or
+/// greater than the last one, only the first or the last leaf is included.
+///
+/// Made with [`SortedMerkleTree::non_membership_proof`].
+///
+/// [`SortedMerkleTree`]: crate::SortedMerkleTree
+/// [`SortedMerkleTree::non_membership_proof`]: crate::SortedMerkleTree::non_membership_proof
+pub struct NonMembershipProof<T: Hasher> {
+    left: Option<(usize, T::Hash)>,
+    right: Option<(usize, T::Hash)>,
+    proof: MerkleProof<T>,
+    total_leaves_count: usize,
+}
+
+impl<T: Hasher> NonMembershipProof<T> {
+    /// Makes a proof from the index and the hash of the neighbours, a proof for them and the
+    /// number of leaves in the tree
+    pub fn new(
+        left: Option<(usize, T::Hash)>,
+        right: Option<(usize, T::Hash)>,
+        proof: MerkleProof<T>,
+        total_leaves_count: usize,
+    ) -> Self {
+        Self {
+            left,
+            right,
+            proof,
+            total_leaves_count,
+        }
+    }
+
+    /// Returns the index and the hash of the greatest leaf that is less than the absent leaf,
+    /// or `None` if the absent leaf is less than all the leaves
+    pub fn left(&self) -> Option<(usize, T::Hash)> {
+        self.left
+    }
+
+    /// Returns the index and the hash of the least leaf that is greater than the absent leaf,
+    /// or `None` if the absent leaf is greater than all the leaves
+    pub fn right(&self) -> Option<(usize, T::Hash)> {
+        self.right
+    }
+
+    /// Returns the proof for the neighbours
+    pub fn proof(&self) -> &MerkleProof<T> {
+        &self.proof
+    }
+
+    /// Returns the number of leaves in the tree the proof was made for
+    pub fn total_leaves_count(&self) -> usize {
+        self.total_leaves_count
+    }
+
+    /// Verifies that the leaf is not in the sorted tree with the given root and number of
+    /// leaves. Checks that the neighbours are adjacent leaves of the tree, or the first or the
+    /// last leaf if there's only one neighbour, and that the leaf is strictly between them.
+    ///
+    /// The number of leaves has to come from the verifier, the same as the root: a proof can
+    /// claim any tree size, and a tree of one leaf can have any hash as the leaf. The proof
+    /// for the neighbours is verified with [`MerkleProof::verify_strict`].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{SortedMerkleTree, algorithms::Sha256, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let leaves = [[1u8; 32], [3u8; 32], [5u8; 32]];
+    ///
+    /// let merkle_tree = SortedMerkleTree::<Sha256>::from_leaves(&leaves)?;
+    /// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+    ///
+    /// let proof = merkle_tree.non_membership_proof(&[4u8; 32])?;
+    /// assert_eq!(proof.left(), Some((1, leaves[1])));
+    /// assert_eq!(proof.right(), Some((2, leaves[2])));
+    /// assert!(proof.verify(root, &[4u8; 32], leaves.len()));
+    ///
+    /// let proof = merkle_tree.non_membership_proof(&[6u8; 32])?;
+    /// assert_eq!(proof.right(), None);
+    /// assert!(proof.verify(root, &[6u8; 32], leaves.len()));
+    /// assert!(!proof.verify(root, &[6u8; 32], leaves.len() + 1));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn verify(&self, root: T::Hash, leaf: &T::Hash, total_leaves_count: usize) -> bool {
+        if !self.verify_neighbours(root, total_leaves_count) {
+            return false;
+        }
+
+        let leaf: Vec<u8> = (*leaf).into();
+        self.left
+            .map_or(true, |(_, left)| Into::<Vec<u8>>::into(left) < leaf)
+            && self
+                .right
+                .map_or(true, |(_, right)| leaf < Into::<Vec<u8>>::into(right))
+    }
+
+    /// Verifies that the neighbours are adjacent leaves of the tree with the given root and
+    /// number of leaves, without looking at what's between them. Trees that order leaves by
+    /// something else than the leaf hash check the bracketing themselves.
+    pub(crate) fn verify_neighbours(&self, root: T::Hash, total_leaves_count: usize) -> bool {
+        if self.total_leaves_count != total_leaves_count {
+            return false;
+        }
+
+        let adjacent = match (self.left, self.right) {
+            (Some((left_index, _)), Some((right_index, _))) => {
+                left_index.checked_add(1) == Some(right_index)
+            }
+            (None, Some((right_index, _))) => right_index == 0,
+            (Some((left_index, _)), None) => left_index.checked_add(1) == Some(total_leaves_count),
+            (None, None) => false,
+        };
+        if !adjacent {
+            return false;
+        }
+
+        let (indices, hashes): (Vec<usize>, Vec<T::Hash>) =
+            self.left.iter().chain(self.right.iter()).copied().unzip();
+        self.proof
+            .verify_strict(root, &indices, &hashes, total_leaves_count)
+    }
+}
@@ -0,0 +1,131 @@
+use crate::prelude::*;
+use crate::{utils, Error, Hasher};
+
+/// [`Padding`] makes a tree a perfect binary tree by padding its leaves with a filler leaf,
+/// either to the next power of two or to a fixed limit. This is the shape SSZ
+/// `hash_tree_root` and many zk circuits expect.
+///
+/// The padding is virtual: filler leaves are never stored. A node whose subtree has only
+/// filler leaves is the root of a perfect tree of filler leaves, so there are only as many
+/// distinct padding nodes as there are layers, see [`Padding::zero_hashes`].
+///
+/// Used by [`MerkleTree::with_padding`] and [`MerkleProof::verify_padded`].
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{MerkleTree, Padding, algorithms::Sha256, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let leaves: Vec<[u8; 32]> = ["a", "b", "c"]
+///     .iter()
+///     .map(|x| Sha256::hash(x.as_bytes()))
+///     .collect();
+///
+/// let merkle_tree =
+///     MerkleTree::<Sha256>::from_leaves(&leaves).with_padding(Padding::to_power_of_two([0; 32]))?;
+/// let padded_tree = MerkleTree::<Sha256>::from_leaves(&[leaves[0], leaves[1], leaves[2], [0; 32]]);
+///
+/// assert_eq!(merkle_tree.root(), padded_tree.root());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`MerkleTree::with_padding`]: crate::MerkleTree::with_padding
+/// [`MerkleProof::verify_padded`]: crate::MerkleProof::verify_padded
+#[derive(Clone)]
+pub struct Padding<T: Hasher> {
+    filler_leaf: T::Hash,
+    limit: Option<usize>,
+}
+
+impl<T: Hasher> Padding<T> {
+    /// Pads the leaves with `filler_leaf` up to the next power of two. A tree of a single leaf
+    /// isn't padded.
+    pub fn to_power_of_two(filler_leaf: T::Hash) -> Self {
+        Self {
+            filler_leaf,
+            limit: None,
+        }
+    }
+
+    /// Pads the leaves with `filler_leaf` up to `limit` rounded up to a power of two, so trees
+    /// with a different number of leaves have the same depth. A tree can't have more leaves
+    /// than `limit`.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidDepth`] if `limit` can't be rounded up to
+    /// a power of two on this platform
+    ///
+    /// [`ErrorKind::InvalidDepth`]: crate::ErrorKind::InvalidDepth
+    pub fn to_limit(filler_leaf: T::Hash, limit: usize) -> Result<Self, Error> {
+        if limit.checked_next_power_of_two().is_none() {
+            return Err(Error::padding_limit_too_large(limit));
+        }
+
+        Ok(Self {
+            filler_leaf,
+            limit: Some(limit),
+        })
+    }
+
+    /// Returns the leaf empty positions are filled with
+    pub fn filler_leaf(&self) -> T::Hash {
+        self.filler_leaf
+    }
+
+    /// Returns the maximum number of leaves, or `None` if the leaves are padded to the next
+    /// power of two
+    pub fn limit(&self) -> Option<usize> {
+        self.limit
+    }
+
+    /// Returns the number of leaves of a tree of `leaves_count` leaves after padding
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::TreeIsFull`] if there are more leaves than the
+    /// limit
+    ///
+    /// [`ErrorKind::TreeIsFull`]: crate::ErrorKind::TreeIsFull
+    pub fn padded_leaves_count(&self, leaves_count: usize) -> Result<usize, Error> {
+        match self.limit {
+            Some(limit) if leaves_count > limit => Err(Error::tree_is_full(limit as u64)),
+            Some(limit) => Ok(limit.next_power_of_two()),
+            None => Ok(leaves_count.max(1).next_power_of_two()),
+        }
+    }
+
+    /// Returns the roots of the perfect trees of filler leaves of heights from 0 to
+    /// `depth - 1`, the first one being the filler leaf itself
+    pub fn zero_hashes(&self, depth: usize) -> Vec<T::Hash> {
+        let mut zero_hashes = Vec::with_capacity(depth);
+        let mut zero_hash = self.filler_leaf;
+        for _ in 0..depth {
+            zero_hashes.push(zero_hash);
+            zero_hash = T::concat_and_hash(&zero_hash, Some(&zero_hash));
+        }
+        zero_hashes
+    }
+
+    /// Returns the root of a tree without leaves, which consists only of padding
+    pub fn empty_root(&self) -> T::Hash {
+        // There can't be more than `limit` leaves in a tree without leaves
+        let padded_leaves_count = self.padded_leaves_count(0).unwrap_or(1);
+        let depth = padded_leaves_count.trailing_zeros() as usize;
+        let zero_hashes = self.zero_hashes(depth + 1);
+        zero_hashes[depth]
+    }
+
+    /// Returns the depth to build a [`PartialTree`] of `leaves_count` padded leaves with, and
+    /// the padding nodes of its layers
+    ///
+    /// [`PartialTree`]: crate::PartialTree
+    pub(crate) fn tree_shape(&self, leaves_count: usize) -> Result<(usize, Vec<T::Hash>), Error> {
+        let padded_leaves_count = self.padded_leaves_count(leaves_count)?;
+        Ok((
+            utils::indices::tree_depth(padded_leaves_count),
+            self.zero_hashes(padded_leaves_count.trailing_zeros() as usize),
+        ))
+    }
+}
@@ -39,7 +39,80 @@ impl<T: Hasher> PartialTree<T> {
     }
 
     pub fn build(partial_layers: Vec<Vec<(usize, T::Hash)>>, depth: usize) -> Result<Self, Error> {
-        let layers = Self::build_tree(partial_layers, depth)?;
+        let layers = Self::build_tree(partial_layers, depth, &[])?;
+        Ok(Self { layers })
+    }
+
+    /// Same as [`PartialTree::build`], but for a tree padded with filler leaves, see
+    /// [`Padding`]. A node without a right sibling is hashed with the padding node of its
+    /// layer, `zero_hashes[layer_index]`, instead of being moved to the next layer as is.
+    /// Layers above the last padding node are built as usual.
+    ///
+    /// [`Padding`]: crate::Padding
+    pub fn build_padded(
+        partial_layers: Vec<Vec<(usize, T::Hash)>>,
+        depth: usize,
+        zero_hashes: &[T::Hash],
+    ) -> Result<Self, Error> {
+        let layers = Self::build_tree(partial_layers, depth, zero_hashes)?;
+        Ok(Self { layers })
+    }
+
+    /// Same as [`PartialTree::build`], but for a tree of `leaves_count` leaves where every node
+    /// has up to `arity` children. Children are hashed with [`Hasher::concat_and_hash_children`].
+    /// Every node that has a known child must have all its children known, otherwise an error
+    /// is returned.
+    pub fn build_with_arity(
+        partial_layers: Vec<Vec<(usize, T::Hash)>>,
+        leaves_count: usize,
+        arity: usize,
+    ) -> Result<Self, Error> {
+        let mut layers: Vec<PartialTreeLayer<T::Hash>> = Vec::new();
+        let mut current_layer: PartialTreeLayer<T::Hash> = Vec::new();
+        let mut layer_len = leaves_count;
+
+        // Reversing helper nodes, so we can remove one layer starting from 0 each iteration
+        let mut reversed_layers: Vec<Vec<(usize, T::Hash)>> =
+            partial_layers.into_iter().rev().collect();
+
+        for layer_index in 0..utils::indices::tree_depth_with_arity(leaves_count, arity) {
+            if let Some(mut nodes) = reversed_layers.pop() {
+                current_layer.append(&mut nodes);
+            }
+            current_layer.sort_by_key(|(index, _)| *index);
+
+            let mut parent_layer = Vec::new();
+            let mut position = 0;
+            while let Some((index, _)) = current_layer.get(position) {
+                if *index >= layer_len {
+                    return Err(Error::node_out_of_range(layer_index, *index, leaves_count));
+                }
+                let siblings = utils::indices::sibling_group_with_arity(*index, arity, layer_len);
+                let children = current_layer
+                    .get(position..position + siblings.len())
+                    .ok_or_else(Error::not_enough_helper_nodes)?;
+                if !children.iter().map(|(index, _)| *index).eq(siblings) {
+                    return Err(Error::not_enough_helper_nodes());
+                }
+
+                let hashes: Vec<T::Hash> = children.iter().map(|(_, hash)| *hash).collect();
+                parent_layer.push((
+                    utils::indices::parent_index_with_arity(*index, arity),
+                    T::concat_and_hash_children(&hashes),
+                ));
+                position += children.len();
+            }
+
+            layers.push(core::mem::replace(&mut current_layer, parent_layer));
+            layer_len = utils::indices::div_ceil(layer_len, arity);
+        }
+
+        // A tree of a single leaf has no layers above the leaves
+        if let Some(mut nodes) = reversed_layers.pop() {
+            current_layer.append(&mut nodes);
+        }
+        layers.push(current_layer);
+
         Ok(Self { layers })
     }
 
@@ -49,6 +122,7 @@ impl<T: Hasher> PartialTree<T> {
     fn build_tree(
         mut partial_layers: Vec<Vec<(usize, T::Hash)>>,
         full_tree_depth: usize,
+        zero_hashes: &[T::Hash],
     ) -> Result<Vec<PartialTreeLayer<T::Hash>>, Error> {
         let mut partial_tree: Vec<Vec<(usize, T::Hash)>> = Vec::new();
         let mut current_layer = Vec::new();
@@ -63,12 +137,12 @@ impl<T: Hasher> PartialTree<T> {
         // It is iterating to full_tree_depth instead of partial_layers.len to address the case
         // of applying changes to a tree when tree requires a resize, and partial layer len
         // in that case going to be lower that the resulting tree depth
-        for _ in 0..full_tree_depth {
+        for layer_index in 0..full_tree_depth {
             // Appending helper nodes to the current known nodes
             if let Some(mut nodes) = reversed_layers.pop() {
                 current_layer.append(&mut nodes);
             }
-            current_layer.sort_by(|(a, _), (b, _)| a.cmp(b));
+            current_layer.sort_by_key(|(index, _)| *index);
 
             // Adding partial layer to the tree
             partial_tree.push(current_layer.clone());
@@ -82,7 +156,12 @@ impl<T: Hasher> PartialTree<T> {
                     // Populate `current_layer` back for the next iteration
                     Some(left_node) => current_layer.push((
                         *parent_node_index,
-                        T::concat_and_hash(left_node, nodes.get(i * 2 + 1)),
+                        T::concat_and_hash(
+                            left_node,
+                            nodes
+                                .get(i * 2 + 1)
+                                .or_else(|| zero_hashes.get(layer_index)),
+                        ),
                     )),
                     None => return Err(Error::not_enough_helper_nodes()),
                 }
@@ -144,7 +223,7 @@ impl<T: Hasher> PartialTree<T> {
                 combined_layer.append(&mut cloned_other_layer);
             }
 
-            combined_layer.sort_by(|(a, _), (b, _)| a.cmp(b));
+            combined_layer.sort_by_key(|(index, _)| *index);
             self.upsert_layer(layer_index, combined_layer);
         }
     }
@@ -175,6 +254,23 @@ impl<T: Hasher> PartialTree<T> {
         &self.layers
     }
 
+    /// Drops the nodes that are out of a tree of `leaves_count` leaves. Nodes that depend on
+    /// the dropped ones are kept as is, so they have to be replaced afterwards.
+    pub(crate) fn truncate(&mut self, leaves_count: usize) {
+        if leaves_count == 0 {
+            self.layers.clear();
+            return;
+        }
+
+        self.layers
+            .truncate(utils::indices::tree_depth(leaves_count) + 1);
+        let mut layer_len = leaves_count;
+        for layer in self.layers.iter_mut() {
+            layer.retain(|(index, _)| *index < layer_len);
+            layer_len = utils::indices::div_ceil(layer_len, 2);
+        }
+    }
+
     /// Clears all elements in the ree
     pub fn clear(&mut self) {
         self.layers.clear();
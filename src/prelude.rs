@@ -1,9 +1,13 @@
 pub use core::prelude::v1::*;
 
+#[cfg(feature = "alloc")]
 pub use alloc::string::{String, ToString};
+#[cfg(feature = "alloc")]
 pub use alloc::vec::Vec;
 
+#[cfg(feature = "alloc")]
 pub use alloc::format;
+#[cfg(feature = "alloc")]
 pub use alloc::vec;
 
 // Those are exported by default in the std prelude in Rust 2021
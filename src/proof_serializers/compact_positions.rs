@@ -0,0 +1,295 @@
+use crate::{
+    prelude::*,
+    proof_serializers::{
+        BoundedReader, DirectHashesOrder, ProofSink, ProofSource, StreamingProofSerializer,
+    },
+    utils, Error, Hasher, MerkleProof, MerkleProofSerializer,
+};
+
+/// [`CompactProof`] is a [`MerkleProof`] together with positions of the proven leaves and the
+/// size of the tree. It is what [`CompactPositions`] serializes, so the receiver can verify the
+/// proof without getting the leaf indices separately.
+///
+/// Positions come with the proof, so the verifier trusts whoever made the proof to say where
+/// the leaves are. Verify the proof with [`MerkleProof::verify`] and indices known to the
+/// verifier when it matters which positions are proven.
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{MerkleTree, algorithms::Sha256, Hasher};
+/// # use rs_merkle::proof_serializers::{CompactPositions, CompactProof};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f"]
+///     .iter()
+///     .map(|x| Sha256::hash(x.as_bytes()))
+///     .collect();
+///
+/// let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+/// let merkle_root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+/// let proof = CompactProof::new(merkle_tree.proof(&[3, 4]), &[3, 4], leaves.len())?;
+/// let proof_bytes = CompactPositions::serialize(&proof);
+///
+/// // The client doesn't need to know indices of the leaves or the tree size
+/// let proof = CompactPositions::deserialize::<Sha256>(&proof_bytes)?;
+/// assert!(proof.verify(merkle_root, &leaves[3..5]));
+/// # Ok(())
+/// # }
+/// ```
+pub struct CompactProof<T: Hasher> {
+    proof: MerkleProof<T>,
+    leaf_indices: Vec<usize>,
+    total_leaves_count: usize,
+}
+
+impl<T: Hasher> CompactProof<T> {
+    /// Attaches indices of the proven leaves and the size of the tree to the proof. Indices
+    /// are stored in ascending order.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::EmptyTree`] if `total_leaves_count` is zero,
+    /// [`ErrorKind::EmptyLeafIndices`] if no indices were passed,
+    /// [`ErrorKind::DuplicateLeafIndex`] if an index was passed more than once and
+    /// [`ErrorKind::LeafIndexOutOfRange`] if an index is not less than `total_leaves_count`
+    ///
+    /// [`ErrorKind::EmptyTree`]: crate::ErrorKind::EmptyTree
+    /// [`ErrorKind::EmptyLeafIndices`]: crate::ErrorKind::EmptyLeafIndices
+    /// [`ErrorKind::DuplicateLeafIndex`]: crate::ErrorKind::DuplicateLeafIndex
+    /// [`ErrorKind::LeafIndexOutOfRange`]: crate::ErrorKind::LeafIndexOutOfRange
+    pub fn new(
+        proof: MerkleProof<T>,
+        leaf_indices: &[usize],
+        total_leaves_count: usize,
+    ) -> Result<Self, Error> {
+        let leaf_indices = utils::indices::sorted_leaf_indices(leaf_indices, total_leaves_count)?;
+        Ok(Self {
+            proof,
+            leaf_indices,
+            total_leaves_count,
+        })
+    }
+
+    /// Returns the proof without the leaf positions
+    pub fn proof(&self) -> &MerkleProof<T> {
+        &self.proof
+    }
+
+    /// Returns indices of the proven leaves in ascending order
+    pub fn leaf_indices(&self) -> &[usize] {
+        &self.leaf_indices
+    }
+
+    /// Returns the number of leaves of the tree the proof was made for
+    pub fn total_leaves_count(&self) -> usize {
+        self.total_leaves_count
+    }
+
+    /// Drops the leaf positions and returns the proof
+    pub fn into_proof(self) -> MerkleProof<T> {
+        self.proof
+    }
+
+    /// Verifies the proof with the attached leaf positions, see [`MerkleProof::verify_strict`].
+    /// Leaf hashes must be in the same order as [`CompactProof::leaf_indices`].
+    pub fn verify(&self, root: T::Hash, leaf_hashes: &[T::Hash]) -> bool {
+        self.proof.verify_strict(
+            root,
+            &self.leaf_indices,
+            leaf_hashes,
+            self.total_leaves_count,
+        )
+    }
+}
+
+/// Serializes [`CompactProof`]s: proofs together with positions of the proven leaves and the
+/// tree size. Positions take a few bytes, and the number of proof hashes follows from them,
+/// so the encoding is barely larger than the hashes alone.
+///
+/// The layout is:
+/// - the tree size, as a varint;
+/// - the number of proven leaves, as a varint;
+/// - leaf indices in ascending order as varints, each but the first one encoded as the gap
+///   from the previous index;
+/// - proof hashes, in the same order as [`DirectHashesOrder`] writes them.
+///
+/// Varints are unsigned LEB128 in their shortest form.
+///
+/// Which nodes of the partial tree are proof hashes and which are calculated follows from the
+/// leaf positions and the tree size, so the layout has no bitmap of them. Unlike
+/// [`DirectHashesOrder`] and [`ReverseHashesOrder`], it doesn't implement
+/// [`MerkleProofSerializer`]: a [`MerkleProof`] doesn't know the positions of its leaves, so it
+/// serializes [`CompactProof`]s instead.
+///
+/// [`ReverseHashesOrder`]: crate::proof_serializers::ReverseHashesOrder
+pub struct CompactPositions {}
+
+impl CompactPositions {
+    /// Serializes the proof together with its leaf positions
+    pub fn serialize<T: Hasher>(proof: &CompactProof<T>) -> Vec<u8> {
+        let mut bytes = Self::header(proof);
+        bytes.append(&mut DirectHashesOrder::serialize(&proof.proof));
+        bytes
+    }
+
+    /// Parses a proof serialized with [`CompactPositions::serialize`]
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::MalformedProof`] if the header can't be decoded or
+    /// if the number of hashes doesn't match the leaf positions
+    ///
+    /// [`ErrorKind::MalformedProof`]: crate::ErrorKind::MalformedProof
+    pub fn deserialize<T: Hasher>(bytes: &[u8]) -> Result<CompactProof<T>, Error> {
+        let mut offset = 0;
+        let mut read_varint = |bytes: &[u8]| -> Result<usize, Error> {
+            let (value, length) = bytes
+                .get(offset..)
+                .and_then(utils::varint::decode)
+                .ok_or_else(|| Error::malformed_proof("invalid varint"))?;
+            offset += length;
+            Ok(value)
+        };
+
+        let total_leaves_count = read_varint(bytes)?;
+        let leaves_count = read_varint(bytes)?;
+        let leaf_indices =
+            Self::sorted_indices(total_leaves_count, leaves_count, || read_varint(bytes))?;
+
+        let proof = DirectHashesOrder::deserialize::<T>(&bytes[offset..])?;
+        if proof.proof_hashes().len() != Self::hashes_count(&leaf_indices, total_leaves_count) {
+            return Err(Error::malformed_proof(
+                "number of hashes doesn't match the leaf positions",
+            ));
+        }
+
+        Ok(CompactProof {
+            proof,
+            leaf_indices,
+            total_leaves_count,
+        })
+    }
+
+    /// Same as [`CompactPositions::serialize`], but writes the proof to a sink
+    pub fn serialize_to<T: Hasher, W: ProofSink>(
+        proof: &CompactProof<T>,
+        sink: &mut W,
+    ) -> Result<(), Error> {
+        sink.write_all(&Self::header(proof))?;
+        DirectHashesOrder::serialize_to(&proof.proof, sink)
+    }
+
+    /// Same as [`CompactPositions::deserialize`], but reads the proof from a source. Unlike other
+    /// built-in serializers, reads exactly as many hashes as the leaf positions need and leaves
+    /// the rest of the source untouched. See [`StreamingProofSerializer::deserialize_from`] for
+    /// the meaning of `max_proof_len`.
+    pub fn deserialize_from<T: Hasher, R: ProofSource>(
+        source: &mut R,
+        max_proof_len: usize,
+    ) -> Result<CompactProof<T>, Error> {
+        let mut reader = BoundedReader::new(source, max_proof_len);
+        let read_varint = |reader: &mut BoundedReader<R>| -> Result<usize, Error> {
+            let mut bytes = Vec::new();
+            loop {
+                let byte = reader.read_byte()?;
+                bytes.push(byte);
+                if byte & 0x80 == 0 {
+                    break;
+                }
+                if bytes.len() >= utils::varint::MAX_LEN {
+                    return Err(Error::malformed_proof("invalid varint"));
+                }
+            }
+            utils::varint::decode(&bytes)
+                .map(|(value, _)| value)
+                .ok_or_else(|| Error::malformed_proof("invalid varint"))
+        };
+
+        let total_leaves_count = read_varint(&mut reader)?;
+        let leaves_count = read_varint(&mut reader)?;
+        let leaf_indices = Self::sorted_indices(total_leaves_count, leaves_count, || {
+            read_varint(&mut reader)
+        })?;
+
+        let hashes_count = Self::hashes_count(&leaf_indices, total_leaves_count);
+        let mut proof_hashes = Vec::with_capacity(hashes_count);
+        for _ in 0..hashes_count {
+            match reader.read_hash::<T>()? {
+                Some(hash) => proof_hashes.push(hash),
+                None => {
+                    return Err(Error::malformed_proof(
+                        "number of hashes doesn't match the leaf positions",
+                    ))
+                }
+            }
+        }
+
+        Ok(CompactProof {
+            proof: MerkleProof::new(proof_hashes),
+            leaf_indices,
+            total_leaves_count,
+        })
+    }
+
+    /// Returns everything that precedes proof hashes: the tree size and leaf indices
+    fn header<T: Hasher>(proof: &CompactProof<T>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        utils::varint::encode(proof.total_leaves_count, &mut bytes);
+        utils::varint::encode(proof.leaf_indices.len(), &mut bytes);
+        let mut previous_index = None;
+        for index in &proof.leaf_indices {
+            match previous_index {
+                Some(previous_index) => {
+                    utils::varint::encode(index - previous_index - 1, &mut bytes)
+                }
+                None => utils::varint::encode(*index, &mut bytes),
+            }
+            previous_index = Some(*index);
+        }
+
+        bytes
+    }
+
+    /// Returns the number of hashes a proof of the leaves has
+    fn hashes_count(sorted_leaf_indices: &[usize], total_leaves_count: usize) -> usize {
+        utils::indices::proof_indices_by_layers(sorted_leaf_indices, total_leaves_count)
+            .iter()
+            .map(Vec::len)
+            .sum()
+    }
+
+    /// Decodes leaf indices, each but the first one being a gap from the previous index, and
+    /// checks that they fit into the tree
+    fn sorted_indices(
+        total_leaves_count: usize,
+        leaves_count: usize,
+        mut read_varint: impl FnMut() -> Result<usize, Error>,
+    ) -> Result<Vec<usize>, Error> {
+        if leaves_count == 0 {
+            return Err(Error::malformed_proof("proof has no leaves"));
+        }
+        if leaves_count > total_leaves_count {
+            return Err(Error::malformed_proof(
+                "more leaves are proven than the tree has",
+            ));
+        }
+
+        let mut sorted_indices = Vec::new();
+        for _ in 0..leaves_count {
+            let gap = read_varint()?;
+            let index = match sorted_indices.last() {
+                Some(previous_index) => gap
+                    .checked_add(*previous_index)
+                    .and_then(|index| index.checked_add(1)),
+                None => Some(gap),
+            };
+            match index {
+                Some(index) if index < total_leaves_count => sorted_indices.push(index),
+                _ => return Err(Error::malformed_proof("leaf index is out of range")),
+            }
+        }
+
+        Ok(sorted_indices)
+    }
+}
@@ -5,10 +5,18 @@
 //! [`MerkleProof::serialize`]: crate::MerkleProof::serialize
 //! [`MerkleProof::deserialize`]: crate::MerkleProof::deserialize
 
+mod compact_positions;
 mod direct_hashes_order;
 mod merkle_proof_serializer;
 mod reverse_hashes_order;
+mod streaming;
 
+pub use compact_positions::{CompactPositions, CompactProof};
 pub use direct_hashes_order::DirectHashesOrder;
 pub use merkle_proof_serializer::MerkleProofSerializer;
 pub use reverse_hashes_order::ReverseHashesOrder;
+#[cfg(feature = "std")]
+pub use streaming::{IoSink, IoSource};
+pub use streaming::{ProofSink, ProofSource, StreamingProofSerializer};
+
+pub(crate) use streaming::BoundedReader;
@@ -1,4 +1,8 @@
-use crate::{prelude::*, Error, Hasher, MerkleProof, MerkleProofSerializer};
+use crate::{
+    prelude::*,
+    proof_serializers::{BoundedReader, ProofSink, ProofSource, StreamingProofSerializer},
+    Error, Hasher, MerkleProof, MerkleProofSerializer,
+};
 use core::convert::TryFrom;
 
 /// Serializes proof data to bytes with a reverse hash order - hashes are concatenated from
@@ -46,3 +50,27 @@ impl MerkleProofSerializer for ReverseHashesOrder {
         Ok(MerkleProof::new(proof_hashes_slices))
     }
 }
+
+impl StreamingProofSerializer for ReverseHashesOrder {
+    fn serialize_to<T: Hasher, W: ProofSink>(
+        proof: &MerkleProof<T>,
+        sink: &mut W,
+    ) -> Result<(), Error> {
+        for hash in proof.proof_hashes().iter().rev() {
+            let bytes: Vec<u8> = (*hash).into();
+            sink.write_all(&bytes)?;
+        }
+        Ok(())
+    }
+
+    fn deserialize_from<T: Hasher, R: ProofSource>(
+        source: &mut R,
+        max_proof_len: usize,
+    ) -> Result<MerkleProof<T>, Error> {
+        let mut reader = BoundedReader::new(source, max_proof_len);
+        let mut proof_hashes = reader.read_hashes_to_end::<T>()?;
+        proof_hashes.reverse();
+
+        Ok(MerkleProof::new(proof_hashes))
+    }
+}
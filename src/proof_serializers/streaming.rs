@@ -0,0 +1,167 @@
+use crate::{prelude::*, Error, Hasher, MerkleProof};
+use core::convert::TryFrom;
+
+/// A destination for serialized proof bytes. Implemented for `Vec<u8>`, and, with the `std`
+/// feature, for any [`std::io::Write`] through [`IoSink`].
+pub trait ProofSink {
+    /// Writes all the bytes to the sink
+    fn write_all(&mut self, bytes: &[u8]) -> Result<(), Error>;
+}
+
+/// A source of serialized proof bytes. Implemented for `&[u8]`, and, with the `std` feature,
+/// for any [`std::io::Read`] through [`IoSource`].
+pub trait ProofSource {
+    /// Reads bytes into `buffer` and returns how many bytes were read. Returns 0 only when the
+    /// source is exhausted.
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Error>;
+}
+
+impl ProofSink for Vec<u8> {
+    fn write_all(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.extend_from_slice(bytes);
+        Ok(())
+    }
+}
+
+impl ProofSource for &[u8] {
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Error> {
+        let len = buffer.len().min(self.len());
+        let (head, tail) = self.split_at(len);
+        buffer[..len].copy_from_slice(head);
+        *self = tail;
+        Ok(len)
+    }
+}
+
+/// Adapts a [`std::io::Write`] to [`ProofSink`]
+#[cfg(feature = "std")]
+pub struct IoSink<W: std::io::Write>(pub W);
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> ProofSink for IoSink<W> {
+    fn write_all(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.0
+            .write_all(bytes)
+            .map_err(|error| Error::io_error(&error.to_string()))
+    }
+}
+
+/// Adapts a [`std::io::Read`] to [`ProofSource`]
+#[cfg(feature = "std")]
+pub struct IoSource<R: std::io::Read>(pub R);
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> ProofSource for IoSource<R> {
+    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, Error> {
+        loop {
+            match self.0.read(buffer) {
+                Ok(len) => return Ok(len),
+                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(Error::io_error(&error.to_string())),
+            }
+        }
+    }
+}
+
+/// Trait representing a Merkle proof serializer that writes to a [`ProofSink`] and reads from
+/// a [`ProofSource`] incrementally, instead of working with the whole proof in memory. Used in
+/// [`MerkleProof::serialize_to`] and [`MerkleProof::deserialize_from`].
+///
+/// [`DirectHashesOrder`] and [`ReverseHashesOrder`] implement this trait, and produce the same
+/// bytes as their [`MerkleProofSerializer`] implementations. [`CompactPositions`] serializes
+/// [`CompactProof`]s and has methods of the same names instead.
+///
+/// [`DirectHashesOrder`]: crate::proof_serializers::DirectHashesOrder
+/// [`ReverseHashesOrder`]: crate::proof_serializers::ReverseHashesOrder
+/// [`CompactPositions`]: crate::proof_serializers::CompactPositions
+/// [`CompactProof`]: crate::proof_serializers::CompactProof
+/// [`MerkleProofSerializer`]: crate::MerkleProofSerializer
+pub trait StreamingProofSerializer {
+    /// Writes data from [`MerkleProof`] to the sink
+    fn serialize_to<T: Hasher, W: ProofSink>(
+        proof: &MerkleProof<T>,
+        sink: &mut W,
+    ) -> Result<(), Error>;
+
+    /// Reads a proof written by [`StreamingProofSerializer::serialize_to`] from the source.
+    /// Fails instead of reading more than `max_proof_len` bytes, which protects from a hostile
+    /// source sending an endless proof.
+    fn deserialize_from<T: Hasher, R: ProofSource>(
+        source: &mut R,
+        max_proof_len: usize,
+    ) -> Result<MerkleProof<T>, Error>;
+}
+
+/// Wraps a [`ProofSource`] and counts bytes read from it against the maximum proof length
+pub(crate) struct BoundedReader<'a, R: ProofSource> {
+    source: &'a mut R,
+    bytes_read: usize,
+    max_proof_len: usize,
+}
+
+impl<'a, R: ProofSource> BoundedReader<'a, R> {
+    pub(crate) fn new(source: &'a mut R, max_proof_len: usize) -> Self {
+        Self {
+            source,
+            bytes_read: 0,
+            max_proof_len,
+        }
+    }
+
+    /// Fills the whole buffer. Returns `false` if the source was already exhausted, and an
+    /// error if it got exhausted in the middle of the buffer.
+    pub(crate) fn read_exact_or_end(&mut self, buffer: &mut [u8]) -> Result<bool, Error> {
+        let mut filled = 0;
+        while filled < buffer.len() {
+            let len = self.source.read(&mut buffer[filled..])?;
+            if len == 0 {
+                break;
+            }
+            filled += len;
+        }
+
+        if filled == 0 && !buffer.is_empty() {
+            return Ok(false);
+        }
+        self.bytes_read += filled;
+        if self.bytes_read > self.max_proof_len {
+            return Err(Error::proof_too_long(self.max_proof_len));
+        }
+        if filled < buffer.len() {
+            return Err(Error::wrong_proof_size(self.bytes_read, buffer.len()));
+        }
+
+        Ok(true)
+    }
+
+    /// Reads one byte, failing if the source is exhausted
+    pub(crate) fn read_byte(&mut self) -> Result<u8, Error> {
+        let mut byte = [0u8; 1];
+        if !self.read_exact_or_end(&mut byte)? {
+            return Err(Error::malformed_proof("proof is truncated"));
+        }
+        Ok(byte[0])
+    }
+
+    /// Reads one hash. Returns `None` if the source is exhausted.
+    pub(crate) fn read_hash<T: Hasher>(&mut self) -> Result<Option<T::Hash>, Error> {
+        let mut buffer = vec![0u8; T::hash_size()];
+        if !self.read_exact_or_end(&mut buffer)? {
+            return Ok(None);
+        }
+
+        match T::Hash::try_from(buffer) {
+            Ok(hash) => Ok(Some(hash)),
+            Err(_) => Err(Error::vec_to_hash_conversion_error()),
+        }
+    }
+
+    /// Reads hashes until the source is exhausted
+    pub(crate) fn read_hashes_to_end<T: Hasher>(&mut self) -> Result<Vec<T::Hash>, Error> {
+        let mut hashes = Vec::new();
+        while let Some(hash) = self.read_hash::<T>()? {
+            hashes.push(hash);
+        }
+        Ok(hashes)
+    }
+}
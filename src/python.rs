@@ -0,0 +1,249 @@
+//! Python bindings built with [`PyO3`](https://docs.rs/pyo3).
+//!
+//! The `rs_merkle` extension module exposes [`MerkleTree`](crate::MerkleTree),
+//! [`MerkleProof`](crate::MerkleProof) and the built-in algorithms, so trees built in Python
+//! have exactly the same roots and proofs as trees built with the Rust API. Hashes are passed
+//! as `bytes`, several hashes as a list of `bytes`. `algorithm` is one of `"sha256"`,
+//! `"sha384"` and `"keccak256"`. Proofs are serialized with [`DirectHashesOrder`]. Errors are
+//! raised as `ValueError`.
+//!
+//! Build the module with [`maturin`](https://www.maturin.rs), `pyproject.toml` enables the
+//! required features:
+//!
+//! ```sh
+//! maturin develop --release
+//! ```
+//!
+//! ```python
+//! from rs_merkle import MerkleTree, MerkleProof, Sha256
+//!
+//! leaves = [Sha256.hash(x) for x in [b"a", b"b", b"c"]]
+//! tree = MerkleTree("sha256", leaves)
+//! proof = tree.proof([2])
+//! assert proof.verify(tree.root(), [2], [leaves[2]], len(leaves))
+//!
+//! # A node without a pair is moved to the next layer as is, it's not hashed with itself
+//! assert Sha256.concat_and_hash(leaves[2]) == leaves[2]
+//! ```
+//!
+//! [`DirectHashesOrder`]: crate::proof_serializers::DirectHashesOrder
+
+use crate::{
+    dynamic::{Algorithm, AnyTree},
+    prelude::*,
+    Error,
+};
+use pyo3::{exceptions::PyValueError, prelude::*, types::PyBytes};
+
+impl From<Error> for PyErr {
+    fn from(error: Error) -> Self {
+        PyValueError::new_err(error.to_string())
+    }
+}
+
+/// [`MerkleTree`](crate::MerkleTree) exposed to Python as `MerkleTree`
+#[pyclass(name = "MerkleTree", module = "rs_merkle")]
+pub struct PyMerkleTree {
+    algorithm: Algorithm,
+    tree: AnyTree,
+}
+
+#[pymethods]
+impl PyMerkleTree {
+    /// Builds a tree from leaf hashes
+    #[new]
+    #[pyo3(signature = (algorithm, leaves = Vec::new()))]
+    pub fn new(algorithm: &str, leaves: Vec<Vec<u8>>) -> Result<Self, Error> {
+        let algorithm = Algorithm::from_name(algorithm)?;
+        let mut tree = AnyTree::new(algorithm);
+        tree.append(&concat_hashes(algorithm, &leaves)?)?;
+        tree.commit();
+
+        Ok(Self { algorithm, tree })
+    }
+
+    /// Appends leaf hashes, they are not a part of the tree until `commit` is called
+    pub fn append(&mut self, leaves: Vec<Vec<u8>>) -> Result<(), Error> {
+        self.tree.append(&concat_hashes(self.algorithm, &leaves)?)
+    }
+
+    /// Commits appended leaves, see [`MerkleTree::commit`](crate::MerkleTree::commit)
+    pub fn commit(&mut self) {
+        self.tree.commit()
+    }
+
+    /// Rolls back the last commit, see [`MerkleTree::rollback`](crate::MerkleTree::rollback)
+    pub fn rollback(&mut self) {
+        self.tree.rollback()
+    }
+
+    /// Returns the root, or `None` if the tree is empty
+    pub fn root<'py>(&self, py: Python<'py>) -> Option<Bound<'py, PyBytes>> {
+        self.tree.root().map(|root| PyBytes::new(py, &root))
+    }
+
+    /// Returns the hex encoded root, or `None` if the tree is empty
+    pub fn root_hex(&self) -> Option<String> {
+        self.tree.root_hex()
+    }
+
+    /// Returns the number of committed leaves
+    pub fn leaves_len(&self) -> usize {
+        self.tree.leaves_len()
+    }
+
+    /// Returns a proof for the leaves at the given indices
+    pub fn proof(&self, leaf_indices: Vec<usize>) -> Result<PyMerkleProof, Error> {
+        Ok(PyMerkleProof {
+            algorithm: self.algorithm,
+            proof: self.tree.proof(&leaf_indices)?,
+        })
+    }
+}
+
+/// [`MerkleProof`](crate::MerkleProof) exposed to Python as `MerkleProof`
+#[pyclass(name = "MerkleProof", module = "rs_merkle")]
+pub struct PyMerkleProof {
+    algorithm: Algorithm,
+    proof: Vec<u8>,
+}
+
+#[pymethods]
+impl PyMerkleProof {
+    /// Makes a proof from its hashes
+    #[new]
+    pub fn new(algorithm: &str, proof_hashes: Vec<Vec<u8>>) -> Result<Self, Error> {
+        let algorithm = Algorithm::from_name(algorithm)?;
+        Ok(Self {
+            algorithm,
+            proof: concat_hashes(algorithm, &proof_hashes)?,
+        })
+    }
+
+    /// Parses a serialized proof, see [`MerkleProof::from_bytes`](crate::MerkleProof::from_bytes)
+    #[staticmethod]
+    pub fn from_bytes(algorithm: &str, bytes: &[u8]) -> Result<Self, Error> {
+        let algorithm = Algorithm::from_name(algorithm)?;
+        algorithm.proof_hashes(bytes)?;
+
+        Ok(Self {
+            algorithm,
+            proof: bytes.to_vec(),
+        })
+    }
+
+    /// Returns the serialized proof
+    pub fn to_bytes<'py>(&self, py: Python<'py>) -> Bound<'py, PyBytes> {
+        PyBytes::new(py, &self.proof)
+    }
+
+    /// Returns the hashes of the proof
+    pub fn proof_hashes<'py>(&self, py: Python<'py>) -> Result<Vec<Bound<'py, PyBytes>>, Error> {
+        Ok(self
+            .algorithm
+            .proof_hashes(&self.proof)?
+            .iter()
+            .map(|hash| PyBytes::new(py, hash))
+            .collect())
+    }
+
+    /// Calculates the root, see [`MerkleProof::root`](crate::MerkleProof::root)
+    pub fn root<'py>(
+        &self,
+        py: Python<'py>,
+        leaf_indices: Vec<usize>,
+        leaf_hashes: Vec<Vec<u8>>,
+        total_leaves_count: usize,
+    ) -> Result<Bound<'py, PyBytes>, Error> {
+        let root = self.algorithm.proof_root(
+            &self.proof,
+            &leaf_indices,
+            &concat_hashes(self.algorithm, &leaf_hashes)?,
+            total_leaves_count,
+        )?;
+        Ok(PyBytes::new(py, &root))
+    }
+
+    /// Verifies the proof, see [`MerkleProof::verify`](crate::MerkleProof::verify)
+    pub fn verify(
+        &self,
+        root: &[u8],
+        leaf_indices: Vec<usize>,
+        leaf_hashes: Vec<Vec<u8>>,
+        total_leaves_count: usize,
+    ) -> Result<bool, Error> {
+        self.algorithm.verify(
+            &self.proof,
+            root,
+            &leaf_indices,
+            &concat_hashes(self.algorithm, &leaf_hashes)?,
+            total_leaves_count,
+        )
+    }
+}
+
+/// Exposes a built-in algorithm to Python as a class with static methods
+macro_rules! algorithm_class {
+    ($class:ident, $name:literal, $algorithm:expr) => {
+        #[doc = concat!("[`", $name, "`](crate::algorithms::", $name, ") exposed to Python as `", $name, "`")]
+        #[pyclass(name = $name, module = "rs_merkle", frozen)]
+        pub struct $class;
+
+        #[pymethods]
+        impl $class {
+            /// Hashes data
+            #[staticmethod]
+            pub fn hash<'py>(py: Python<'py>, data: &[u8]) -> Bound<'py, PyBytes> {
+                PyBytes::new(py, &$algorithm.hash(data))
+            }
+
+            /// Returns the size of a hash in bytes
+            #[staticmethod]
+            pub fn hash_size() -> usize {
+                $algorithm.hash_size()
+            }
+
+            /// Hashes a pair of nodes, see
+            /// [`Hasher::concat_and_hash`](crate::Hasher::concat_and_hash)
+            #[staticmethod]
+            #[pyo3(signature = (left, right = None))]
+            pub fn concat_and_hash<'py>(
+                py: Python<'py>,
+                left: &[u8],
+                right: Option<&[u8]>,
+            ) -> Result<Bound<'py, PyBytes>, Error> {
+                Ok(PyBytes::new(
+                    py,
+                    &$algorithm.concat_and_hash(left, right)?,
+                ))
+            }
+        }
+    };
+}
+
+algorithm_class!(PySha256, "Sha256", Algorithm::Sha256);
+algorithm_class!(PySha384, "Sha384", Algorithm::Sha384);
+algorithm_class!(PyKeccak256, "Keccak256", Algorithm::Keccak256);
+
+/// Concatenates hashes, checking that each of them has the size of the algorithm's hash
+fn concat_hashes(algorithm: Algorithm, hashes: &[Vec<u8>]) -> Result<Vec<u8>, Error> {
+    if hashes
+        .iter()
+        .any(|hash| hash.len() != algorithm.hash_size())
+    {
+        return Err(Error::vec_to_hash_conversion_error());
+    }
+
+    Ok(hashes.concat())
+}
+
+/// The `rs_merkle` Python module
+#[pymodule]
+pub fn rs_merkle(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyMerkleTree>()?;
+    module.add_class::<PyMerkleProof>()?;
+    module.add_class::<PySha256>()?;
+    module.add_class::<PySha384>()?;
+    module.add_class::<PyKeccak256>()?;
+    Ok(())
+}
@@ -0,0 +1,323 @@
+//! Solidity verifier generator and ABI encoding of Merkle proofs.
+//!
+//! [`VerifierConfig::verifier_source`] emits the source of a Solidity contract that verifies
+//! multi-proofs made by [`MerkleTree`] the same way [`MerkleProof::verify`] does, for the
+//! given node hashing configuration. [`encode_verify_call`] encodes a proof as calldata for
+//! the `verify` function of that contract.
+//!
+//! The generated contract expects leaf indices in ascending order, which
+//! [`encode_verify_call`] takes care of.
+//!
+//! ## Examples
+//!
+//! ```
+//! # use rs_merkle::{MerkleTree, algorithms::Keccak256, Hasher};
+//! # use rs_merkle::solidity::{self, SolidityHasher, VerifierConfig};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f"]
+//!     .iter()
+//!     .map(|x| Keccak256::hash(x.as_bytes()))
+//!     .collect();
+//! let merkle_tree = MerkleTree::<Keccak256>::from_leaves(&leaves);
+//! let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+//! let proof = merkle_tree.proof(&[4, 1]);
+//!
+//! let source = VerifierConfig::new(SolidityHasher::Keccak256)
+//!     .with_contract_name("AirdropVerifier")
+//!     .verifier_source();
+//! assert!(source.contains("contract AirdropVerifier"));
+//!
+//! let calldata = solidity::encode_verify_call(
+//!     root,
+//!     &proof,
+//!     &[4, 1],
+//!     &[leaves[4], leaves[1]],
+//!     leaves.len(),
+//! );
+//! assert_eq!(&calldata[..4], &solidity::VERIFY_SELECTOR);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`MerkleTree`]: crate::MerkleTree
+//! [`MerkleProof::verify`]: crate::MerkleProof::verify
+
+use crate::{prelude::*, utils, Hasher, MerkleProof};
+use tiny_keccak::{Hasher as KeccakHasher, Keccak};
+
+/// Signature of the `verify` function of the generated contract
+pub const VERIFY_SIGNATURE: &str = "verify(bytes32,bytes32[],uint256[],bytes32[],uint256)";
+
+/// Selector of the `verify` function of the generated contract, i.e. the first four bytes of
+/// the keccak256 hash of [`VERIFY_SIGNATURE`]
+pub const VERIFY_SELECTOR: [u8; 4] = [0x50, 0x19, 0x5a, 0x56];
+
+/// Hash function the generated contract uses to hash a pair of nodes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SolidityHasher {
+    /// The `keccak256` built-in, matches [`algorithms::Keccak256`](crate::algorithms::Keccak256)
+    Keccak256,
+    /// The `sha256` precompile, matches [`algorithms::Sha256`](crate::algorithms::Sha256)
+    Sha256,
+}
+
+/// What the generated contract does with the last node of a layer that has an odd number of
+/// nodes. Has to match what [`Hasher::concat_and_hash`] does when the right node is `None`.
+///
+/// [`Hasher::concat_and_hash`]: crate::Hasher::concat_and_hash
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OddNodePolicy {
+    /// The node is moved to the next layer as it is. This is what the default
+    /// [`Hasher::concat_and_hash`] does.
+    Propagate,
+    /// The node is hashed together with itself
+    Duplicate,
+}
+
+/// Configuration of the tree the generated contract verifies proofs for
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VerifierConfig {
+    contract_name: String,
+    hasher: SolidityHasher,
+    odd_node_policy: OddNodePolicy,
+    node_prefix: Vec<u8>,
+}
+
+impl VerifierConfig {
+    /// Creates a configuration for the `MerkleVerifier` contract that hashes nodes with the
+    /// given hash function and propagates odd nodes, the same as the built-in hashers do
+    pub fn new(hasher: SolidityHasher) -> Self {
+        Self {
+            contract_name: "MerkleVerifier".into(),
+            hasher,
+            odd_node_policy: OddNodePolicy::Propagate,
+            node_prefix: Vec::new(),
+        }
+    }
+
+    /// Sets the name of the generated contract
+    pub fn with_contract_name(mut self, contract_name: &str) -> Self {
+        self.contract_name = contract_name.into();
+        self
+    }
+
+    /// Sets what to do with the last node of an odd layer
+    pub fn with_odd_node_policy(mut self, odd_node_policy: OddNodePolicy) -> Self {
+        self.odd_node_policy = odd_node_policy;
+        self
+    }
+
+    /// Sets a domain separation prefix that is hashed before the left and the right node, for
+    /// hashers that implement [`Hasher::concat_and_hash`] as `hash(prefix || left || right)`
+    ///
+    /// [`Hasher::concat_and_hash`]: crate::Hasher::concat_and_hash
+    pub fn with_node_prefix(mut self, node_prefix: &[u8]) -> Self {
+        self.node_prefix = node_prefix.to_vec();
+        self
+    }
+
+    /// Returns the Solidity source of the verifier contract
+    pub fn verifier_source(&self) -> String {
+        let hash_function = match self.hasher {
+            SolidityHasher::Keccak256 => "keccak256",
+            SolidityHasher::Sha256 => "sha256",
+        };
+        let node_prefix = if self.node_prefix.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "hex\"{}\", ",
+                utils::collections::to_hex_string(&self.node_prefix)
+            )
+        };
+        let odd_node = match self.odd_node_policy {
+            OddNodePolicy::Propagate => "node",
+            OddNodePolicy::Duplicate => "hashPair(node, node)",
+        };
+
+        format!(
+            r#"// SPDX-License-Identifier: MIT
+// Generated by rs_merkle. Regenerate instead of editing by hand.
+pragma solidity ^0.8.0;
+
+contract {contract_name} {{
+    /// Verifies a multi-proof for leaves at ascending `leafIndices` of a tree with
+    /// `totalLeavesCount` leaves
+    function verify(
+        bytes32 root,
+        bytes32[] calldata proof,
+        uint256[] calldata leafIndices,
+        bytes32[] calldata leaves,
+        uint256 totalLeavesCount
+    ) external pure returns (bool) {{
+        (bool ok, bytes32 computedRoot) = computeRoot(proof, leafIndices, leaves, totalLeavesCount);
+        return ok && computedRoot == root;
+    }}
+
+    function computeRoot(
+        bytes32[] calldata proof,
+        uint256[] calldata leafIndices,
+        bytes32[] calldata leaves,
+        uint256 totalLeavesCount
+    ) public pure returns (bool, bytes32) {{
+        uint256 count = leafIndices.length;
+        if (count == 0 || count != leaves.length) {{
+            return (false, 0);
+        }}
+
+        uint256[] memory indices = new uint256[](count);
+        bytes32[] memory nodes = new bytes32[](count);
+        for (uint256 i = 0; i < count; i++) {{
+            if (leafIndices[i] >= totalLeavesCount || (i > 0 && leafIndices[i] <= leafIndices[i - 1])) {{
+                return (false, 0);
+            }}
+            indices[i] = leafIndices[i];
+            nodes[i] = leaves[i];
+        }}
+
+        uint256 proofPosition = 0;
+        uint256 layerLength = totalLeavesCount;
+        for (uint256 depth = treeDepth(totalLeavesCount); depth > 0; depth--) {{
+            uint256 parentsCount = 0;
+            uint256 i = 0;
+            while (i < count) {{
+                uint256 index = indices[i];
+                bytes32 node = nodes[i];
+                bytes32 parent;
+                if (index % 2 == 1) {{
+                    if (proofPosition >= proof.length) {{
+                        return (false, 0);
+                    }}
+                    parent = hashPair(proof[proofPosition++], node);
+                    i += 1;
+                }} else if (i + 1 < count && indices[i + 1] == index + 1) {{
+                    parent = hashPair(node, nodes[i + 1]);
+                    i += 2;
+                }} else if (index + 1 < layerLength) {{
+                    if (proofPosition >= proof.length) {{
+                        return (false, 0);
+                    }}
+                    parent = hashPair(node, proof[proofPosition++]);
+                    i += 1;
+                }} else {{
+                    parent = {odd_node};
+                    i += 1;
+                }}
+                indices[parentsCount] = index / 2;
+                nodes[parentsCount] = parent;
+                parentsCount++;
+            }}
+            count = parentsCount;
+            layerLength = (layerLength + 1) / 2;
+        }}
+
+        return (true, nodes[0]);
+    }}
+
+    function hashPair(bytes32 left, bytes32 right) internal pure returns (bytes32) {{
+        return {hash_function}(abi.encodePacked({node_prefix}left, right));
+    }}
+
+    function treeDepth(uint256 leavesCount) internal pure returns (uint256 depth) {{
+        while (leavesCount > 0) {{
+            depth++;
+            leavesCount >>= 1;
+        }}
+    }}
+}}
+"#,
+            contract_name = self.contract_name,
+            hash_function = hash_function,
+            node_prefix = node_prefix,
+            odd_node = odd_node,
+        )
+    }
+}
+
+/// Returns the selector of a function with the given signature, e.g. `transfer(address,uint256)`
+pub fn selector(signature: &str) -> [u8; 4] {
+    let mut hasher = Keccak::v256();
+    let mut output = [0u8; 32];
+    hasher.update(signature.as_bytes());
+    hasher.finalize(&mut output);
+
+    [output[0], output[1], output[2], output[3]]
+}
+
+/// ABI-encodes proof hashes as a standalone `bytes32[]` value
+pub fn encode_proof<T: Hasher<Hash = [u8; 32]>>(proof: &MerkleProof<T>) -> Vec<u8> {
+    let mut encoded = uint256(32).to_vec();
+    encoded.append(&mut encode_array_tail(proof.proof_hashes()));
+    encoded
+}
+
+/// ABI-encodes leaf indices as a standalone `uint256[]` value
+pub fn encode_indices(leaf_indices: &[usize]) -> Vec<u8> {
+    let words: Vec<[u8; 32]> = leaf_indices.iter().map(|index| uint256(*index)).collect();
+    let mut encoded = uint256(32).to_vec();
+    encoded.append(&mut encode_array_tail(&words));
+    encoded
+}
+
+/// Encodes calldata for the `verify` function of the generated contract. Leaf indices and
+/// hashes are sorted by index, the same as [`MerkleProof::verify`] does before verification.
+pub fn encode_verify_call<T: Hasher<Hash = [u8; 32]>>(
+    root: [u8; 32],
+    proof: &MerkleProof<T>,
+    leaf_indices: &[usize],
+    leaf_hashes: &[[u8; 32]],
+    total_leaves_count: usize,
+) -> Vec<u8> {
+    let mut leaf_tuples: Vec<(usize, [u8; 32])> = leaf_indices
+        .iter()
+        .cloned()
+        .zip(leaf_hashes.iter().cloned())
+        .collect();
+    leaf_tuples.sort_by_key(|(index, _)| *index);
+    let sorted_indices: Vec<[u8; 32]> = leaf_tuples
+        .iter()
+        .map(|(index, _)| uint256(*index))
+        .collect();
+    let sorted_hashes: Vec<[u8; 32]> = leaf_tuples.iter().map(|(_, hash)| *hash).collect();
+
+    let tails = [
+        encode_array_tail(proof.proof_hashes()),
+        encode_array_tail(&sorted_indices),
+        encode_array_tail(&sorted_hashes),
+    ];
+    // Five head words: the root, three array offsets and the leaves count
+    let mut offset = 5 * 32;
+    let mut offsets = Vec::new();
+    for tail in &tails {
+        offsets.push(uint256(offset));
+        offset += tail.len();
+    }
+
+    let mut calldata = VERIFY_SELECTOR.to_vec();
+    calldata.extend_from_slice(&root);
+    for array_offset in &offsets {
+        calldata.extend_from_slice(array_offset);
+    }
+    calldata.extend_from_slice(&uint256(total_leaves_count));
+    for tail in &tails {
+        calldata.extend_from_slice(tail);
+    }
+    calldata
+}
+
+/// Encodes the dynamic part of an array of words: the length followed by the elements
+fn encode_array_tail(words: &[[u8; 32]]) -> Vec<u8> {
+    let mut encoded = uint256(words.len()).to_vec();
+    for word in words {
+        encoded.extend_from_slice(word);
+    }
+    encoded
+}
+
+/// Encodes a number as a big-endian 256-bit word
+fn uint256(value: usize) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    let bytes = (value as u64).to_be_bytes();
+    word[32 - bytes.len()..].copy_from_slice(&bytes);
+    word
+}
@@ -0,0 +1,202 @@
+use crate::prelude::*;
+use crate::{Error, Hasher, MerkleProof, MerkleTree, NonMembershipProof};
+
+/// [`SortedMerkleTree`] is a [`MerkleTree`] that keeps its leaves sorted: every inserted leaf
+/// has to be greater than the previous one when compared as bytes. Sorted leaves allow
+/// proving that a leaf is not in the tree with [`SortedMerkleTree::non_membership_proof`].
+///
+/// Unlike [`MerkleTree::insert`] and [`MerkleTree::append`], inserting into a sorted tree can
+/// fail, so it returns a `Result`. Read-only methods of the underlying tree, like proofs or
+/// leaves, are available through [`SortedMerkleTree::merkle_tree`].
+///
+/// ## Examples
+///
+/// ```
+/// # use rs_merkle::{SortedMerkleTree, algorithms::Sha256, Hasher};
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut leaves: Vec<[u8; 32]> = ["a", "b", "c"]
+///     .iter()
+///     .map(|x| Sha256::hash(x.as_bytes()))
+///     .collect();
+/// leaves.sort();
+///
+/// let mut merkle_tree = SortedMerkleTree::<Sha256>::from_leaves(&leaves)?;
+/// assert!(merkle_tree.insert(leaves[0]).is_err());
+/// assert_eq!(merkle_tree.merkle_tree().leaves_len(), 3);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct SortedMerkleTree<T: Hasher> {
+    merkle_tree: MerkleTree<T>,
+}
+
+impl<T: Hasher> Default for SortedMerkleTree<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hasher> SortedMerkleTree<T> {
+    /// Creates an empty tree
+    pub fn new() -> Self {
+        Self {
+            merkle_tree: MerkleTree::new(),
+        }
+    }
+
+    /// Builds the tree from sorted leaves
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::UnsortedLeaves`] if a leaf is not greater than
+    /// the previous one
+    ///
+    /// [`ErrorKind::UnsortedLeaves`]: crate::ErrorKind::UnsortedLeaves
+    pub fn from_leaves(leaves: &[T::Hash]) -> Result<Self, Error> {
+        let mut tree = Self::new();
+        tree.append(&mut leaves.to_vec())?;
+        tree.commit();
+        Ok(tree)
+    }
+
+    /// Inserts a leaf into the tree without committing it, see [`MerkleTree::insert`]
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::UnsortedLeaves`] if the leaf is not greater than
+    /// the previous one, committed or not. The leaf is not inserted in that case.
+    ///
+    /// [`ErrorKind::UnsortedLeaves`]: crate::ErrorKind::UnsortedLeaves
+    pub fn insert(&mut self, leaf: T::Hash) -> Result<&mut Self, Error> {
+        self.check_order(core::slice::from_ref(&leaf))?;
+        self.merkle_tree.insert(leaf);
+        Ok(self)
+    }
+
+    /// Appends leaves to the tree without committing them, see [`MerkleTree::append`]
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::UnsortedLeaves`] if a leaf is not greater than
+    /// the previous one. No leaves are appended in that case.
+    ///
+    /// [`ErrorKind::UnsortedLeaves`]: crate::ErrorKind::UnsortedLeaves
+    pub fn append(&mut self, leaves: &mut Vec<T::Hash>) -> Result<&mut Self, Error> {
+        self.check_order(leaves)?;
+        self.merkle_tree.append(leaves);
+        Ok(self)
+    }
+
+    /// Commits the inserted leaves, see [`MerkleTree::commit`]
+    pub fn commit(&mut self) {
+        self.merkle_tree.commit();
+    }
+
+    /// Rolls back one commit, see [`MerkleTree::rollback`]
+    pub fn rollback(&mut self) {
+        self.merkle_tree.rollback();
+    }
+
+    /// Drops the leaves that weren't committed, see [`MerkleTree::abort_uncommitted`]
+    pub fn abort_uncommitted(&mut self) {
+        self.merkle_tree.abort_uncommitted();
+    }
+
+    /// Returns the root of the tree, see [`MerkleTree::root`]
+    pub fn root(&self) -> Option<T::Hash> {
+        self.merkle_tree.root()
+    }
+
+    /// Returns a proof for the leaves at the given indices, see [`MerkleTree::proof`]
+    pub fn proof(&self, leaf_indices: &[usize]) -> MerkleProof<T> {
+        self.merkle_tree.proof(leaf_indices)
+    }
+
+    /// Returns the underlying tree
+    pub fn merkle_tree(&self) -> &MerkleTree<T> {
+        &self.merkle_tree
+    }
+
+    /// Returns a proof that the leaf is not in the tree. The proof consists of the committed
+    /// leaves right before and after the position the leaf would have in the tree.
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::EmptyTree`] if the tree has no committed leaves
+    /// and [`ErrorKind::LeafInTree`] if the leaf is in the tree
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use rs_merkle::{SortedMerkleTree, algorithms::Sha256, Hasher};
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut leaves: Vec<[u8; 32]> = ["a", "b", "c", "d"]
+    ///     .iter()
+    ///     .map(|x| Sha256::hash(x.as_bytes()))
+    ///     .collect();
+    /// leaves.sort();
+    ///
+    /// let merkle_tree = SortedMerkleTree::<Sha256>::from_leaves(&leaves)?;
+    /// let root = merkle_tree.root().ok_or("couldn't get the merkle root")?;
+    ///
+    /// let missing_leaf = Sha256::hash("e".as_bytes());
+    /// let proof = merkle_tree.non_membership_proof(&missing_leaf)?;
+    /// assert!(proof.verify(root, &missing_leaf, leaves.len()));
+    /// assert!(!proof.verify(root, &leaves[1], leaves.len()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`ErrorKind::EmptyTree`]: crate::ErrorKind::EmptyTree
+    /// [`ErrorKind::LeafInTree`]: crate::ErrorKind::LeafInTree
+    pub fn non_membership_proof(&self, leaf: &T::Hash) -> Result<NonMembershipProof<T>, Error> {
+        let leaves = self
+            .merkle_tree
+            .leaves_tuples()
+            .ok_or_else(Error::empty_tree)?;
+
+        let leaf_bytes: Vec<u8> = (*leaf).into();
+        let position = match leaves.binary_search_by(|(_, hash)| {
+            let hash_bytes: Vec<u8> = (*hash).into();
+            hash_bytes.cmp(&leaf_bytes)
+        }) {
+            Ok(index) => return Err(Error::leaf_in_tree(index)),
+            Err(position) => position,
+        };
+
+        let left = position.checked_sub(1).map(|index| leaves[index]);
+        let right = leaves.get(position).copied();
+        let indices: Vec<usize> = left.iter().chain(right.iter()).map(|(i, _)| *i).collect();
+
+        Ok(NonMembershipProof::new(
+            left,
+            right,
+            self.merkle_tree.proof(&indices),
+            leaves.len(),
+        ))
+    }
+
+    /// Checks that each leaf is greater than the previous one when compared as bytes, starting
+    /// from the last leaf of the tree, committed or not
+    fn check_order(&self, leaves: &[T::Hash]) -> Result<(), Error> {
+        let previous_leaf = self.merkle_tree.uncommitted_leaves().last().or_else(|| {
+            self.merkle_tree
+                .leaves_tuples()?
+                .last()
+                .map(|(_, hash)| hash)
+        });
+        let first_position =
+            self.merkle_tree.leaves_len() + self.merkle_tree.uncommitted_leaves().len();
+
+        let mut previous_bytes: Option<Vec<u8>> = previous_leaf.map(|leaf| (*leaf).into());
+        for (offset, leaf) in leaves.iter().enumerate() {
+            let bytes: Vec<u8> = (*leaf).into();
+            if previous_bytes.is_some_and(|previous_bytes| previous_bytes >= bytes) {
+                return Err(Error::unsorted_leaf(first_position + offset));
+            }
+            previous_bytes = Some(bytes);
+        }
+        Ok(())
+    }
+}
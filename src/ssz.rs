@@ -0,0 +1,467 @@
+//! SSZ merkleization and Merkle proofs, as defined by the Ethereum consensus specs.
+//!
+//! Values are packed into 32-byte chunks with [`pack`] or [`pack_bits`], and the chunks are
+//! merkleized with [`merkleize`]: they are virtually padded with zero chunks to the next power
+//! of two, or to a limit for lists and bitlists, and hashed with SHA-256 as a perfect binary
+//! tree. The root of a list is then mixed with its length by [`mix_in_length`].
+//!
+//! Nodes of the tree are addressed by generalized indices: the root is 1, and the children of
+//! the node `i` are `2i` and `2i + 1`. [`ChunkTree`] makes single and multi proofs for
+//! generalized indices, and [`verify_merkle_proof`] and [`verify_merkle_multiproof`] verify
+//! them, for example to check a light client update against a beacon block state root.
+//!
+//! ## Examples
+//!
+//! ```
+//! # use rs_merkle::ssz::{self, ChunkTree};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! // A `List[uint64, 100]` with 3 elements
+//! let values: Vec<u8> = [1u64, 2, 3].iter().flat_map(|x| x.to_le_bytes()).collect();
+//! let chunks = ssz::pack(&values);
+//! let limit = ssz::chunk_count(100, 8)?;
+//!
+//! let root = ssz::mix_in_length(ssz::merkleize(&chunks, Some(limit))?, 3);
+//!
+//! let tree = ChunkTree::new(&chunks, Some(limit))?.with_length(3);
+//! assert_eq!(tree.root(), root);
+//!
+//! let gindex = tree.chunk_generalized_index(0);
+//! let proof = tree.proof(gindex)?;
+//! assert!(ssz::verify_merkle_proof(chunks[0], &proof, gindex, root));
+//! # Ok(())
+//! # }
+//! ```
+use crate::prelude::*;
+use crate::{algorithms::Sha256, utils, Error, Hasher, MerkleTree, Padding};
+use alloc::collections::BTreeMap;
+
+/// Size of a chunk in bytes
+pub const BYTES_PER_CHUNK: usize = 32;
+
+/// A 32-byte chunk, which is also the type of every node of the tree
+pub type Chunk = [u8; BYTES_PER_CHUNK];
+
+/// Position of a node in a tree: the root is 1, and the children of the node `i` are `2i` and
+/// `2i + 1`
+pub type GeneralizedIndex = usize;
+
+/// Chunk of zero bytes that chunks are padded with
+pub const ZERO_CHUNK: Chunk = [0; BYTES_PER_CHUNK];
+
+/// Packs serialized basic values into chunks, padding the last chunk with zero bytes
+pub fn pack(bytes: &[u8]) -> Vec<Chunk> {
+    bytes
+        .chunks(BYTES_PER_CHUNK)
+        .map(|bytes| {
+            let mut chunk = ZERO_CHUNK;
+            chunk[..bytes.len()].copy_from_slice(bytes);
+            chunk
+        })
+        .collect()
+}
+
+/// Packs bits of a bitvector or a bitlist into chunks, the first bit being the least
+/// significant bit of the first byte. The length delimiter of a bitlist is not included.
+pub fn pack_bits(bits: &[bool]) -> Vec<Chunk> {
+    let mut bytes = vec![0u8; utils::indices::div_ceil(bits.len(), 8)];
+    for (position, bit) in bits.iter().enumerate() {
+        if *bit {
+            bytes[position / 8] |= 1 << (position % 8);
+        }
+    }
+    pack(&bytes)
+}
+
+/// Returns how many chunks `elements_count` basic values of `element_size` bytes take, which
+/// is the chunk limit of a list of such values. For bitlists, the element size is 1 and the
+/// number of elements is the number of bits divided by 8, rounded up.
+///
+/// ## Errors
+///
+/// Returns an error of kind [`ErrorKind::InvalidDepth`] if the values take more than
+/// `usize::MAX` bytes
+///
+/// [`ErrorKind::InvalidDepth`]: crate::ErrorKind::InvalidDepth
+pub fn chunk_count(elements_count: usize, element_size: usize) -> Result<usize, Error> {
+    let bytes_count = elements_count
+        .checked_mul(element_size)
+        .ok_or_else(|| Error::list_limit_too_large(elements_count, element_size))?;
+    Ok(utils::indices::div_ceil(bytes_count, BYTES_PER_CHUNK))
+}
+
+/// Returns the roots of the trees of zero chunks of heights from 0 to `depth - 1`
+pub fn zero_hashes(depth: usize) -> Vec<Chunk> {
+    Padding::<Sha256>::to_power_of_two(ZERO_CHUNK).zero_hashes(depth)
+}
+
+/// Merkleizes the chunks padded with zero chunks to the next power of two, or to `limit`
+/// rounded up to a power of two if there's a limit
+///
+/// ## Errors
+///
+/// Returns an error of kind [`ErrorKind::TreeIsFull`] if there are more chunks than `limit`
+///
+/// [`ErrorKind::TreeIsFull`]: crate::ErrorKind::TreeIsFull
+pub fn merkleize(chunks: &[Chunk], limit: Option<usize>) -> Result<Chunk, Error> {
+    Ok(ChunkTree::new(chunks, limit)?.root())
+}
+
+/// Mixes the length of a list or a bitlist into the root of its chunks
+pub fn mix_in_length(root: Chunk, length: usize) -> Chunk {
+    Sha256::concat_and_hash(&root, Some(&length_chunk(length)))
+}
+
+/// Returns the length of a list serialized as a little-endian 256-bit integer
+fn length_chunk(length: usize) -> Chunk {
+    let mut chunk = ZERO_CHUNK;
+    chunk[..8].copy_from_slice(&(length as u64).to_le_bytes());
+    chunk
+}
+
+/// Returns how many layers there are between the node and the root
+pub fn generalized_index_length(index: GeneralizedIndex) -> usize {
+    (usize::BITS - 1).saturating_sub(index.leading_zeros()) as usize
+}
+
+/// Returns `true` if the node is the right child of its ancestor `position` layers above
+pub fn generalized_index_bit(index: GeneralizedIndex, position: usize) -> bool {
+    (index >> position) & 1 == 1
+}
+
+/// Returns the other child of the node's parent
+pub fn generalized_index_sibling(index: GeneralizedIndex) -> GeneralizedIndex {
+    index ^ 1
+}
+
+/// Returns the left or the right child of the node
+pub fn generalized_index_child(index: GeneralizedIndex, right_side: bool) -> GeneralizedIndex {
+    index * 2 + right_side as usize
+}
+
+/// Returns the parent of the node
+pub fn generalized_index_parent(index: GeneralizedIndex) -> GeneralizedIndex {
+    index / 2
+}
+
+/// Returns the index of the node relative to the subtree of its ancestor at the root layer,
+/// i.e. its position in its layer
+pub fn subtree_index(index: GeneralizedIndex) -> usize {
+    index % (1 << generalized_index_length(index))
+}
+
+/// Combines generalized indices of nested trees, each relative to the root of the node
+/// addressed by the previous one, into a generalized index in the outermost tree
+pub fn concat_generalized_indices(indices: &[GeneralizedIndex]) -> GeneralizedIndex {
+    indices.iter().fold(1, |outer, index| {
+        let length = generalized_index_length(*index);
+        outer * (1 << length) + subtree_index(*index)
+    })
+}
+
+/// Returns generalized indices of the nodes a multiproof for the given nodes consists of,
+/// in the order the proof contains them, which is descending order of generalized index
+pub fn helper_indices(indices: &[GeneralizedIndex]) -> Vec<GeneralizedIndex> {
+    let mut helper_indices = Vec::new();
+    let mut path_indices = Vec::new();
+    for index in indices {
+        let mut current = *index;
+        while current > 1 {
+            helper_indices.push(generalized_index_sibling(current));
+            path_indices.push(current);
+            current = generalized_index_parent(current);
+        }
+    }
+
+    helper_indices.sort_unstable_by(|a, b| b.cmp(a));
+    helper_indices.dedup();
+    path_indices.sort_unstable();
+    helper_indices.retain(|index| path_indices.binary_search(index).is_err());
+    helper_indices
+}
+
+/// Calculates the root from a leaf and a proof of the node at the generalized index. The
+/// proof contains the siblings of the node and its ancestors, from the bottom up.
+///
+/// ## Errors
+///
+/// Returns an error of kind [`ErrorKind::InvalidGeneralizedIndex`] if the index is zero,
+/// [`ErrorKind::NotEnoughHashesToCalculateRoot`] if the proof is shorter than the path from
+/// the node to the root and [`ErrorKind::TooManyProofHashes`] if it's longer
+///
+/// [`ErrorKind::InvalidGeneralizedIndex`]: crate::ErrorKind::InvalidGeneralizedIndex
+/// [`ErrorKind::NotEnoughHashesToCalculateRoot`]: crate::ErrorKind::NotEnoughHashesToCalculateRoot
+/// [`ErrorKind::TooManyProofHashes`]: crate::ErrorKind::TooManyProofHashes
+pub fn calculate_merkle_root(
+    leaf: Chunk,
+    proof: &[Chunk],
+    index: GeneralizedIndex,
+) -> Result<Chunk, Error> {
+    if index == 0 {
+        return Err(Error::invalid_generalized_index(index));
+    }
+    let length = generalized_index_length(index);
+    if proof.len() < length {
+        return Err(Error::not_enough_hashes_to_calculate_root());
+    }
+    if proof.len() > length {
+        return Err(Error::too_many_proof_hashes(proof.len() - length));
+    }
+
+    Ok(proof
+        .iter()
+        .enumerate()
+        .fold(leaf, |node, (position, sibling)| {
+            if generalized_index_bit(index, position) {
+                Sha256::concat_and_hash(sibling, Some(&node))
+            } else {
+                Sha256::concat_and_hash(&node, Some(sibling))
+            }
+        }))
+}
+
+/// Verifies a proof made by [`ChunkTree::proof`] that `leaf` is the node at the generalized
+/// index of a tree with the given root
+pub fn verify_merkle_proof(
+    leaf: Chunk,
+    proof: &[Chunk],
+    index: GeneralizedIndex,
+    root: Chunk,
+) -> bool {
+    calculate_merkle_root(leaf, proof, index).is_ok_and(|calculated| calculated == root)
+}
+
+/// Verifies a Merkle branch of `depth` siblings that `leaf` is at `index` of the layer
+/// `depth` layers below the root. This is `is_valid_merkle_branch` of the specs, used for
+/// example to verify deposits and light client updates. Returns `false` if the index doesn't
+/// fit into the layer.
+pub fn is_valid_merkle_branch(
+    leaf: Chunk,
+    branch: &[Chunk],
+    depth: usize,
+    index: usize,
+    root: Chunk,
+) -> bool {
+    if depth >= usize::BITS as usize || branch.len() < depth || index >> depth != 0 {
+        return false;
+    }
+    verify_merkle_proof(leaf, &branch[..depth], (1 << depth) | index, root)
+}
+
+/// Calculates the root from leaves at the generalized indices and a multiproof made by
+/// [`ChunkTree::multiproof`]. The proof contains the nodes returned by [`helper_indices`],
+/// in the same order.
+///
+/// ## Errors
+///
+/// Returns an error of kind [`ErrorKind::LeavesIndicesCountMismatch`] if the number of leaves
+/// doesn't match the number of indices, [`ErrorKind::InvalidGeneralizedIndex`] if an index is
+/// zero, and [`ErrorKind::NotEnoughHashesToCalculateRoot`] or
+/// [`ErrorKind::TooManyProofHashes`] if the proof has a wrong number of nodes
+///
+/// [`ErrorKind::LeavesIndicesCountMismatch`]: crate::ErrorKind::LeavesIndicesCountMismatch
+/// [`ErrorKind::InvalidGeneralizedIndex`]: crate::ErrorKind::InvalidGeneralizedIndex
+/// [`ErrorKind::NotEnoughHashesToCalculateRoot`]: crate::ErrorKind::NotEnoughHashesToCalculateRoot
+/// [`ErrorKind::TooManyProofHashes`]: crate::ErrorKind::TooManyProofHashes
+pub fn calculate_multi_merkle_root(
+    leaves: &[Chunk],
+    proof: &[Chunk],
+    indices: &[GeneralizedIndex],
+) -> Result<Chunk, Error> {
+    if leaves.len() != indices.len() {
+        return Err(Error::leaves_indices_count_mismatch(
+            indices.len(),
+            leaves.len(),
+        ));
+    }
+    if let Some(index) = indices.iter().find(|index| **index == 0) {
+        return Err(Error::invalid_generalized_index(*index));
+    }
+    let helper_indices = helper_indices(indices);
+    if proof.len() < helper_indices.len() {
+        return Err(Error::not_enough_hashes_to_calculate_root());
+    }
+    if proof.len() > helper_indices.len() {
+        return Err(Error::too_many_proof_hashes(
+            proof.len() - helper_indices.len(),
+        ));
+    }
+
+    let mut nodes: BTreeMap<GeneralizedIndex, Chunk> = indices
+        .iter()
+        .copied()
+        .zip(leaves.iter().copied())
+        .chain(helper_indices.into_iter().zip(proof.iter().copied()))
+        .collect();
+
+    // Going from the deepest nodes up, so children are always calculated before the parent
+    let mut keys: Vec<GeneralizedIndex> = nodes.keys().rev().copied().collect();
+    let mut position = 0;
+    while let Some(index) = keys.get(position).copied() {
+        let parent = generalized_index_parent(index);
+        if index > 1 && !nodes.contains_key(&parent) {
+            if let (Some(left), Some(right)) = (nodes.get(&(index & !1)), nodes.get(&(index | 1))) {
+                let parent_hash = Sha256::concat_and_hash(left, Some(right));
+                nodes.insert(parent, parent_hash);
+                keys.push(parent);
+            }
+        }
+        position += 1;
+    }
+
+    nodes
+        .get(&1)
+        .copied()
+        .ok_or_else(Error::not_enough_hashes_to_calculate_root)
+}
+
+/// Verifies a multiproof made by [`ChunkTree::multiproof`] that `leaves` are the nodes at the
+/// generalized indices of a tree with the given root
+pub fn verify_merkle_multiproof(
+    leaves: &[Chunk],
+    proof: &[Chunk],
+    indices: &[GeneralizedIndex],
+    root: Chunk,
+) -> bool {
+    calculate_multi_merkle_root(leaves, proof, indices).is_ok_and(|calculated| calculated == root)
+}
+
+/// [`ChunkTree`] is the tree of merkleized chunks, which can make proofs for any of its nodes,
+/// padding included, by their generalized indices. Only the chunks are stored, see
+/// [`MerkleTree::with_padding`].
+///
+/// [`MerkleTree::with_padding`]: crate::MerkleTree::with_padding
+#[derive(Clone)]
+pub struct ChunkTree {
+    merkle_tree: MerkleTree<Sha256>,
+    zero_hashes: Vec<Chunk>,
+    length: Option<usize>,
+}
+
+impl ChunkTree {
+    /// Merkleizes the chunks the same way as [`merkleize`]
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::TreeIsFull`] if there are more chunks than
+    /// `limit`, and [`ErrorKind::InvalidDepth`] if `limit` is too large
+    ///
+    /// [`ErrorKind::TreeIsFull`]: crate::ErrorKind::TreeIsFull
+    /// [`ErrorKind::InvalidDepth`]: crate::ErrorKind::InvalidDepth
+    pub fn new(chunks: &[Chunk], limit: Option<usize>) -> Result<Self, Error> {
+        let padding = match limit {
+            Some(limit) => Padding::to_limit(ZERO_CHUNK, limit)?,
+            None => Padding::to_power_of_two(ZERO_CHUNK),
+        };
+        let depth = padding.padded_leaves_count(chunks.len())?.trailing_zeros() as usize;
+
+        let mut merkle_tree = MerkleTree::new().with_padding(padding)?;
+        merkle_tree.try_append(&mut chunks.to_vec())?.commit();
+
+        Ok(Self {
+            merkle_tree,
+            zero_hashes: zero_hashes(depth + 1),
+            length: None,
+        })
+    }
+
+    /// Mixes the length into the root, as for lists and bitlists. The root of the chunks
+    /// becomes the node 2 and the length becomes the node 3.
+    pub fn with_length(mut self, length: usize) -> Self {
+        self.length = Some(length);
+        self
+    }
+
+    /// Returns the root of the tree, with the length mixed in if there's one
+    pub fn root(&self) -> Chunk {
+        let chunks_root = self.chunks_root();
+        match self.length {
+            Some(length) => mix_in_length(chunks_root, length),
+            None => chunks_root,
+        }
+    }
+
+    /// Returns how many layers there are between the chunks and the root, including the
+    /// length mix-in
+    pub fn depth(&self) -> usize {
+        self.chunks_depth() + self.length.map_or(0, |_| 1)
+    }
+
+    /// Returns the generalized index of the chunk at the position
+    pub fn chunk_generalized_index(&self, chunk_index: usize) -> GeneralizedIndex {
+        (1 << self.depth()) | chunk_index
+    }
+
+    /// Returns the node at the generalized index, or `None` if there's no such node
+    pub fn node(&self, index: GeneralizedIndex) -> Option<Chunk> {
+        let chunks_index = match self.length {
+            Some(length) => match index {
+                0 => return None,
+                1 => return Some(self.root()),
+                3 => return Some(length_chunk(length)),
+                _ => {
+                    // Replacing the node 2 with the node 1 in the path
+                    let length = generalized_index_length(index);
+                    if generalized_index_bit(index, length - 1) {
+                        return None;
+                    }
+                    index ^ (3 << (length - 1))
+                }
+            },
+            None => index,
+        };
+
+        let length = generalized_index_length(chunks_index);
+        let layer_index = self.chunks_depth().checked_sub(length)?;
+        if chunks_index == 0 {
+            return None;
+        }
+        let node = self
+            .merkle_tree
+            .node(layer_index, subtree_index(chunks_index))
+            .unwrap_or(self.zero_hashes[layer_index]);
+        Some(node)
+    }
+
+    /// Returns the proof of the node at the generalized index: the siblings of the node and
+    /// its ancestors, from the bottom up
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidGeneralizedIndex`] if there's no such node
+    ///
+    /// [`ErrorKind::InvalidGeneralizedIndex`]: crate::ErrorKind::InvalidGeneralizedIndex
+    pub fn proof(&self, index: GeneralizedIndex) -> Result<Vec<Chunk>, Error> {
+        self.multiproof(&[index])
+    }
+
+    /// Returns the multiproof of the nodes at the generalized indices: the nodes returned by
+    /// [`helper_indices`], in the same order
+    ///
+    /// ## Errors
+    ///
+    /// Returns an error of kind [`ErrorKind::InvalidGeneralizedIndex`] if there's no node at
+    /// one of the indices
+    ///
+    /// [`ErrorKind::InvalidGeneralizedIndex`]: crate::ErrorKind::InvalidGeneralizedIndex
+    pub fn multiproof(&self, indices: &[GeneralizedIndex]) -> Result<Vec<Chunk>, Error> {
+        if let Some(index) = indices.iter().find(|index| self.node(**index).is_none()) {
+            return Err(Error::invalid_generalized_index(*index));
+        }
+
+        Ok(helper_indices(indices)
+            .into_iter()
+            .filter_map(|index| self.node(index))
+            .collect())
+    }
+
+    fn chunks_root(&self) -> Chunk {
+        // A tree padded with zero chunks always has a root
+        self.merkle_tree
+            .root()
+            .unwrap_or(self.zero_hashes[self.chunks_depth()])
+    }
+
+    fn chunks_depth(&self) -> usize {
+        self.zero_hashes.len() - 1
+    }
+}
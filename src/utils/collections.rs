@@ -1,4 +1,4 @@
-use crate::prelude::*;
+use crate::{prelude::*, Error};
 
 fn byte_to_hex(byte: &u8) -> String {
     format!("{:02x}", byte)
@@ -11,6 +11,24 @@ pub fn to_hex_string<T: Clone + Into<Vec<u8>>>(bytes: &T) -> String {
     hex_vec.join("")
 }
 
+/// Parses a hex string, with or without the `0x` prefix, into bytes
+pub fn from_hex_string(hex: &str) -> Result<Vec<u8>, Error> {
+    let digits = hex.strip_prefix("0x").unwrap_or(hex);
+    if digits.len() % 2 != 0 {
+        return Err(Error::invalid_hex_string(hex));
+    }
+
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            digits
+                .get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| Error::invalid_hex_string(hex))
+        })
+        .collect()
+}
+
 /// Find a difference between two vectors and return a third vector
 /// containing the difference. This function preserves the first
 /// vector order.
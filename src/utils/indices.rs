@@ -1,5 +1,11 @@
-use crate::{prelude::*, utils};
+use crate::prelude::*;
+#[cfg(feature = "alloc")]
+use crate::utils;
+#[cfg(feature = "alloc")]
+use crate::Error;
+#[cfg(feature = "alloc")]
 use alloc::collections::BTreeMap;
+use core::ops::Range;
 
 pub fn is_left_index(index: usize) -> bool {
     index % 2 == 0
@@ -14,6 +20,7 @@ pub fn get_sibling_index(index: usize) -> usize {
     index - 1
 }
 
+#[cfg(feature = "alloc")]
 pub fn sibling_indices(indices: &[usize]) -> Vec<usize> {
     indices.iter().cloned().map(get_sibling_index).collect()
 }
@@ -25,6 +32,7 @@ pub fn parent_index(index: usize) -> usize {
     get_sibling_index(index) / 2
 }
 
+#[cfg(feature = "alloc")]
 pub fn parent_indices(indices: &[usize]) -> Vec<usize> {
     let mut parents: Vec<usize> = indices.iter().cloned().map(parent_index).collect();
     parents.dedup();
@@ -35,6 +43,94 @@ pub fn tree_depth(leaves_count: usize) -> usize {
     8 * core::mem::size_of::<usize>() - leaves_count.leading_zeros() as usize
 }
 
+/// Sorts leaf indices and checks that they can be used to make a proof for a tree of
+/// `leaves_len` leaves
+#[cfg(feature = "alloc")]
+pub fn sorted_leaf_indices(leaf_indices: &[usize], leaves_len: usize) -> Result<Vec<usize>, Error> {
+    if leaves_len == 0 {
+        return Err(Error::empty_tree());
+    }
+    if leaf_indices.is_empty() {
+        return Err(Error::empty_leaf_indices());
+    }
+
+    let mut sorted_indices = leaf_indices.to_vec();
+    sorted_indices.sort_unstable();
+
+    for pair in sorted_indices.windows(2) {
+        if pair[0] == pair[1] {
+            return Err(Error::duplicate_leaf_index(pair[0]));
+        }
+    }
+    if let Some(index) = sorted_indices.iter().find(|index| **index >= leaves_len) {
+        return Err(Error::leaf_index_out_of_range(*index, leaves_len));
+    }
+
+    Ok(sorted_indices)
+}
+
+/// Returns the index of the parent of a node in a tree where every node has up to `arity`
+/// children
+pub fn parent_index_with_arity(index: usize, arity: usize) -> usize {
+    index / arity
+}
+
+/// Returns indices of the nodes that have the same parent as the node, including the node
+/// itself, in a layer of `layer_len` nodes
+pub fn sibling_group_with_arity(index: usize, arity: usize, layer_len: usize) -> Range<usize> {
+    let first_sibling = index - index % arity;
+    first_sibling..(first_sibling + arity).min(layer_len)
+}
+
+/// Returns how many layers there are above the leaves in a tree where every node has up to
+/// `arity` children. Unlike [`tree_depth`], a tree of a single leaf has depth 0.
+pub fn tree_depth_with_arity(leaves_count: usize, arity: usize) -> usize {
+    let mut depth = 0;
+    let mut layer_len = leaves_count;
+    while layer_len > 1 {
+        layer_len = div_ceil(layer_len, arity);
+        depth += 1;
+    }
+    depth
+}
+
+/// Same as [`proof_indices_by_layers`], but for a tree where every node has up to `arity`
+/// children
+#[cfg(feature = "alloc")]
+pub fn proof_indices_by_layers_with_arity(
+    sorted_leaf_indices: &[usize],
+    leaves_count: usize,
+    arity: usize,
+) -> Vec<Vec<usize>> {
+    let depth = tree_depth_with_arity(leaves_count, arity);
+
+    let mut layer_nodes = sorted_leaf_indices.to_vec();
+    let mut layer_len = leaves_count;
+    let mut proof_indices: Vec<Vec<usize>> = Vec::with_capacity(depth);
+
+    for _ in 0..depth {
+        let mut siblings: Vec<usize> = layer_nodes
+            .iter()
+            .flat_map(|index| sibling_group_with_arity(*index, arity, layer_len))
+            .collect();
+        // Nodes with the same parent have the same siblings
+        siblings.sort_unstable();
+        siblings.dedup();
+
+        proof_indices.push(utils::collections::difference(&siblings, &layer_nodes));
+
+        layer_nodes = layer_nodes
+            .iter()
+            .map(|index| parent_index_with_arity(*index, arity))
+            .collect();
+        layer_nodes.dedup();
+        layer_len = div_ceil(layer_len, arity);
+    }
+
+    proof_indices
+}
+
+#[cfg(feature = "alloc")]
 pub fn uneven_layers(tree_leaves_count: usize) -> BTreeMap<usize, usize> {
     let mut leaves_count = tree_leaves_count;
     let depth = tree_depth(tree_leaves_count);
@@ -53,6 +149,7 @@ pub fn uneven_layers(tree_leaves_count: usize) -> BTreeMap<usize, usize> {
 }
 
 /// Returns layered proof indices
+#[cfg(feature = "alloc")]
 pub fn proof_indices_by_layers(
     sorted_leaf_indices: &[usize],
     leaves_count: usize,
@@ -87,6 +184,73 @@ pub fn proof_indices_by_layers(
     proof_indices
 }
 
+/// Returns layered proof indices for the leaves in the range `[start, end)`. Nodes inside
+/// the range can be calculated from the leaves, so only siblings of the leftmost and the
+/// rightmost nodes of each layer are needed. Gives the same indices as
+/// [`proof_indices_by_layers`] for the same leaves, without comparing the indices to each other.
+#[cfg(feature = "alloc")]
+pub fn range_proof_indices_by_layers(
+    start: usize,
+    end: usize,
+    leaves_count: usize,
+) -> Vec<Vec<usize>> {
+    let depth = tree_depth(leaves_count);
+
+    let mut proof_indices: Vec<Vec<usize>> = Vec::with_capacity(depth);
+    let (mut start, mut end, mut layer_len) = (start, end, leaves_count);
+
+    for _ in 0..depth {
+        let mut layer_proof_indices = Vec::new();
+        // The leftmost node is a right child, so its left sibling is needed
+        if !is_left_index(start) {
+            layer_proof_indices.push(start - 1);
+        }
+        // The rightmost node is a left child, so its right sibling is needed if there's one
+        if !is_left_index(end) && end < layer_len {
+            layer_proof_indices.push(end);
+        }
+
+        proof_indices.push(layer_proof_indices);
+        start /= 2;
+        end = div_ceil(end, 2);
+        layer_len = div_ceil(layer_len, 2);
+    }
+
+    proof_indices
+}
+
+/// Returns `true` if a node has the same hash in a tree of `old_leaves_count` leaves and in
+/// the same tree after appending leaves to it, up to `new_leaves_count`. A node depends only on
+/// the leaves below it, so it stays the same if it existed before and no leaves were added
+/// below it.
+pub fn is_node_unchanged(
+    layer_index: usize,
+    node_index: usize,
+    old_leaves_count: usize,
+    new_leaves_count: usize,
+) -> bool {
+    let subtree_width = 1usize << layer_index;
+    let first_leaf = node_index.saturating_mul(subtree_width);
+    let leaves_end = (node_index + 1).saturating_mul(subtree_width);
+
+    first_leaf < old_leaves_count
+        && leaves_end.min(old_leaves_count) == leaves_end.min(new_leaves_count)
+}
+
+/// Returns how many nodes there are in the layer `layer_index` of a tree with `leaves_count`
+/// leaves. Layers above the root have one node, so the loop stops at the root instead of
+/// running `layer_index` times.
+pub fn layer_len(leaves_count: usize, layer_index: usize) -> usize {
+    let mut len = leaves_count;
+    for _ in 0..layer_index {
+        if len <= 1 {
+            break;
+        }
+        len = div_ceil(len, 2);
+    }
+    len
+}
+
 pub fn div_ceil(x: usize, y: usize) -> usize {
     x / y + if x % y != 0 { 1 } else { 0 }
 }
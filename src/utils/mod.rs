@@ -1,3 +1,6 @@
 //! Utilities used internally to manipulate tree indices
+#[cfg(feature = "alloc")]
 pub mod collections;
 pub mod indices;
+#[cfg(feature = "alloc")]
+pub mod varint;
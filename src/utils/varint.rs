@@ -0,0 +1,40 @@
+use crate::prelude::*;
+
+/// The maximum number of bytes a `usize` varint can take
+pub const MAX_LEN: usize = (usize::BITS as usize).div_ceil(7);
+
+/// Appends `value` to `bytes` as an unsigned LEB128 varint: 7 bits per byte, least
+/// significant group first, with the high bit set on every byte but the last one.
+pub fn encode(mut value: usize, bytes: &mut Vec<u8>) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+/// Decodes an unsigned LEB128 varint from the beginning of `bytes`. Returns the value and the
+/// number of bytes it took, or `None` if the varint is truncated, doesn't fit into `usize` or
+/// isn't in its shortest form, so every value has exactly one encoding.
+pub fn decode(bytes: &[u8]) -> Option<(usize, usize)> {
+    let mut value: usize = 0;
+
+    for (position, byte) in bytes.iter().enumerate() {
+        let shift = 7 * position as u32;
+        let group = (*byte & 0x7f) as usize;
+        if shift >= usize::BITS || (group << shift) >> shift != group {
+            return None;
+        }
+        value |= group << shift;
+
+        if *byte & 0x80 == 0 {
+            // A zero last byte only adds a redundant group, like in 0x80 0x00
+            if *byte == 0 && position > 0 {
+                return None;
+            }
+            return Some((value, position + 1));
+        }
+    }
+
+    None
+}
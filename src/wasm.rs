@@ -0,0 +1,126 @@
+//! JavaScript bindings built with [`wasm-bindgen`](https://docs.rs/wasm-bindgen).
+//!
+//! Exposes tree construction, roots, proof generation and proof verification with the same
+//! code the Rust API uses. Hashes are passed as `Uint8Array`s or hex strings; several hashes
+//! are passed as one array of concatenated hashes. `algorithm` is one of `"sha256"`,
+//! `"sha384"` and `"keccak256"`. Proofs are serialized with [`DirectHashesOrder`].
+//!
+//! ```js
+//! import { MerkleTree, hash, verifyProof } from "rs_merkle";
+//!
+//! const encoder = new TextEncoder();
+//! const a = hash("sha256", encoder.encode("a"));
+//! const b = hash("sha256", encoder.encode("b"));
+//! const tree = new MerkleTree("sha256", new Uint8Array([...a, ...b]));
+//! const proof = tree.proof(new Uint32Array([1]));
+//! verifyProof("sha256", proof, tree.root(), new Uint32Array([1]), b, 2); // true
+//! ```
+//!
+//! [`DirectHashesOrder`]: crate::proof_serializers::DirectHashesOrder
+
+use crate::{
+    dynamic::{Algorithm, AnyTree},
+    prelude::*,
+    utils, Error,
+};
+use wasm_bindgen::prelude::*;
+
+impl From<Error> for JsValue {
+    fn from(error: Error) -> Self {
+        JsError::new(&error.to_string()).into()
+    }
+}
+
+/// [`MerkleTree`](crate::MerkleTree) exposed to JavaScript as `MerkleTree`
+#[wasm_bindgen(js_name = MerkleTree)]
+pub struct WasmMerkleTree {
+    tree: AnyTree,
+}
+
+#[wasm_bindgen(js_class = MerkleTree)]
+impl WasmMerkleTree {
+    /// Builds a tree from concatenated leaf hashes
+    #[wasm_bindgen(constructor)]
+    pub fn new(algorithm: &str, leaves: &[u8]) -> Result<WasmMerkleTree, Error> {
+        let mut tree = AnyTree::new(Algorithm::from_name(algorithm)?);
+        tree.append(leaves)?;
+        tree.commit();
+
+        Ok(Self { tree })
+    }
+
+    /// Returns the root, or `undefined` if the tree is empty
+    pub fn root(&self) -> Option<Vec<u8>> {
+        self.tree.root()
+    }
+
+    /// Returns the hex encoded root, or `undefined` if the tree is empty
+    #[wasm_bindgen(js_name = rootHex)]
+    pub fn root_hex(&self) -> Option<String> {
+        self.tree.root_hex()
+    }
+
+    /// Returns the number of leaves
+    #[wasm_bindgen(js_name = leavesLen)]
+    pub fn leaves_len(&self) -> usize {
+        self.tree.leaves_len()
+    }
+
+    /// Returns a serialized proof for the leaves at the given indices
+    pub fn proof(&self, leaf_indices: &[usize]) -> Result<Vec<u8>, Error> {
+        self.tree.proof(leaf_indices)
+    }
+
+    /// Same as `proof`, but returns the proof hex encoded
+    #[wasm_bindgen(js_name = proofHex)]
+    pub fn proof_hex(&self, leaf_indices: &[usize]) -> Result<String, Error> {
+        Ok(utils::collections::to_hex_string(
+            &self.proof(leaf_indices)?,
+        ))
+    }
+}
+
+/// Hashes data with the given algorithm
+#[wasm_bindgen]
+pub fn hash(algorithm: &str, data: &[u8]) -> Result<Vec<u8>, Error> {
+    Ok(Algorithm::from_name(algorithm)?.hash(data))
+}
+
+/// Returns the size of a hash of the given algorithm in bytes
+#[wasm_bindgen(js_name = hashSize)]
+pub fn hash_size(algorithm: &str) -> Result<usize, Error> {
+    Ok(Algorithm::from_name(algorithm)?.hash_size())
+}
+
+/// Verifies a serialized proof, see [`MerkleProof::verify`](crate::MerkleProof::verify)
+#[wasm_bindgen(js_name = verifyProof)]
+pub fn verify_proof(
+    algorithm: &str,
+    proof: &[u8],
+    root: &[u8],
+    leaf_indices: &[usize],
+    leaves: &[u8],
+    total_leaves_count: usize,
+) -> Result<bool, Error> {
+    Algorithm::from_name(algorithm)?.verify(proof, root, leaf_indices, leaves, total_leaves_count)
+}
+
+/// Same as `verifyProof`, but takes the proof, the root and concatenated leaves hex encoded
+#[wasm_bindgen(js_name = verifyProofHex)]
+pub fn verify_proof_hex(
+    algorithm: &str,
+    proof_hex: &str,
+    root_hex: &str,
+    leaf_indices: &[usize],
+    leaves_hex: &str,
+    total_leaves_count: usize,
+) -> Result<bool, Error> {
+    verify_proof(
+        algorithm,
+        &utils::collections::from_hex_string(proof_hex)?,
+        &utils::collections::from_hex_string(root_hex)?,
+        leaf_indices,
+        &utils::collections::from_hex_string(leaves_hex)?,
+        total_leaves_count,
+    )
+}
@@ -0,0 +1,63 @@
+/*
+ * Builds a tree, makes and verifies a proof through the C API. Compiled and run by
+ * tests/ffi_test.rs, exits with a non-zero status on the first failed check.
+ */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "rs_merkle.h"
+
+#define CHECK(condition)                                                  \
+    do {                                                                  \
+        if (!(condition)) {                                               \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, \
+                    #condition);                                          \
+            return 1;                                                     \
+        }                                                                 \
+    } while (0)
+
+static const char *EXPECTED_ROOT =
+    "1f7379539707bcaea00564168d1d4d626b09b73f8a2a365234c62d763f854da2";
+
+int main(void) {
+    const char *values[] = {"a", "b", "c", "d", "e", "f"};
+    uint8_t leaves[6 * 32];
+    uint8_t root[32];
+    uint8_t proof[256];
+    char root_hex[65];
+    size_t written = 0;
+    size_t i;
+
+    CHECK(rs_merkle_hash_size(RS_MERKLE_SHA256) == 32);
+    for (i = 0; i < 6; i++) {
+        CHECK(rs_merkle_hash(RS_MERKLE_SHA256, (const uint8_t *)values[i], 1, leaves + i * 32,
+                             32) == RS_MERKLE_OK);
+    }
+
+    RsMerkleTree *tree = rs_merkle_tree_new(RS_MERKLE_SHA256);
+    CHECK(tree != NULL);
+    CHECK(rs_merkle_tree_root(tree, root, sizeof(root)) == RS_MERKLE_EMPTY_TREE);
+    CHECK(rs_merkle_tree_append(tree, leaves, sizeof(leaves)) == RS_MERKLE_OK);
+    CHECK(rs_merkle_tree_commit(tree) == RS_MERKLE_OK);
+    CHECK(rs_merkle_tree_leaves_len(tree) == 6);
+
+    CHECK(rs_merkle_tree_root(tree, root, sizeof(root)) == RS_MERKLE_OK);
+    for (i = 0; i < 32; i++) {
+        sprintf(root_hex + i * 2, "%02x", root[i]);
+    }
+    CHECK(strcmp(root_hex, EXPECTED_ROOT) == 0);
+
+    const size_t indices[] = {3, 4};
+    CHECK(rs_merkle_tree_proof(tree, indices, 2, proof, sizeof(proof), &written) ==
+          RS_MERKLE_OK);
+    CHECK(written == 3 * 32);
+    CHECK(rs_merkle_verify(RS_MERKLE_SHA256, proof, written, root, sizeof(root), indices, 2,
+                           leaves + 3 * 32, 2 * 32, 6) == 1);
+    CHECK(rs_merkle_verify(RS_MERKLE_SHA256, proof, written, root, sizeof(root), indices, 2,
+                           leaves + 2 * 32, 2 * 32, 6) == 0);
+
+    rs_merkle_tree_free(tree);
+    printf("ok\n");
+    return 0;
+}
@@ -0,0 +1,131 @@
+#![cfg(feature = "cli")]
+
+pub mod cli {
+    use std::{fs, path::PathBuf, process::Command};
+
+    const ROOT: &str = "1f7379539707bcaea00564168d1d4d626b09b73f8a2a365234c62d763f854da2";
+
+    fn temp_file(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("rs-merkle-{}-{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn run(args: &[&str]) -> (i32, String) {
+        let output = Command::new(env!("CARGO_BIN_EXE_rs-merkle"))
+            .args(args)
+            .output()
+            .unwrap();
+        (
+            output.status.code().unwrap(),
+            String::from_utf8(output.stdout).unwrap().trim().to_string(),
+        )
+    }
+
+    #[test]
+    pub fn should_print_the_root() {
+        let raw_leaves = temp_file("raw-leaves", "a\nb\nc\nd\ne\nf\n");
+        let raw_leaves = raw_leaves.to_str().unwrap();
+        assert_eq!(
+            run(&["root", "--leaves", raw_leaves, "--raw"]),
+            (0, ROOT.into())
+        );
+
+        let hex_leaves = temp_file(
+            "hex-leaves",
+            "ca978112ca1bbdcafac231b39a23dc4da786eff8147c4e72b9807785afee48bb\n\
+             3e23e8160039594a33894f6564e1b1348bbd7a0088d42c4acb73eeaed59c009d\n",
+        );
+        assert_eq!(
+            run(&["root", "--leaves", hex_leaves.to_str().unwrap()]),
+            (
+                0,
+                "e5a01fee14e0ed5c48714f22180f25ad8365b53f9779f79dc4a3d7e93963f94a".into()
+            )
+        );
+    }
+
+    #[test]
+    pub fn should_issue_and_verify_proofs() {
+        let leaves = temp_file("leaves", "a\nb\nc\nd\ne\nf\n");
+        let leaves = leaves.to_str().unwrap();
+
+        for format in ["direct", "reverse", "compact"] {
+            let (code, proof_hex) = run(&[
+                "proof",
+                "--leaves",
+                leaves,
+                "--raw",
+                "--indices",
+                "3,4",
+                "--format",
+                format,
+            ]);
+            assert_eq!(code, 0);
+            let proof = temp_file(&format!("proof-{}", format), &proof_hex);
+            let proof = proof.to_str().unwrap();
+
+            let verify = |leaf: &str| {
+                run(&[
+                    "verify",
+                    "--root",
+                    ROOT,
+                    "--proof",
+                    proof,
+                    "--format",
+                    format,
+                    "--raw",
+                    "--indices",
+                    "3,4",
+                    "--total",
+                    "6",
+                    "--leaf",
+                    "d",
+                    "--leaf",
+                    leaf,
+                ])
+            };
+            assert_eq!(verify("e"), (0, "valid".into()));
+            assert_eq!(verify("x"), (1, "invalid".into()));
+        }
+    }
+
+    #[test]
+    pub fn should_verify_compact_proofs_without_indices() {
+        let leaves = temp_file("compact-leaves", "a\nb\nc\nd\ne\nf\n");
+        let (_, proof_hex) = run(&[
+            "proof",
+            "--leaves",
+            leaves.to_str().unwrap(),
+            "--raw",
+            "--indices",
+            "2",
+            "--format",
+            "compact",
+        ]);
+        let proof = temp_file("compact-proof", &proof_hex);
+
+        assert_eq!(
+            run(&[
+                "verify",
+                "--root",
+                ROOT,
+                "--proof",
+                proof.to_str().unwrap(),
+                "--format",
+                "compact",
+                "--raw",
+                "--leaf",
+                "c",
+            ]),
+            (0, "valid".into())
+        );
+    }
+
+    #[test]
+    pub fn should_fail_on_bad_arguments() {
+        assert_eq!(run(&["root", "--algorithm", "md5"]).0, 2);
+        assert_eq!(run(&["root", "--leaves", "/nonexistent/leaves"]).0, 2);
+        assert_eq!(run(&["fly"]).0, 2);
+    }
+}
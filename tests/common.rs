@@ -25,7 +25,7 @@ pub struct TestDataKeccak256 {
 }
 
 fn combine<T: Clone>(active: Vec<T>, rest: Vec<T>, mut combinations: Vec<Vec<T>>) -> Vec<Vec<T>> {
-    return if rest.is_empty() {
+    if rest.is_empty() {
         if active.is_empty() {
             combinations
         } else {
@@ -42,7 +42,7 @@ fn combine<T: Clone>(active: Vec<T>, rest: Vec<T>, mut combinations: Vec<Vec<T>>
         combinations = combine(next, rest.clone().drain(1..).collect(), combinations);
         combinations = combine(active, rest.clone().drain(1..).collect(), combinations);
         combinations
-    };
+    }
 }
 
 /// Create all possible combinations of elements inside a vector without duplicates
@@ -50,6 +50,13 @@ pub fn combinations<T: Clone>(vec: Vec<T>) -> Vec<Vec<T>> {
     combine(Vec::new(), vec, Vec::new())
 }
 
+/// Hashes of the numbers from zero to `count`, for trees of any size
+pub fn leaves(count: usize) -> Vec<[u8; 32]> {
+    (0..count)
+        .map(|x| Sha256::hash(&(x as u64).to_le_bytes()))
+        .collect()
+}
+
 pub fn setup() -> TestData {
     let leaf_values = ["a", "b", "c", "d", "e", "f"];
     let expected_root_hex = "1f7379539707bcaea00564168d1d4d626b09b73f8a2a365234c62d763f854da2";
@@ -0,0 +1,393 @@
+#![cfg(feature = "ffi")]
+
+pub mod ffi {
+    // Links the library, the functions are called through the declarations below
+    extern crate rs_merkle;
+
+    use rs_merkle::{
+        algorithms::Sha256,
+        ffi::{
+            RS_MERKLE_BUFFER_TOO_SMALL, RS_MERKLE_EMPTY_TREE, RS_MERKLE_INVALID_ARGUMENT,
+            RS_MERKLE_KECCAK256, RS_MERKLE_OK, RS_MERKLE_PROOF_ERROR, RS_MERKLE_SHA256,
+            RS_MERKLE_SHA384,
+        },
+        Hasher,
+    };
+    use std::ptr;
+
+    #[repr(C)]
+    pub struct RsMerkleTree {
+        _private: [u8; 0],
+    }
+
+    extern "C" {
+        fn rs_merkle_hash_size(algorithm_code: u32) -> usize;
+        fn rs_merkle_hash(
+            algorithm_code: u32,
+            data: *const u8,
+            data_len: usize,
+            out: *mut u8,
+            out_len: usize,
+        ) -> i32;
+        fn rs_merkle_tree_new(algorithm_code: u32) -> *mut RsMerkleTree;
+        fn rs_merkle_tree_free(tree: *mut RsMerkleTree);
+        fn rs_merkle_tree_append(tree: *mut RsMerkleTree, leaves: *const u8, len: usize) -> i32;
+        fn rs_merkle_tree_commit(tree: *mut RsMerkleTree) -> i32;
+        fn rs_merkle_tree_rollback(tree: *mut RsMerkleTree) -> i32;
+        fn rs_merkle_tree_leaves_len(tree: *const RsMerkleTree) -> usize;
+        fn rs_merkle_tree_root(tree: *const RsMerkleTree, out: *mut u8, out_len: usize) -> i32;
+        fn rs_merkle_tree_proof(
+            tree: *const RsMerkleTree,
+            leaf_indices: *const usize,
+            leaf_indices_len: usize,
+            out: *mut u8,
+            out_len: usize,
+            written: *mut usize,
+        ) -> i32;
+        fn rs_merkle_verify(
+            algorithm_code: u32,
+            proof: *const u8,
+            proof_len: usize,
+            root: *const u8,
+            root_len: usize,
+            leaf_indices: *const usize,
+            leaf_indices_len: usize,
+            leaves: *const u8,
+            leaves_len: usize,
+            total_leaves_count: usize,
+        ) -> i32;
+    }
+
+    const ROOT: &str = "1f7379539707bcaea00564168d1d4d626b09b73f8a2a365234c62d763f854da2";
+
+    fn leaves() -> Vec<u8> {
+        ["a", "b", "c", "d", "e", "f"]
+            .iter()
+            .flat_map(|leaf| Sha256::hash(leaf.as_bytes()))
+            .collect()
+    }
+
+    unsafe fn tree_of(leaves: &[u8]) -> *mut RsMerkleTree {
+        let tree = rs_merkle_tree_new(RS_MERKLE_SHA256);
+        assert_eq!(
+            rs_merkle_tree_append(tree, leaves.as_ptr(), leaves.len()),
+            RS_MERKLE_OK
+        );
+        assert_eq!(rs_merkle_tree_commit(tree), RS_MERKLE_OK);
+        tree
+    }
+
+    unsafe fn root_of(tree: *const RsMerkleTree) -> Result<[u8; 32], i32> {
+        let mut root = [0u8; 32];
+        match rs_merkle_tree_root(tree, root.as_mut_ptr(), root.len()) {
+            RS_MERKLE_OK => Ok(root),
+            status => Err(status),
+        }
+    }
+
+    unsafe fn proof_of(tree: *const RsMerkleTree, leaf_indices: &[usize]) -> Result<Vec<u8>, i32> {
+        let mut written = 0;
+        let status = rs_merkle_tree_proof(
+            tree,
+            leaf_indices.as_ptr(),
+            leaf_indices.len(),
+            ptr::null_mut(),
+            0,
+            &mut written,
+        );
+        if status != RS_MERKLE_BUFFER_TOO_SMALL {
+            return Err(status);
+        }
+
+        let mut proof = vec![0u8; written];
+        match rs_merkle_tree_proof(
+            tree,
+            leaf_indices.as_ptr(),
+            leaf_indices.len(),
+            proof.as_mut_ptr(),
+            proof.len(),
+            &mut written,
+        ) {
+            RS_MERKLE_OK => Ok(proof),
+            status => Err(status),
+        }
+    }
+
+    unsafe fn verify(proof: &[u8], root: &[u8], leaf_indices: &[usize], leaves: &[u8]) -> i32 {
+        rs_merkle_verify(
+            RS_MERKLE_SHA256,
+            proof.as_ptr(),
+            proof.len(),
+            root.as_ptr(),
+            root.len(),
+            leaf_indices.as_ptr(),
+            leaf_indices.len(),
+            leaves.as_ptr(),
+            leaves.len(),
+            6,
+        )
+    }
+
+    #[test]
+    pub fn should_hash_with_every_algorithm() {
+        unsafe {
+            assert_eq!(rs_merkle_hash_size(RS_MERKLE_SHA256), 32);
+            assert_eq!(rs_merkle_hash_size(RS_MERKLE_SHA384), 48);
+            assert_eq!(rs_merkle_hash_size(RS_MERKLE_KECCAK256), 32);
+            assert_eq!(rs_merkle_hash_size(42), 0);
+
+            let mut hash = [0u8; 32];
+            assert_eq!(
+                rs_merkle_hash(RS_MERKLE_SHA256, b"a".as_ptr(), 1, hash.as_mut_ptr(), 32),
+                RS_MERKLE_OK
+            );
+            assert_eq!(hash, Sha256::hash(b"a"));
+            assert_eq!(
+                rs_merkle_hash(RS_MERKLE_SHA384, b"a".as_ptr(), 1, hash.as_mut_ptr(), 32),
+                RS_MERKLE_BUFFER_TOO_SMALL
+            );
+            assert_eq!(
+                rs_merkle_hash(42, b"a".as_ptr(), 1, hash.as_mut_ptr(), 32),
+                RS_MERKLE_INVALID_ARGUMENT
+            );
+        }
+    }
+
+    #[test]
+    pub fn should_build_a_tree_and_get_the_root() {
+        unsafe {
+            let tree = tree_of(&leaves());
+
+            assert_eq!(rs_merkle_tree_leaves_len(tree), 6);
+            assert_eq!(
+                root_of(tree).map(|root| rs_merkle::utils::collections::to_hex_string(&root)),
+                Ok(ROOT.to_string())
+            );
+
+            let mut short = [0u8; 31];
+            assert_eq!(
+                rs_merkle_tree_root(tree, short.as_mut_ptr(), short.len()),
+                RS_MERKLE_BUFFER_TOO_SMALL
+            );
+
+            rs_merkle_tree_free(tree);
+        }
+    }
+
+    #[test]
+    pub fn should_make_and_verify_a_proof() {
+        unsafe {
+            let leaves = leaves();
+            let tree = tree_of(&leaves);
+            let root = root_of(tree).unwrap();
+
+            let proof = proof_of(tree, &[3, 4]).unwrap();
+            assert_eq!(verify(&proof, &root, &[3, 4], &leaves[96..160]), 1);
+            assert_eq!(verify(&proof, &root, &[3, 4], &leaves[64..128]), 0);
+            assert_eq!(
+                verify(&proof[1..], &root, &[3, 4], &leaves[96..160]),
+                RS_MERKLE_INVALID_ARGUMENT
+            );
+
+            assert_eq!(proof_of(tree, &[6]), Err(RS_MERKLE_PROOF_ERROR));
+            assert_eq!(proof_of(tree, &[]), Err(RS_MERKLE_PROOF_ERROR));
+
+            rs_merkle_tree_free(tree);
+        }
+    }
+
+    #[test]
+    pub fn should_commit_and_rollback() {
+        unsafe {
+            let leaves = leaves();
+            let tree = rs_merkle_tree_new(RS_MERKLE_SHA256);
+            assert_eq!(root_of(tree), Err(RS_MERKLE_EMPTY_TREE));
+            assert_eq!(proof_of(tree, &[0]), Err(RS_MERKLE_EMPTY_TREE));
+
+            rs_merkle_tree_append(tree, leaves.as_ptr(), 128);
+            assert_eq!(rs_merkle_tree_leaves_len(tree), 0);
+            rs_merkle_tree_commit(tree);
+            let first_root = root_of(tree).unwrap();
+
+            rs_merkle_tree_append(tree, leaves[128..].as_ptr(), 64);
+            rs_merkle_tree_commit(tree);
+            assert_eq!(rs_merkle_tree_leaves_len(tree), 6);
+
+            assert_eq!(rs_merkle_tree_rollback(tree), RS_MERKLE_OK);
+            assert_eq!(rs_merkle_tree_leaves_len(tree), 4);
+            assert_eq!(root_of(tree), Ok(first_root));
+
+            rs_merkle_tree_free(tree);
+        }
+    }
+
+    #[test]
+    pub fn should_reject_invalid_arguments() {
+        unsafe {
+            assert!(rs_merkle_tree_new(42).is_null());
+            rs_merkle_tree_free(ptr::null_mut());
+
+            assert_eq!(
+                rs_merkle_tree_commit(ptr::null_mut()),
+                RS_MERKLE_INVALID_ARGUMENT
+            );
+            assert_eq!(
+                rs_merkle_tree_rollback(ptr::null_mut()),
+                RS_MERKLE_INVALID_ARGUMENT
+            );
+            assert_eq!(rs_merkle_tree_leaves_len(ptr::null()), 0);
+            assert_eq!(root_of(ptr::null()), Err(RS_MERKLE_INVALID_ARGUMENT));
+
+            let tree = rs_merkle_tree_new(RS_MERKLE_SHA256);
+            assert_eq!(
+                rs_merkle_tree_append(tree, ptr::null(), 32),
+                RS_MERKLE_INVALID_ARGUMENT
+            );
+            assert_eq!(
+                rs_merkle_tree_append(tree, leaves().as_ptr(), 33),
+                RS_MERKLE_INVALID_ARGUMENT
+            );
+            assert_eq!(rs_merkle_tree_append(tree, ptr::null(), 0), RS_MERKLE_OK);
+
+            let mut written = 0;
+            assert_eq!(
+                rs_merkle_tree_proof(tree, [0].as_ptr(), 1, ptr::null_mut(), 0, ptr::null_mut()),
+                RS_MERKLE_INVALID_ARGUMENT
+            );
+            assert_eq!(
+                rs_merkle_tree_proof(tree, ptr::null(), 1, ptr::null_mut(), 0, &mut written),
+                RS_MERKLE_INVALID_ARGUMENT
+            );
+
+            rs_merkle_tree_free(tree);
+        }
+    }
+
+    /// Spells a Rust type of the C API the way the header does
+    fn c_type(rust_type: &str) -> String {
+        if let Some(pointee) = rust_type.strip_prefix("*const ") {
+            return format!("const {} *", c_type(pointee));
+        }
+        if let Some(pointee) = rust_type.strip_prefix("*mut ") {
+            return format!("{} *", c_type(pointee));
+        }
+        match rust_type {
+            "u8" => "uint8_t",
+            "u32" => "uint32_t",
+            "i32" => "int32_t",
+            "usize" => "size_t",
+            "RsMerkleTree" => "RsMerkleTree",
+            _ => panic!("{} has no C counterpart", rust_type),
+        }
+        .to_string()
+    }
+
+    /// Removes whitespace, so declarations compare regardless of how they are wrapped
+    fn squeeze(declaration: &str) -> String {
+        declaration.split_whitespace().collect()
+    }
+
+    #[test]
+    pub fn should_declare_every_function_and_constant_in_the_header() {
+        let header = squeeze(include_str!("../include/rs_merkle.h"));
+        let source = include_str!("../src/ffi.rs");
+
+        let signatures: Vec<&str> = source
+            .split("extern \"C\" fn ")
+            .skip(1)
+            .map(|rest| rest.split('{').next().unwrap())
+            .collect();
+        assert_eq!(signatures.len(), 11);
+
+        for signature in signatures {
+            let (name, rest) = signature.split_once('(').unwrap();
+            let (parameters, return_type) = rest.rsplit_once(')').unwrap();
+            let return_type = match return_type.trim().strip_prefix("->") {
+                Some(return_type) => c_type(return_type.trim()),
+                None => "void".to_string(),
+            };
+            let parameters: Vec<String> = parameters
+                .split(',')
+                .filter(|parameter| !parameter.trim().is_empty())
+                .map(|parameter| {
+                    let (name, rust_type) = parameter.split_once(':').unwrap();
+                    format!("{} {}", c_type(rust_type.trim()), name.trim())
+                })
+                .collect();
+
+            let declaration = format!("{} {}({});", return_type, name, parameters.join(", "));
+            assert!(
+                header.contains(&squeeze(&declaration)),
+                "{} is not declared in the header",
+                declaration
+            );
+        }
+
+        for line in source.lines() {
+            if let Some(constant) = line.strip_prefix("pub const ") {
+                let (name, rest) = constant.split_once(':').unwrap();
+                let value = rest.split('=').nth(1).unwrap().trim_end_matches(';').trim();
+                assert!(
+                    header.contains(&squeeze(&format!("#define {} {}", name, value))),
+                    "{} is not defined as {} in the header",
+                    name,
+                    value
+                );
+            }
+        }
+    }
+
+    /// Builds the static library, compiles tests/c/rs_merkle_test.c against the header, links
+    /// them and runs the program. The library is built into a target directory of its own, which
+    /// keeps it off the lock of the running build.
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn should_compile_and_run_a_c_program() {
+        use std::{env, path::Path, process::Command};
+
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let target_dir = env::current_exe()
+            .unwrap()
+            .parent()
+            .and_then(|deps| deps.parent())
+            .unwrap()
+            .join("c-api");
+        let status = Command::new(env!("CARGO"))
+            .args([
+                "rustc",
+                "--lib",
+                "--features",
+                "ffi",
+                "--crate-type",
+                "staticlib",
+            ])
+            .arg("--manifest-path")
+            .arg(manifest_dir.join("Cargo.toml"))
+            .arg("--target-dir")
+            .arg(&target_dir)
+            .status()
+            .unwrap();
+        assert!(status.success(), "the static library doesn't build");
+
+        let compiler = env::var("CC").unwrap_or_else(|_| "cc".into());
+        let program = target_dir.join("rs_merkle_test");
+        let status = Command::new(&compiler)
+            .args(["-std=c99", "-Wall", "-Werror", "-I"])
+            .arg(manifest_dir.join("include"))
+            .arg(manifest_dir.join("tests/c/rs_merkle_test.c"))
+            .arg(target_dir.join("debug/librs_merkle.a"))
+            .args(["-lpthread", "-ldl", "-lm", "-o"])
+            .arg(&program)
+            .status()
+            .unwrap_or_else(|err| panic!("couldn't run {}: {}", compiler, err));
+        assert!(status.success(), "the C program doesn't compile");
+
+        let output = Command::new(&program).output().unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "ok\n");
+    }
+}
@@ -1,3 +1,4 @@
+#[allow(dead_code)]
 mod common;
 
 pub mod root {
@@ -174,6 +175,101 @@ pub mod root {
     }
 }
 
+pub mod verify_strict {
+    use crate::common;
+    use rayon::prelude::*;
+    use rs_merkle::{algorithms::Sha256, ErrorKind, MerkleProof, MerkleTree};
+
+    #[test]
+    pub fn should_verify_proofs_made_by_the_tree() {
+        let test_cases = common::setup_proof_test_cases();
+
+        test_cases.par_iter().for_each(|test_case| {
+            let merkle_tree = &test_case.merkle_tree;
+            let root = merkle_tree.root().unwrap();
+
+            test_case.cases.par_iter().for_each(|case| {
+                let proof = merkle_tree.proof(&case.leaf_indices_to_prove);
+
+                assert!(proof.verify_strict(
+                    root,
+                    &case.leaf_indices_to_prove,
+                    &case.leaf_hashes_to_prove,
+                    merkle_tree.leaves_len(),
+                ));
+            });
+        });
+    }
+
+    #[test]
+    pub fn should_reject_malleable_proofs() {
+        let test_data = common::setup();
+        let leaf_hashes = &test_data.leaf_hashes;
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(leaf_hashes);
+        let proof = merkle_tree.proof(&[3, 4]);
+        let leaves = [leaf_hashes[3], leaf_hashes[4]];
+
+        let kind = |result: Result<[u8; 32], rs_merkle::Error>| result.err().map(|e| e.kind());
+
+        let mut padded_hashes = proof.proof_hashes().to_vec();
+        padded_hashes.push(leaf_hashes[0]);
+        let padded_proof = MerkleProof::<Sha256>::new(padded_hashes);
+
+        assert_eq!(
+            padded_proof.root(&[3, 4], &leaves, 6).ok(),
+            merkle_tree.root()
+        );
+        assert_eq!(
+            kind(padded_proof.root_strict(&[3, 4], &leaves, 6)),
+            Some(ErrorKind::TooManyProofHashes)
+        );
+        assert_eq!(
+            kind(proof.root_strict(&[3, 3], &leaves, 6)),
+            Some(ErrorKind::DuplicateLeafIndex)
+        );
+        assert_eq!(
+            kind(proof.root_strict(&[3, 6], &leaves, 6)),
+            Some(ErrorKind::LeafIndexOutOfRange)
+        );
+        assert_eq!(
+            kind(proof.root_strict(&[3, 4], &leaves, 0)),
+            Some(ErrorKind::ZeroLeavesCount)
+        );
+    }
+}
+
+pub mod verify_detailed {
+    use crate::common;
+    use rs_merkle::{algorithms::Sha256, ErrorKind, MerkleTree, VerificationError};
+
+    #[test]
+    pub fn should_tell_root_mismatch_from_malformed_proof() {
+        let test_data = common::setup();
+        let leaf_hashes = &test_data.leaf_hashes;
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(leaf_hashes);
+        let root = merkle_tree.root().unwrap();
+        let proof = merkle_tree.proof(&[3, 4]);
+        let leaves = [leaf_hashes[3], leaf_hashes[4]];
+
+        assert!(proof.verify_detailed(root, &[3, 4], &leaves, 6).is_ok());
+
+        match proof.verify_detailed(leaf_hashes[0], &[3, 4], &leaves, 6) {
+            Err(VerificationError::RootMismatch { expected, computed }) => {
+                assert_eq!(expected, leaf_hashes[0]);
+                assert_eq!(computed, root);
+            }
+            _ => panic!("expected a root mismatch"),
+        }
+
+        match proof.verify_detailed(root, &[3, 4], &leaves[..1], 6) {
+            Err(VerificationError::Proof(error)) => {
+                assert_eq!(error.kind(), ErrorKind::LeavesIndicesCountMismatch)
+            }
+            _ => panic!("expected a proof error"),
+        }
+    }
+}
+
 pub mod to_bytes {
     use crate::common;
     use rs_merkle::{algorithms::Sha256, MerkleTree};
@@ -247,3 +343,584 @@ pub mod from_bytes {
         );
     }
 }
+
+pub mod batch_verifier {
+    use crate::common;
+    use rs_merkle::{algorithms::Sha256, BatchVerifier, MerkleProof, MerkleTree};
+
+    #[test]
+    pub fn should_report_failed_entries() {
+        let test_cases = common::setup_proof_test_cases();
+
+        for test_case in &test_cases {
+            let merkle_tree = &test_case.merkle_tree;
+            let root = merkle_tree.root().unwrap();
+            let leaves = merkle_tree.leaves().unwrap();
+            let proofs: Vec<MerkleProof<Sha256>> = test_case
+                .cases
+                .iter()
+                .map(|case| merkle_tree.proof(&case.leaf_indices_to_prove))
+                .collect();
+
+            let mut batch = BatchVerifier::<Sha256>::new(root, merkle_tree.leaves_len());
+            for (case, proof) in test_case.cases.iter().zip(&proofs) {
+                batch.add(
+                    proof,
+                    &case.leaf_indices_to_prove,
+                    &case.leaf_hashes_to_prove,
+                );
+            }
+            assert_eq!(batch.verify(), Ok(()));
+
+            // Proving the last leaf with a hash of the first one must fail even though all
+            // the nodes above the leaf are already verified
+            let last_index = [leaves.len() - 1];
+            let last_proof = merkle_tree.proof(&last_index);
+            batch.add(&last_proof, &last_index, &leaves[0..1]);
+            let expected = if leaves.len() == 1 {
+                Ok(())
+            } else {
+                Err(vec![test_case.cases.len()])
+            };
+            assert_eq!(batch.verify(), expected);
+        }
+    }
+
+    #[test]
+    pub fn should_not_verify_entries_against_a_wrong_root() {
+        let test_data = common::setup();
+        let leaf_hashes = &test_data.leaf_hashes;
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(leaf_hashes);
+        let indices: Vec<usize> = (0..leaf_hashes.len()).collect();
+        let proofs: Vec<MerkleProof<Sha256>> =
+            indices.iter().map(|i| merkle_tree.proof(&[*i])).collect();
+
+        let mut batch = BatchVerifier::<Sha256>::new(leaf_hashes[0], leaf_hashes.len());
+        for (i, proof) in proofs.iter().enumerate() {
+            batch.add(proof, &indices[i..i + 1], &leaf_hashes[i..i + 1]);
+        }
+
+        assert_eq!(batch.verify(), Err(indices.clone()));
+    }
+
+    #[test]
+    pub fn should_report_entries_with_invalid_indices() {
+        let test_data = common::setup();
+        let leaf_hashes = &test_data.leaf_hashes;
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(leaf_hashes);
+        let root = merkle_tree.root().unwrap();
+        let proof = merkle_tree.proof(&[0]);
+        let last_index = [leaf_hashes.len() - 1];
+        let last_proof = merkle_tree.proof(&last_index);
+
+        let duplicate_indices = [0, 0];
+        let duplicate_hashes = [leaf_hashes[0], leaf_hashes[0]];
+        let out_of_range_index = [leaf_hashes.len()];
+
+        let mut batch = BatchVerifier::<Sha256>::new(root, leaf_hashes.len());
+        batch
+            .add(&proof, &[0], &leaf_hashes[0..1])
+            .add(&proof, &duplicate_indices, &duplicate_hashes)
+            .add(&last_proof, &last_index, &leaf_hashes[last_index[0]..])
+            .add(&last_proof, &out_of_range_index, &leaf_hashes[0..1])
+            .add(&proof, &[], &[]);
+
+        assert_eq!(batch.verify(), Err(vec![1, 3, 4]));
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    pub fn should_verify_in_parallel() {
+        let leaf_hashes: Vec<[u8; 32]> = (0..100u32)
+            .map(|i| <Sha256 as rs_merkle::Hasher>::hash(&i.to_be_bytes()))
+            .collect();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaf_hashes);
+        let root = merkle_tree.root().unwrap();
+        let indices: Vec<usize> = (0..leaf_hashes.len()).collect();
+        let proofs: Vec<MerkleProof<Sha256>> =
+            indices.iter().map(|i| merkle_tree.proof(&[*i])).collect();
+
+        let mut batch = BatchVerifier::<Sha256>::new(root, leaf_hashes.len());
+        for (i, proof) in proofs.iter().enumerate() {
+            // Every tenth entry is given a wrong leaf
+            let leaf = if i % 10 == 0 { i + 1 } else { i };
+            batch.add(proof, &indices[i..i + 1], &leaf_hashes[leaf..leaf + 1]);
+        }
+
+        let expected: Vec<usize> = (0..100).step_by(10).collect();
+        assert_eq!(batch.par_verify(), Err(expected.clone()));
+        assert_eq!(batch.verify(), Err(expected));
+    }
+}
+
+pub mod merge {
+    use crate::common;
+    use rayon::prelude::*;
+    use rs_merkle::{algorithms::Sha256, ErrorKind, MerkleProof, MerkleTree};
+
+    #[test]
+    pub fn should_merge_single_leaf_proofs_into_a_minimal_multiproof() {
+        let test_cases = common::setup_proof_test_cases();
+
+        test_cases.par_iter().for_each(|test_case| {
+            let merkle_tree = &test_case.merkle_tree;
+            let leaves_len = merkle_tree.leaves_len();
+            let single_proofs: Vec<MerkleProof<Sha256>> = (0..leaves_len)
+                .map(|index| merkle_tree.proof(&[index]))
+                .collect();
+
+            test_case.cases.par_iter().for_each(|case| {
+                let proofs: Vec<MerkleProof<Sha256>> = case
+                    .leaf_indices_to_prove
+                    .iter()
+                    .map(|index| MerkleProof::new(single_proofs[*index].proof_hashes().to_vec()))
+                    .collect();
+
+                let merged =
+                    MerkleProof::merge(&proofs, &case.leaf_indices_to_prove, leaves_len).unwrap();
+
+                assert_eq!(
+                    merged.proof_hashes(),
+                    merkle_tree
+                        .proof(&case.leaf_indices_to_prove)
+                        .proof_hashes()
+                );
+            });
+        });
+    }
+
+    #[test]
+    pub fn should_return_error_when_proofs_and_indices_do_not_match() {
+        let test_data = common::setup();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&test_data.leaf_hashes);
+        let proofs = [merkle_tree.proof(&[0])];
+
+        let err = MerkleProof::merge(&proofs, &[0, 1], 6).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::ProofsIndicesCountMismatch);
+
+        let err = MerkleProof::<Sha256>::merge(&[MerkleProof::new(vec![])], &[0], 6)
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), ErrorKind::NotEnoughHashesToCalculateRoot);
+    }
+
+    #[test]
+    pub fn should_return_error_when_proofs_have_conflicting_hashes() {
+        let test_data = common::setup();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&test_data.leaf_hashes);
+
+        // Both proofs contain the parent of leaves 4 and 5 as their last hash
+        let mut tampered_hashes = merkle_tree.proof(&[2]).proof_hashes().to_vec();
+        let last = tampered_hashes.len() - 1;
+        tampered_hashes[last] = test_data.leaf_hashes[0];
+        let proofs = [merkle_tree.proof(&[0]), MerkleProof::new(tampered_hashes)];
+
+        let err = MerkleProof::merge(&proofs, &[0, 2], 6).err().unwrap();
+        assert_eq!(err.kind(), ErrorKind::ConflictingProofHashes);
+    }
+}
+
+pub mod split {
+    use crate::common;
+    use rayon::prelude::*;
+    use rs_merkle::{algorithms::Sha256, ErrorKind, MerkleTree};
+
+    #[test]
+    pub fn should_extract_single_leaf_proofs_from_a_multiproof() {
+        let test_cases = common::setup_proof_test_cases();
+
+        // Every leaf of every case is extracted, so bigger trees are skipped to keep the test fast
+        test_cases.par_iter().take(10).for_each(|test_case| {
+            let merkle_tree = &test_case.merkle_tree;
+            let leaves_len = merkle_tree.leaves_len();
+
+            test_case.cases.par_iter().for_each(|case| {
+                let multiproof = merkle_tree.proof(&case.leaf_indices_to_prove);
+
+                for leaf_index in &case.leaf_indices_to_prove {
+                    let proof = multiproof
+                        .split(
+                            &case.leaf_indices_to_prove,
+                            &case.leaf_hashes_to_prove,
+                            leaves_len,
+                            *leaf_index,
+                        )
+                        .unwrap();
+
+                    assert_eq!(
+                        proof.proof_hashes(),
+                        merkle_tree.proof(&[*leaf_index]).proof_hashes()
+                    );
+                }
+            });
+        });
+    }
+
+    #[test]
+    pub fn should_return_error_when_leaf_is_not_proven() {
+        let test_data = common::setup();
+        let leaf_hashes = &test_data.leaf_hashes;
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(leaf_hashes);
+        let multiproof = merkle_tree.proof(&[3, 4]);
+
+        let err = multiproof
+            .split(&[3, 4], &leaf_hashes[3..5], 6, 2)
+            .err()
+            .unwrap();
+
+        assert_eq!(err.kind(), ErrorKind::LeafNotInProof);
+    }
+}
+
+pub mod verify_range {
+    use crate::common;
+    use rs_merkle::{algorithms::Sha256, ErrorKind, MerkleProof, MerkleTree};
+
+    #[test]
+    pub fn should_reject_tampered_range_proofs() {
+        let test_data = common::setup();
+        let leaf_hashes = &test_data.leaf_hashes;
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(leaf_hashes);
+        let root = merkle_tree.root().unwrap();
+        let proof = merkle_tree.range_proof(1..4).unwrap();
+
+        assert!(proof.verify_range(root, 1, &leaf_hashes[1..4], 6));
+        // Shifted range
+        assert!(!proof.verify_range(root, 2, &leaf_hashes[2..5], 6));
+        // Range with a missing leaf
+        assert!(!proof.verify_range(root, 1, &leaf_hashes[1..3], 6));
+
+        let mut padded_hashes = proof.proof_hashes().to_vec();
+        padded_hashes.push(leaf_hashes[0]);
+        let padded_proof = MerkleProof::<Sha256>::new(padded_hashes);
+        assert_eq!(
+            padded_proof
+                .range_root(1, &leaf_hashes[1..4], 6)
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::TooManyProofHashes)
+        );
+
+        // A range start that overflows together with the number of leaves
+        assert_eq!(
+            proof
+                .range_root(usize::MAX, &leaf_hashes[1..4], 6)
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::LeafIndexOutOfRange)
+        );
+        assert!(!proof.verify_range(root, usize::MAX - 1, &leaf_hashes[1..4], 6));
+    }
+}
+
+pub mod compact_positions {
+    use crate::common;
+    use rayon::prelude::*;
+    use rs_merkle::{
+        algorithms::Sha256,
+        proof_serializers::{CompactPositions, CompactProof},
+        ErrorKind, MerkleProof, MerkleTree,
+    };
+
+    #[test]
+    pub fn should_verify_without_separate_indices() {
+        let test_cases = common::setup_proof_test_cases();
+
+        test_cases.par_iter().for_each(|test_case| {
+            let merkle_tree = &test_case.merkle_tree;
+            let root = merkle_tree.root().unwrap();
+
+            test_case.cases.par_iter().for_each(|case| {
+                let proof = CompactProof::new(
+                    merkle_tree.proof(&case.leaf_indices_to_prove),
+                    &case.leaf_indices_to_prove,
+                    merkle_tree.leaves_len(),
+                )
+                .unwrap();
+                let bytes = CompactPositions::serialize(&proof);
+                let parsed = CompactPositions::deserialize::<Sha256>(&bytes).unwrap();
+
+                assert_eq!(parsed.proof().proof_hashes(), proof.proof().proof_hashes());
+                assert_eq!(parsed.leaf_indices(), case.leaf_indices_to_prove.as_slice());
+                assert_eq!(parsed.total_leaves_count(), merkle_tree.leaves_len());
+                assert!(parsed.verify(root, &case.leaf_hashes_to_prove));
+            });
+        });
+    }
+
+    #[test]
+    pub fn should_be_compact() {
+        let test_data = common::setup();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&test_data.leaf_hashes);
+        let proof = CompactProof::new(merkle_tree.proof(&[3, 4]), &[4, 3], 6).unwrap();
+
+        // Tree size, leaves count, two indices and three hashes
+        let bytes = CompactPositions::serialize(&proof);
+        assert_eq!(bytes.len(), 4 + 3 * 32);
+        assert_eq!(&bytes[..4], &[6, 2, 3, 0]);
+    }
+
+    #[test]
+    pub fn should_reject_invalid_leaf_positions() {
+        let proof = || MerkleProof::<Sha256>::new(vec![]);
+        let kind = |result: Result<CompactProof<Sha256>, rs_merkle::Error>| {
+            result.err().map(|err| err.kind())
+        };
+
+        assert_eq!(
+            kind(CompactProof::new(proof(), &[0], 0)),
+            Some(ErrorKind::EmptyTree)
+        );
+        assert_eq!(
+            kind(CompactProof::new(proof(), &[], 6)),
+            Some(ErrorKind::EmptyLeafIndices)
+        );
+        assert_eq!(
+            kind(CompactProof::new(proof(), &[1, 1], 6)),
+            Some(ErrorKind::DuplicateLeafIndex)
+        );
+        assert_eq!(
+            kind(CompactProof::new(proof(), &[6], 6)),
+            Some(ErrorKind::LeafIndexOutOfRange)
+        );
+    }
+
+    #[test]
+    pub fn should_reject_malformed_proofs() {
+        let test_data = common::setup();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&test_data.leaf_hashes);
+        let proof = CompactProof::new(merkle_tree.proof(&[3, 4]), &[3, 4], 6).unwrap();
+        let bytes = CompactPositions::serialize(&proof);
+        let kind = |bytes: &[u8]| {
+            CompactPositions::deserialize::<Sha256>(bytes)
+                .err()
+                .map(|err| err.kind())
+        };
+
+        // Missing hash
+        assert_eq!(
+            kind(&bytes[..bytes.len() - 32]),
+            Some(ErrorKind::MalformedProof)
+        );
+        // Leaf index past the end of the tree
+        let mut out_of_range = bytes.clone();
+        out_of_range[2] = 6;
+        assert_eq!(kind(&out_of_range), Some(ErrorKind::MalformedProof));
+        // No leaves
+        assert_eq!(kind(&[6, 0]), Some(ErrorKind::MalformedProof));
+        // Truncated varint
+        assert_eq!(kind(&[0x80]), Some(ErrorKind::MalformedProof));
+        // Tree size of 6 encoded in two bytes instead of one
+        let mut overlong = vec![0x86, 0x00];
+        overlong.extend_from_slice(&bytes[1..]);
+        assert_eq!(kind(&overlong), Some(ErrorKind::MalformedProof));
+    }
+
+    #[test]
+    pub fn should_not_verify_with_other_leaves() {
+        let test_data = common::setup();
+        let leaf_hashes = &test_data.leaf_hashes;
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(leaf_hashes);
+        let root = merkle_tree.root().unwrap();
+        let proof = CompactProof::new(merkle_tree.proof(&[3, 4]), &[3, 4], 6).unwrap();
+
+        assert!(proof.verify(root, &leaf_hashes[3..5]));
+        assert!(!proof.verify(root, &leaf_hashes[2..4]));
+        assert!(!proof.verify(root, &leaf_hashes[3..4]));
+    }
+}
+
+pub mod streaming {
+    use crate::common;
+    use rayon::prelude::*;
+    use rs_merkle::{
+        algorithms::Sha256,
+        proof_serializers::{
+            CompactPositions, CompactProof, DirectHashesOrder, IoSink, IoSource,
+            MerkleProofSerializer, ReverseHashesOrder, StreamingProofSerializer,
+        },
+        ErrorKind, MerkleProof, MerkleTree,
+    };
+
+    fn assert_same_as_in_memory<S: MerkleProofSerializer + StreamingProofSerializer>() {
+        let test_cases = common::setup_proof_test_cases();
+
+        test_cases.par_iter().take(5).for_each(|test_case| {
+            let merkle_tree = &test_case.merkle_tree;
+
+            test_case.cases.par_iter().for_each(|case| {
+                let proof = merkle_tree.proof(&case.leaf_indices_to_prove);
+                let mut bytes = Vec::new();
+                proof.serialize_to::<S, _>(&mut bytes).unwrap();
+                assert_eq!(bytes, proof.serialize::<S>());
+
+                let parsed =
+                    MerkleProof::<Sha256>::deserialize_from::<S, _>(&mut bytes.as_slice(), 4096)
+                        .unwrap();
+                let expected = MerkleProof::<Sha256>::deserialize::<S>(&bytes).unwrap();
+                assert_eq!(parsed.proof_hashes(), expected.proof_hashes());
+            });
+        });
+    }
+
+    #[test]
+    pub fn should_match_in_memory_serializers() {
+        assert_same_as_in_memory::<DirectHashesOrder>();
+        assert_same_as_in_memory::<ReverseHashesOrder>();
+    }
+
+    #[test]
+    pub fn should_match_in_memory_compact_positions() {
+        let test_cases = common::setup_proof_test_cases();
+
+        test_cases.par_iter().take(5).for_each(|test_case| {
+            let merkle_tree = &test_case.merkle_tree;
+
+            test_case.cases.par_iter().for_each(|case| {
+                let proof = CompactProof::new(
+                    merkle_tree.proof(&case.leaf_indices_to_prove),
+                    &case.leaf_indices_to_prove,
+                    merkle_tree.leaves_len(),
+                )
+                .unwrap();
+                let mut bytes = Vec::new();
+                CompactPositions::serialize_to(&proof, &mut bytes).unwrap();
+                assert_eq!(bytes, CompactPositions::serialize(&proof));
+
+                let parsed =
+                    CompactPositions::deserialize_from::<Sha256, _>(&mut bytes.as_slice(), 4096)
+                        .unwrap();
+                assert_eq!(parsed.proof().proof_hashes(), proof.proof().proof_hashes());
+                assert_eq!(parsed.leaf_indices(), proof.leaf_indices());
+                assert_eq!(parsed.total_leaves_count(), proof.total_leaves_count());
+            });
+        });
+    }
+
+    #[test]
+    pub fn should_work_over_io() {
+        let test_data = common::setup();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&test_data.leaf_hashes);
+        let proof = merkle_tree.proof(&[3, 4]);
+
+        let mut sink = IoSink(std::io::Cursor::new(Vec::new()));
+        proof
+            .serialize_to::<ReverseHashesOrder, _>(&mut sink)
+            .unwrap();
+        let bytes = sink.0.into_inner();
+
+        let mut source = IoSource(std::io::Cursor::new(bytes));
+        let parsed =
+            MerkleProof::<Sha256>::deserialize_from::<ReverseHashesOrder, _>(&mut source, 96)
+                .unwrap();
+        assert_eq!(parsed.proof_hashes(), proof.proof_hashes());
+    }
+
+    #[test]
+    pub fn should_leave_trailing_bytes_after_compact_positions() {
+        let test_data = common::setup();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&test_data.leaf_hashes);
+        let proof = CompactProof::new(merkle_tree.proof(&[3, 4]), &[3, 4], 6).unwrap();
+
+        let mut bytes = CompactPositions::serialize(&proof);
+        bytes.extend_from_slice(&[1, 2, 3]);
+        let mut source = bytes.as_slice();
+        let parsed = CompactPositions::deserialize_from::<Sha256, _>(&mut source, 1024).unwrap();
+
+        assert_eq!(parsed.proof().proof_hashes(), proof.proof().proof_hashes());
+        assert_eq!(source, &[1, 2, 3]);
+    }
+
+    #[test]
+    pub fn should_reject_too_long_and_truncated_proofs() {
+        let test_data = common::setup();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&test_data.leaf_hashes);
+        let proof = merkle_tree.proof(&[3, 4]);
+        let bytes = proof.serialize::<DirectHashesOrder>();
+
+        let too_long =
+            MerkleProof::<Sha256>::deserialize_from::<DirectHashesOrder, _>(&mut &bytes[..], 95);
+        assert_eq!(
+            too_long.err().map(|err| err.kind()),
+            Some(ErrorKind::ProofTooLong)
+        );
+
+        let truncated = MerkleProof::<Sha256>::deserialize_from::<DirectHashesOrder, _>(
+            &mut &bytes[..bytes.len() - 1],
+            1024,
+        );
+        assert_eq!(
+            truncated.err().map(|err| err.kind()),
+            Some(ErrorKind::SerializedProofSizeIsIncorrect)
+        );
+
+        let compact_proof = CompactProof::new(proof, &[3, 4], 6).unwrap();
+        let compact_bytes = CompactPositions::serialize(&compact_proof);
+        let compact_truncated = CompactPositions::deserialize_from::<Sha256, _>(
+            &mut &compact_bytes[..compact_bytes.len() - 32],
+            1024,
+        );
+        assert_eq!(
+            compact_truncated.err().map(|err| err.kind()),
+            Some(ErrorKind::MalformedProof)
+        );
+    }
+}
+
+pub mod proof_ref {
+    use crate::common;
+    use rayon::prelude::*;
+    use rs_merkle::{algorithms::Sha256, Error, ErrorKind, MerkleProofRef, MerkleTree};
+
+    #[test]
+    pub fn should_calculate_the_same_root_as_merkle_proof() {
+        let test_cases = common::setup_proof_test_cases();
+
+        test_cases.par_iter().for_each(|test_case| {
+            let merkle_tree = &test_case.merkle_tree;
+            let root = merkle_tree.root().unwrap();
+            let leaves = merkle_tree.leaves().unwrap();
+
+            (0..leaves.len()).into_par_iter().for_each(|index| {
+                let proof_bytes = merkle_tree.proof(&[index]).to_bytes();
+                let proof = MerkleProofRef::<Sha256>::from_bytes(&proof_bytes).unwrap();
+
+                assert_eq!(proof.root(index, leaves[index], leaves.len()), Some(root));
+                assert!(proof.verify(root, index, leaves[index], leaves.len()));
+                assert_eq!(
+                    proof.to_proof().proof_hashes(),
+                    merkle_tree.proof(&[index]).proof_hashes()
+                );
+            });
+        });
+    }
+
+    #[test]
+    pub fn should_reject_wrong_leaves_and_short_proofs() {
+        let test_data = common::setup();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&test_data.leaf_hashes);
+        let root = merkle_tree.root().unwrap();
+        let leaves = &test_data.leaf_hashes;
+        let proof_bytes = merkle_tree.proof(&[2]).to_bytes();
+        let proof = MerkleProofRef::<Sha256>::from_bytes(&proof_bytes).unwrap();
+
+        assert_eq!(proof.proof_hashes_count(), 3);
+        assert!(!proof.verify(root, 2, leaves[3], leaves.len()));
+        assert!(!proof.verify(root, 3, leaves[2], leaves.len()));
+        assert_eq!(proof.root(6, leaves[2], leaves.len()), None);
+
+        let short_proof = MerkleProofRef::<Sha256>::from_bytes(&proof_bytes[..64]).unwrap();
+        assert_eq!(short_proof.root(2, leaves[2], leaves.len()), None);
+
+        let malformed = MerkleProofRef::<Sha256>::from_bytes(&proof_bytes[..63]);
+        assert_eq!(
+            malformed.as_ref().err().map(|err| err.kind()),
+            Some(ErrorKind::SerializedProofSizeIsIncorrect)
+        );
+        let error = malformed.err().map(Error::from);
+        assert_eq!(
+            error.map(|err| err.kind()),
+            Some(ErrorKind::SerializedProofSizeIsIncorrect)
+        );
+    }
+}
@@ -1,3 +1,4 @@
+#[allow(dead_code)]
 mod common;
 
 pub mod root {
@@ -65,6 +66,55 @@ pub mod proof {
     }
 }
 
+pub mod try_proof {
+    use crate::common;
+    use rs_merkle::{algorithms::Sha256, ErrorKind, MerkleTree};
+
+    #[test]
+    pub fn should_return_a_proof_for_unsorted_indices() {
+        let test_data = common::setup();
+        let leaf_hashes = &test_data.leaf_hashes;
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(leaf_hashes);
+        let root = merkle_tree.root().unwrap();
+
+        let proof = merkle_tree.try_proof(&[5, 0, 3]).unwrap();
+
+        assert_eq!(
+            proof.proof_hashes(),
+            merkle_tree.proof(&[0, 3, 5]).proof_hashes()
+        );
+        assert!(proof.verify(
+            root,
+            &[5, 0, 3],
+            &[leaf_hashes[5], leaf_hashes[0], leaf_hashes[3]],
+            leaf_hashes.len()
+        ));
+    }
+
+    #[test]
+    pub fn should_reject_invalid_indices() {
+        let test_data = common::setup();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&test_data.leaf_hashes);
+        let empty_tree = MerkleTree::<Sha256>::new();
+
+        let kind = |result: Result<_, rs_merkle::Error>| result.err().map(|err| err.kind());
+
+        assert_eq!(kind(empty_tree.try_proof(&[0])), Some(ErrorKind::EmptyTree));
+        assert_eq!(
+            kind(merkle_tree.try_proof(&[])),
+            Some(ErrorKind::EmptyLeafIndices)
+        );
+        assert_eq!(
+            kind(merkle_tree.try_proof(&[1, 6])),
+            Some(ErrorKind::LeafIndexOutOfRange)
+        );
+        assert_eq!(
+            kind(merkle_tree.try_proof(&[3, 1, 3])),
+            Some(ErrorKind::DuplicateLeafIndex)
+        );
+    }
+}
+
 pub mod commit {
     use crate::common;
     use rs_merkle::{algorithms::Sha256, Hasher, MerkleTree};
@@ -276,3 +326,1610 @@ pub mod rollback {
         );
     }
 }
+
+pub mod leaf_index {
+    use crate::common;
+    use rs_merkle::{algorithms::Sha256, Hasher, MerkleTree};
+
+    #[test]
+    pub fn should_find_leaves_with_and_without_index() {
+        let test_data = common::setup();
+        let leaf_hashes = &test_data.leaf_hashes;
+
+        let plain_tree = MerkleTree::<Sha256>::from_leaves(leaf_hashes);
+        let indexed_tree = MerkleTree::<Sha256>::from_leaves(leaf_hashes).with_leaf_index();
+
+        for (position, leaf) in leaf_hashes.iter().enumerate() {
+            assert_eq!(plain_tree.position_of(leaf), Some(position));
+            assert_eq!(indexed_tree.position_of(leaf), Some(position));
+            assert!(indexed_tree.contains_leaf(leaf));
+        }
+
+        let missing_leaf = Sha256::hash("z".as_bytes());
+        assert_eq!(plain_tree.position_of(&missing_leaf), None);
+        assert!(!indexed_tree.contains_leaf(&missing_leaf));
+    }
+
+    #[test]
+    pub fn should_keep_index_up_to_date_across_commit_and_rollback() {
+        let test_data = common::setup();
+        let mut merkle_tree = MerkleTree::<Sha256>::new().with_leaf_index();
+        merkle_tree.append(test_data.leaf_hashes.clone().as_mut());
+
+        // Uncommitted leaves are not indexed
+        assert!(!merkle_tree.contains_leaf(&test_data.leaf_hashes[0]));
+        merkle_tree.commit();
+        assert_eq!(merkle_tree.position_of(&test_data.leaf_hashes[5]), Some(5));
+
+        let leaf = Sha256::hash("g".as_bytes());
+        merkle_tree.insert(leaf).commit();
+        assert_eq!(merkle_tree.position_of(&leaf), Some(6));
+
+        merkle_tree.rollback();
+        assert!(!merkle_tree.contains_leaf(&leaf));
+        assert_eq!(merkle_tree.position_of(&test_data.leaf_hashes[5]), Some(5));
+    }
+
+    #[test]
+    pub fn should_return_a_verifiable_proof_for_leaves() {
+        let test_data = common::setup();
+        let leaf_hashes = &test_data.leaf_hashes;
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(leaf_hashes).with_leaf_index();
+        let root = merkle_tree.root().unwrap();
+
+        let leaves_to_prove = vec![leaf_hashes[5], leaf_hashes[0], leaf_hashes[3]];
+        let (indices, proof) = merkle_tree.proof_for_leaves(&leaves_to_prove).unwrap();
+
+        assert_eq!(indices, vec![5, 0, 3]);
+        assert!(proof.verify(root, &indices, &leaves_to_prove, leaf_hashes.len()));
+
+        let missing_leaf = Sha256::hash("z".as_bytes());
+        assert!(merkle_tree
+            .proof_for_leaves(&[leaf_hashes[0], missing_leaf])
+            .is_none());
+    }
+
+    #[test]
+    pub fn should_not_return_a_proof_for_repeated_leaves() {
+        let test_data = common::setup();
+        let leaf_hashes = &test_data.leaf_hashes;
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(leaf_hashes).with_leaf_index();
+
+        assert!(merkle_tree
+            .proof_for_leaves(&[leaf_hashes[2], leaf_hashes[4], leaf_hashes[2]])
+            .is_none());
+    }
+}
+
+pub mod sorted_leaves {
+    use rs_merkle::{
+        algorithms::Sha256, ErrorKind, MerkleProof, MerkleTree, NonMembershipProof,
+        SortedMerkleTree,
+    };
+
+    fn leaf(byte: u8) -> [u8; 32] {
+        [byte; 32]
+    }
+
+    fn sorted_tree(bytes: &[u8]) -> SortedMerkleTree<Sha256> {
+        let leaves: Vec<[u8; 32]> = bytes.iter().map(|byte| leaf(*byte)).collect();
+        SortedMerkleTree::<Sha256>::from_leaves(&leaves).unwrap()
+    }
+
+    #[test]
+    pub fn should_enforce_the_order_on_insert() {
+        let mut merkle_tree = sorted_tree(&[1, 3]);
+
+        assert_eq!(
+            merkle_tree.insert(leaf(3)).err().map(|err| err.kind()),
+            Some(ErrorKind::UnsortedLeaves)
+        );
+        merkle_tree.insert(leaf(4)).unwrap();
+        assert_eq!(
+            merkle_tree
+                .append(&mut vec![leaf(6), leaf(5)])
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::UnsortedLeaves)
+        );
+        merkle_tree.append(&mut vec![leaf(5), leaf(6)]).unwrap();
+        merkle_tree.commit();
+        assert_eq!(merkle_tree.merkle_tree().leaves_len(), 5);
+
+        merkle_tree.rollback();
+        merkle_tree.insert(leaf(2)).err().unwrap();
+        merkle_tree.insert(leaf(4)).unwrap();
+        merkle_tree.abort_uncommitted();
+        merkle_tree.insert(leaf(2)).err().unwrap();
+        merkle_tree.insert(leaf(3)).err().unwrap();
+        merkle_tree.insert(leaf(4)).unwrap();
+    }
+
+    #[test]
+    pub fn should_reject_unsorted_leaves() {
+        assert_eq!(
+            SortedMerkleTree::<Sha256>::from_leaves(&[leaf(2), leaf(1)])
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::UnsortedLeaves)
+        );
+        assert!(SortedMerkleTree::<Sha256>::from_leaves(&[leaf(2), leaf(2)]).is_err());
+
+        // Plain trees don't check the order
+        let mut plain_tree = MerkleTree::<Sha256>::from_leaves(&[leaf(2), leaf(1)]);
+        plain_tree.insert(leaf(0)).commit();
+        assert_eq!(plain_tree.leaves_len(), 3);
+    }
+
+    #[test]
+    pub fn should_prove_absence_of_every_missing_leaf() {
+        let bytes = [2, 4, 6, 8, 10];
+        for leaves_count in 1..=bytes.len() {
+            let merkle_tree = sorted_tree(&bytes[..leaves_count]);
+            let root = merkle_tree.root().unwrap();
+
+            for byte in 0..12 {
+                let result = merkle_tree.non_membership_proof(&leaf(byte));
+                if bytes[..leaves_count].contains(&byte) {
+                    assert_eq!(
+                        result.err().map(|err| err.kind()),
+                        Some(ErrorKind::LeafInTree)
+                    );
+                    continue;
+                }
+
+                let proof = result.unwrap();
+                assert!(proof.verify(root, &leaf(byte), leaves_count));
+                assert!(!proof.verify(root, &leaf(byte), leaves_count + 1));
+                for present in &bytes[..leaves_count] {
+                    assert!(!proof.verify(root, &leaf(*present), leaves_count));
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_handle_the_edges() {
+        let merkle_tree = sorted_tree(&[2, 4, 6]);
+
+        let first = merkle_tree.non_membership_proof(&leaf(1)).unwrap();
+        assert_eq!(first.left(), None);
+        assert_eq!(first.right(), Some((0, leaf(2))));
+
+        let last = merkle_tree.non_membership_proof(&leaf(7)).unwrap();
+        assert_eq!(last.left(), Some((2, leaf(6))));
+        assert_eq!(last.right(), None);
+
+        let empty_tree = SortedMerkleTree::<Sha256>::new();
+        assert_eq!(
+            empty_tree
+                .non_membership_proof(&leaf(1))
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::EmptyTree)
+        );
+    }
+
+    #[test]
+    pub fn should_reject_neighbours_that_are_not_adjacent() {
+        let merkle_tree = sorted_tree(&[2, 4, 6, 8]);
+        let root = merkle_tree.root().unwrap();
+
+        // 2 and 6 are in the tree and bracket 5, but 4 is between them
+        let skipping_proof = NonMembershipProof::new(
+            Some((0, leaf(2))),
+            Some((2, leaf(6))),
+            merkle_tree.proof(&[0, 2]),
+            4,
+        );
+        assert!(skipping_proof
+            .proof()
+            .verify(root, &[0, 2], &[leaf(2), leaf(6)], 4));
+        assert!(!skipping_proof.verify(root, &leaf(5), 4));
+
+        // 6 is not the last leaf
+        let not_last_proof =
+            NonMembershipProof::new(Some((2, leaf(6))), None, merkle_tree.proof(&[2]), 4);
+        assert!(!not_last_proof.verify(root, &leaf(7), 4));
+
+        // 4 is not the first leaf
+        let not_first_proof =
+            NonMembershipProof::new(None, Some((1, leaf(4))), merkle_tree.proof(&[1]), 4);
+        assert!(!not_first_proof.verify(root, &leaf(3), 4));
+
+        let no_neighbours_proof = NonMembershipProof::new(None, None, merkle_tree.proof(&[0]), 4);
+        assert!(!no_neighbours_proof.verify(root, &leaf(3), 4));
+    }
+
+    #[test]
+    pub fn should_reject_proofs_that_claim_another_tree_size() {
+        let merkle_tree = sorted_tree(&[1, 3, 5]);
+        let root = merkle_tree.root().unwrap();
+
+        // The root of the tree passed off as the only leaf of a tree of one leaf
+        let forged_proof =
+            NonMembershipProof::<Sha256>::new(Some((0, root)), None, MerkleProof::new(vec![]), 1);
+        assert!(forged_proof.proof().verify(root, &[0], &[root], 1));
+        assert!(!forged_proof.verify(root, &[0xff; 32], 3));
+
+        // Extra hashes are rejected, even though they don't change the root
+        let proof = merkle_tree.non_membership_proof(&leaf(6)).unwrap();
+        let mut proof_hashes = proof.proof().proof_hashes().to_vec();
+        proof_hashes.push(leaf(0));
+        let padded_proof = NonMembershipProof::<Sha256>::new(
+            proof.left(),
+            proof.right(),
+            MerkleProof::new(proof_hashes),
+            3,
+        );
+        assert!(proof.verify(root, &leaf(6), 3));
+        assert!(!padded_proof.verify(root, &leaf(6), 3));
+    }
+}
+
+pub mod uncommitted_proof {
+    use crate::common;
+    use rs_merkle::{algorithms::Sha256, Hasher, MerkleTree};
+
+    #[test]
+    pub fn should_verify_against_uncommitted_root() {
+        let test_data = common::setup();
+        let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&test_data.leaf_hashes);
+        assert!(merkle_tree.uncommitted_proof(&[0]).is_none());
+
+        let mut new_leaves = vec![
+            Sha256::hash("g".as_bytes()),
+            Sha256::hash("h".as_bytes()),
+            Sha256::hash("k".as_bytes()),
+        ];
+        let mut all_leaves = test_data.leaf_hashes.clone();
+        all_leaves.extend_from_slice(&new_leaves);
+        merkle_tree.append(&mut new_leaves);
+
+        let uncommitted_root = merkle_tree.uncommitted_root().unwrap();
+        let cases = common::combinations((0..all_leaves.len()).collect::<Vec<usize>>());
+
+        for indices in &cases {
+            let leaves: Vec<[u8; 32]> = indices.iter().map(|i| all_leaves[*i]).collect();
+            let proof = merkle_tree.uncommitted_proof(indices).unwrap();
+
+            assert!(proof.verify(uncommitted_root, indices, &leaves, all_leaves.len()));
+        }
+
+        // The proof must be the same as the one made after the commit
+        let uncommitted_proof = merkle_tree.uncommitted_proof(&[2, 7]).unwrap();
+        merkle_tree.commit();
+        assert_eq!(
+            uncommitted_proof.proof_hashes(),
+            merkle_tree.proof(&[2, 7]).proof_hashes()
+        );
+    }
+}
+
+pub mod range_proof {
+    use crate::common;
+    use rs_merkle::{algorithms::Sha256, ErrorKind, Hasher, MerkleTree};
+
+    #[test]
+    pub fn should_return_the_same_proof_as_for_all_range_indices() {
+        for leaves_len in 1..=17usize {
+            let leaves: Vec<[u8; 32]> = (0..leaves_len)
+                .map(|i| Sha256::hash(&i.to_be_bytes()))
+                .collect();
+            let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+            let root = merkle_tree.root().unwrap();
+
+            for start in 0..leaves_len {
+                for end in start + 1..=leaves_len {
+                    let indices: Vec<usize> = (start..end).collect();
+                    let range_proof = merkle_tree.range_proof(start..end).unwrap();
+
+                    assert_eq!(
+                        range_proof.proof_hashes(),
+                        merkle_tree.proof(&indices).proof_hashes()
+                    );
+                    assert!(range_proof.verify_range(root, start, &leaves[start..end], leaves_len));
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_reject_invalid_ranges() {
+        let test_data = common::setup();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&test_data.leaf_hashes);
+        let kind = |result: Result<_, rs_merkle::Error>| result.err().map(|err| err.kind());
+
+        assert_eq!(
+            kind(MerkleTree::<Sha256>::new().range_proof(0..1)),
+            Some(ErrorKind::EmptyTree)
+        );
+        assert_eq!(
+            kind(merkle_tree.range_proof(2..2)),
+            Some(ErrorKind::EmptyLeafIndices)
+        );
+        assert_eq!(
+            kind(merkle_tree.range_proof(4..7)),
+            Some(ErrorKind::LeafIndexOutOfRange)
+        );
+    }
+}
+
+pub mod proof_update {
+    use rs_merkle::{algorithms::Sha256, ErrorKind, Hasher, MerkleTree};
+
+    #[test]
+    pub fn should_update_proofs_after_appends() {
+        let leaves: Vec<[u8; 32]> = (0..20usize)
+            .map(|i| Sha256::hash(&i.to_be_bytes()))
+            .collect();
+
+        for old_leaves_count in 1..=16 {
+            let old_tree = MerkleTree::<Sha256>::from_leaves(&leaves[..old_leaves_count]);
+
+            for new_leaves_count in old_leaves_count..=leaves.len() {
+                let new_tree = MerkleTree::<Sha256>::from_leaves(&leaves[..new_leaves_count]);
+                let new_root = new_tree.root().unwrap();
+
+                for leaf_index in 0..old_leaves_count {
+                    let old_proof = old_tree.proof(&[leaf_index]);
+                    let update_hashes =
+                        new_tree.proof_update(leaf_index, old_leaves_count).unwrap();
+                    let updated_proof = old_proof
+                        .update(
+                            leaf_index,
+                            old_leaves_count,
+                            new_leaves_count,
+                            &update_hashes,
+                        )
+                        .unwrap();
+
+                    assert_eq!(
+                        updated_proof.proof_hashes(),
+                        new_tree.proof(&[leaf_index]).proof_hashes()
+                    );
+                    assert!(updated_proof.verify(
+                        new_root,
+                        &[leaf_index],
+                        &leaves[leaf_index..leaf_index + 1],
+                        new_leaves_count
+                    ));
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_reject_invalid_updates() {
+        let leaves: Vec<[u8; 32]> = (0..8usize)
+            .map(|i| Sha256::hash(&i.to_be_bytes()))
+            .collect();
+        let old_tree = MerkleTree::<Sha256>::from_leaves(&leaves[..5]);
+        let new_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+        let proof = old_tree.proof(&[4]);
+        fn kind<V>(result: Result<V, rs_merkle::Error>) -> Option<ErrorKind> {
+            result.err().map(|err| err.kind())
+        }
+
+        assert_eq!(
+            kind(old_tree.proof_update(4, 8)),
+            Some(ErrorKind::LeavesCountDecreased)
+        );
+        assert_eq!(
+            kind(new_tree.proof_update(5, 5)),
+            Some(ErrorKind::LeafIndexOutOfRange)
+        );
+
+        let mut update_hashes = new_tree.proof_update(4, 5).unwrap();
+        update_hashes.push(leaves[0]);
+        assert_eq!(
+            kind(proof.update(4, 5, 8, &update_hashes)),
+            Some(ErrorKind::TooManyProofHashes)
+        );
+        assert_eq!(
+            kind(proof.update(4, 5, 8, &[])),
+            Some(ErrorKind::NotEnoughHashesToCalculateRoot)
+        );
+    }
+}
+
+pub mod node_proof {
+    use rs_merkle::{algorithms::Sha256, ErrorKind, Hasher, MerkleTree};
+
+    #[test]
+    pub fn should_prove_every_node_of_the_tree() {
+        for leaves_len in 1..=17usize {
+            let leaves: Vec<[u8; 32]> = (0..leaves_len)
+                .map(|i| Sha256::hash(&i.to_be_bytes()))
+                .collect();
+            let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+            let root = merkle_tree.root().unwrap();
+
+            for layer_index in 0..=merkle_tree.depth() {
+                let mut node_index = 0;
+                while let Some(node_hash) = merkle_tree.node(layer_index, node_index) {
+                    let proof = merkle_tree.node_proof(layer_index, node_index).unwrap();
+
+                    if layer_index == 0 {
+                        assert_eq!(
+                            proof.proof_hashes(),
+                            merkle_tree.proof(&[node_index]).proof_hashes()
+                        );
+                    }
+                    assert!(proof.verify_node(
+                        root,
+                        layer_index,
+                        node_index,
+                        node_hash,
+                        leaves_len
+                    ));
+                    assert!(!proof.verify_node(
+                        root,
+                        layer_index,
+                        node_index,
+                        Sha256::hash("missing".as_bytes()),
+                        leaves_len
+                    ));
+
+                    node_index += 1;
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_return_error_for_missing_nodes() {
+        let leaves: Vec<[u8; 32]> = (0..6usize)
+            .map(|i| Sha256::hash(&i.to_be_bytes()))
+            .collect();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+
+        assert_eq!(
+            merkle_tree.node_proof(1, 3).err().map(|err| err.kind()),
+            Some(ErrorKind::NodeOutOfRange)
+        );
+
+        let proof = merkle_tree.node_proof(1, 2).unwrap();
+        assert_eq!(
+            proof
+                .node_root(1, 3, leaves[0], leaves.len())
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::NodeOutOfRange)
+        );
+    }
+
+    #[test]
+    pub fn should_reject_huge_layer_indices_without_walking_them() {
+        let leaves: Vec<[u8; 32]> = (0..3usize)
+            .map(|i| Sha256::hash(&i.to_be_bytes()))
+            .collect();
+        let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+        let root = merkle_tree.root().unwrap();
+        let proof = merkle_tree.node_proof(0, 0).unwrap();
+
+        assert!(!proof.verify_node(root, usize::MAX, 0, leaves[0], leaves.len()));
+        assert_eq!(
+            proof
+                .node_root(usize::MAX, 0, leaves[0], leaves.len())
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::NodeOutOfRange)
+        );
+    }
+}
+
+pub mod kary_tree {
+    use crate::common;
+    use rs_merkle::{algorithms::Sha256, ErrorKind, Hasher, KaryMerkleTree, MerkleTree};
+
+    /// Calculates the root layer by layer, independently of the library's index helpers
+    fn expected_root(leaves: &[[u8; 32]], arity: usize) -> [u8; 32] {
+        let mut layer = leaves.to_vec();
+        while layer.len() > 1 {
+            layer = layer
+                .chunks(arity)
+                .map(|children| match children {
+                    [child] => *child,
+                    _ => Sha256::hash(&children.concat()),
+                })
+                .collect();
+        }
+        layer[0]
+    }
+
+    #[test]
+    pub fn should_build_the_same_tree_as_merkle_tree_with_arity_2() {
+        for count in 1..40 {
+            let leaves = common::leaves(count);
+            assert_eq!(
+                KaryMerkleTree::<Sha256>::from_leaves(&leaves, 2)
+                    .unwrap()
+                    .root(),
+                MerkleTree::<Sha256>::from_leaves(&leaves).root()
+            );
+        }
+    }
+
+    #[test]
+    pub fn should_return_a_correct_root_and_depth() {
+        for arity in [3, 4, 8, 16] {
+            for count in 1..70 {
+                let leaves = common::leaves(count);
+                let merkle_tree = KaryMerkleTree::<Sha256>::from_leaves(&leaves, arity).unwrap();
+                assert_eq!(merkle_tree.root(), Some(expected_root(&leaves, arity)));
+                assert_eq!(merkle_tree.leaves(), Some(leaves));
+            }
+        }
+
+        let merkle_tree = KaryMerkleTree::<Sha256>::from_leaves(&common::leaves(64), 4).unwrap();
+        assert_eq!(merkle_tree.depth(), 3);
+        assert_eq!(merkle_tree.arity(), 4);
+        assert_eq!(
+            KaryMerkleTree::<Sha256>::from_leaves(&[], 4)
+                .unwrap()
+                .root(),
+            None
+        );
+    }
+
+    #[test]
+    pub fn should_verify_single_and_multi_proofs() {
+        for arity in [4, 8, 16] {
+            for count in [1, 5, 16, 17, 33, 70] {
+                let leaves = common::leaves(count);
+                let merkle_tree = KaryMerkleTree::<Sha256>::from_leaves(&leaves, arity).unwrap();
+                let root = merkle_tree.root().unwrap();
+
+                for index in 0..count {
+                    let proof = merkle_tree.proof(&[index]).unwrap();
+                    assert!(proof.verify_with_arity(
+                        root,
+                        &[index],
+                        &[leaves[index]],
+                        count,
+                        arity
+                    ));
+                    let wrong_leaf = leaves[(index + 1) % count];
+                    if count > 1 {
+                        assert!(!proof.verify_with_arity(
+                            root,
+                            &[index],
+                            &[wrong_leaf],
+                            count,
+                            arity
+                        ));
+                    }
+                }
+
+                let indices: Vec<usize> = (0..count).step_by(3).rev().collect();
+                let proven_leaves: Vec<[u8; 32]> =
+                    indices.iter().map(|index| leaves[*index]).collect();
+                let proof = merkle_tree.proof(&indices).unwrap();
+                assert!(proof.verify_with_arity(root, &indices, &proven_leaves, count, arity));
+                assert_eq!(
+                    proof
+                        .root_with_arity(&indices, &proven_leaves, count, arity)
+                        .ok(),
+                    Some(root)
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_make_shallower_proofs_than_binary_trees() {
+        let leaves = common::leaves(4096);
+        let binary_proof = MerkleTree::<Sha256>::from_leaves(&leaves).proof(&[100]);
+        let proof = KaryMerkleTree::<Sha256>::from_leaves(&leaves, 16)
+            .unwrap()
+            .proof(&[100])
+            .unwrap();
+
+        assert_eq!(binary_proof.proof_hashes().len(), 12);
+        assert_eq!(proof.proof_hashes().len(), 45);
+        assert_eq!(
+            KaryMerkleTree::<Sha256>::from_leaves(&leaves, 16)
+                .unwrap()
+                .depth(),
+            3
+        );
+    }
+
+    #[test]
+    pub fn should_reject_proofs_for_another_arity_or_size() {
+        let leaves = common::leaves(20);
+        let merkle_tree = KaryMerkleTree::<Sha256>::from_leaves(&leaves, 4).unwrap();
+        let root = merkle_tree.root().unwrap();
+        let proof = merkle_tree.proof(&[7]).unwrap();
+
+        assert!(!proof.verify_with_arity(root, &[7], &[leaves[7]], 20, 8));
+        assert!(!proof.verify_with_arity(root, &[7], &[leaves[7]], 70, 4));
+        assert!(!proof.verify_with_arity(root, &[7, 7], &[leaves[7], leaves[7]], 20, 4));
+        assert_eq!(
+            proof
+                .root_with_arity(&[20], &[leaves[7]], 20, 4)
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::LeafIndexOutOfRange)
+        );
+        assert_eq!(
+            proof
+                .root_with_arity(&[7], &[leaves[7]], 20, 1)
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::InvalidArity)
+        );
+    }
+
+    #[test]
+    pub fn should_validate_arity_and_indices() {
+        assert_eq!(
+            KaryMerkleTree::<Sha256>::from_leaves(&common::leaves(4), 1)
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::InvalidArity)
+        );
+
+        let merkle_tree = KaryMerkleTree::<Sha256>::from_leaves(&common::leaves(4), 4).unwrap();
+        assert_eq!(
+            merkle_tree.proof(&[4]).err().map(|err| err.kind()),
+            Some(ErrorKind::LeafIndexOutOfRange)
+        );
+        assert_eq!(
+            merkle_tree.proof(&[1, 1]).err().map(|err| err.kind()),
+            Some(ErrorKind::DuplicateLeafIndex)
+        );
+        assert_eq!(
+            merkle_tree.proof(&[]).err().map(|err| err.kind()),
+            Some(ErrorKind::EmptyLeafIndices)
+        );
+    }
+}
+
+pub mod incremental_tree {
+    use crate::common;
+    use rs_merkle::{
+        algorithms::Sha256, utils, ErrorKind, Hasher, IncrementalMerkleTree, MerkleProof,
+        MerkleTree,
+    };
+
+    /// Root of a regular tree of the leaves padded with `zero_leaf` to `2^depth` leaves
+    fn padded_root(leaves: &[[u8; 32]], depth: usize, zero_leaf: [u8; 32]) -> [u8; 32] {
+        let mut padded_leaves = leaves.to_vec();
+        padded_leaves.resize(1 << depth, zero_leaf);
+        MerkleTree::<Sha256>::from_leaves(&padded_leaves)
+            .root()
+            .unwrap()
+    }
+
+    #[test]
+    pub fn should_match_the_empty_deposit_contract_root() {
+        let merkle_tree = IncrementalMerkleTree::<Sha256>::new(32).unwrap();
+
+        assert_eq!(merkle_tree.leaves_len(), 0);
+        assert_eq!(merkle_tree.zero_hashes().len(), 33);
+        assert_eq!(merkle_tree.root(), merkle_tree.zero_hashes()[32]);
+        assert_eq!(
+            utils::collections::to_hex_string(&merkle_tree.deposit_root()),
+            "d70a234731285c6804c2a4f56711ddb8c82c99740f207854891028af34e27e5e"
+        );
+    }
+
+    #[test]
+    pub fn should_match_a_zero_padded_tree() {
+        for depth in 1..6 {
+            let mut merkle_tree = IncrementalMerkleTree::<Sha256>::new(depth).unwrap();
+            let leaves = common::leaves(1 << depth);
+            assert_eq!(merkle_tree.root(), padded_root(&[], depth, [0u8; 32]));
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                assert_eq!(merkle_tree.append(*leaf).unwrap(), index as u64);
+                assert_eq!(
+                    merkle_tree.root(),
+                    padded_root(&leaves[..=index], depth, [0u8; 32])
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_use_a_custom_zero_leaf() {
+        let zero_leaf = Sha256::hash(b"tornado");
+        let mut merkle_tree =
+            IncrementalMerkleTree::<Sha256>::with_zero_leaf(4, zero_leaf).unwrap();
+        let leaves = common::leaves(5);
+        for leaf in &leaves {
+            merkle_tree.append(*leaf).unwrap();
+        }
+
+        assert_eq!(merkle_tree.zero_hashes()[0], zero_leaf);
+        assert_eq!(merkle_tree.root(), padded_root(&leaves, 4, zero_leaf));
+        assert_ne!(
+            merkle_tree.root(),
+            IncrementalMerkleTree::<Sha256>::new(4)
+                .map(|mut tree| {
+                    leaves.iter().for_each(|leaf| {
+                        tree.append(*leaf).unwrap();
+                    });
+                    tree.root()
+                })
+                .unwrap()
+        );
+    }
+
+    #[test]
+    pub fn should_make_proofs_compatible_with_merkle_proof() {
+        let mut merkle_tree = IncrementalMerkleTree::<Sha256>::new(20).unwrap();
+        let leaves = common::leaves(11);
+        for leaf in &leaves {
+            merkle_tree.append(*leaf).unwrap();
+        }
+        let root = merkle_tree.root();
+
+        let capacity = merkle_tree.capacity() as usize;
+
+        for (index, leaf) in leaves.iter().enumerate() {
+            let proof = merkle_tree.proof(&leaves, index).unwrap();
+            assert_eq!(proof.proof_hashes().len(), 20);
+            assert!(proof.verify(root, &[index], &[*leaf], capacity));
+            assert!(!proof.verify(root, &[index], &[[0u8; 32]], capacity));
+            assert!(IncrementalMerkleTree::verify(
+                root,
+                &proof,
+                20,
+                index as u64,
+                *leaf
+            ));
+            assert!(!IncrementalMerkleTree::verify(
+                root,
+                &proof,
+                20,
+                index as u64 + 1,
+                *leaf
+            ));
+            assert!(!IncrementalMerkleTree::verify(
+                root,
+                &proof,
+                21,
+                index as u64,
+                *leaf
+            ));
+
+            let proof = MerkleProof::<Sha256>::from_bytes(&proof.to_bytes()).unwrap();
+            assert!(proof.verify(root, &[index], &[*leaf], capacity));
+        }
+    }
+
+    #[test]
+    pub fn should_count_leaves_beyond_32_bits() {
+        // 2^32 leaves don't fit a usize on 32-bit platforms
+        let mut merkle_tree = IncrementalMerkleTree::<Sha256>::new(32).unwrap();
+        assert_eq!(merkle_tree.capacity(), 1u64 << 32);
+
+        let leaves = common::leaves(3);
+        for leaf in &leaves {
+            merkle_tree.append(*leaf).unwrap();
+        }
+        let root = merkle_tree.root();
+        let proof = merkle_tree.proof(&leaves, 2).unwrap();
+        assert!(IncrementalMerkleTree::verify(
+            root, &proof, 32, 2, leaves[2]
+        ));
+
+        let merkle_tree = IncrementalMerkleTree::<Sha256>::new(63).unwrap();
+        assert_eq!(merkle_tree.capacity(), 1u64 << 63);
+        assert_eq!(merkle_tree.root(), merkle_tree.zero_hashes()[63]);
+    }
+
+    #[test]
+    pub fn should_return_an_error_when_the_tree_is_full() {
+        let mut merkle_tree = IncrementalMerkleTree::<Sha256>::new(2).unwrap();
+        for leaf in common::leaves(4) {
+            merkle_tree.append(leaf).unwrap();
+        }
+        let root = merkle_tree.root();
+
+        assert_eq!(
+            merkle_tree.append([0u8; 32]).err().map(|err| err.kind()),
+            Some(ErrorKind::TreeIsFull)
+        );
+        assert_eq!(merkle_tree.leaves_len(), 4);
+        assert_eq!(merkle_tree.root(), root);
+    }
+
+    #[test]
+    pub fn should_return_an_error_for_invalid_arguments() {
+        assert_eq!(
+            IncrementalMerkleTree::<Sha256>::new(64)
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::InvalidDepth)
+        );
+        assert_eq!(
+            IncrementalMerkleTree::<Sha256>::new(0)
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::InvalidDepth)
+        );
+
+        let mut merkle_tree = IncrementalMerkleTree::<Sha256>::new(8).unwrap();
+        let leaves = common::leaves(3);
+        for leaf in &leaves {
+            merkle_tree.append(*leaf).unwrap();
+        }
+        assert_eq!(
+            merkle_tree
+                .proof(&leaves[..2], 0)
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::LeavesCountMismatch)
+        );
+        assert_eq!(
+            merkle_tree.proof(&leaves, 3).err().map(|err| err.kind()),
+            Some(ErrorKind::LeafIndexOutOfRange)
+        );
+    }
+}
+
+pub mod padding {
+    use crate::common;
+    use rs_merkle::{
+        algorithms::Sha256, ErrorKind, Hasher, IncrementalMerkleTree, MerkleTree, Padding,
+    };
+
+    /// Root of a tree where the filler leaves are stored
+    fn explicitly_padded_root(
+        leaves: &[[u8; 32]],
+        padded_leaves_count: usize,
+        filler_leaf: [u8; 32],
+    ) -> [u8; 32] {
+        let mut padded_leaves = leaves.to_vec();
+        padded_leaves.resize(padded_leaves_count, filler_leaf);
+        MerkleTree::<Sha256>::from_leaves(&padded_leaves)
+            .root()
+            .unwrap()
+    }
+
+    #[test]
+    pub fn should_pad_to_the_next_power_of_two() {
+        let filler_leaf = Sha256::hash(b"filler");
+        for count in 1..40 {
+            let leaves = common::leaves(count);
+            let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves)
+                .with_padding(Padding::to_power_of_two(filler_leaf))
+                .unwrap();
+
+            assert_eq!(
+                merkle_tree.root(),
+                Some(explicitly_padded_root(
+                    &leaves,
+                    count.next_power_of_two(),
+                    filler_leaf
+                ))
+            );
+            assert_eq!(merkle_tree.leaves_len(), count);
+            assert_eq!(merkle_tree.leaves(), Some(leaves));
+        }
+    }
+
+    #[test]
+    pub fn should_pad_to_a_limit() {
+        let padding = Padding::<Sha256>::to_limit([0u8; 32], 20).unwrap();
+        let mut incremental_tree = IncrementalMerkleTree::<Sha256>::new(5).unwrap();
+
+        for (count, leaf) in common::leaves(20).iter().enumerate() {
+            incremental_tree.append(*leaf).unwrap();
+            let leaves = common::leaves(count + 1);
+            let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves)
+                .with_padding(padding.clone())
+                .unwrap();
+
+            assert_eq!(
+                merkle_tree.root(),
+                Some(explicitly_padded_root(&leaves, 32, [0u8; 32]))
+            );
+            assert_eq!(merkle_tree.root(), Some(incremental_tree.root()));
+        }
+    }
+
+    #[test]
+    pub fn should_return_the_root_of_padding_for_an_empty_tree() {
+        let padding = Padding::<Sha256>::to_limit([0u8; 32], 16).unwrap();
+        let merkle_tree = MerkleTree::<Sha256>::new().with_padding(padding).unwrap();
+
+        assert_eq!(
+            merkle_tree.root(),
+            Some(IncrementalMerkleTree::<Sha256>::new(4).unwrap().root())
+        );
+        assert_eq!(
+            MerkleTree::<Sha256>::new()
+                .with_padding(Padding::to_power_of_two([7u8; 32]))
+                .unwrap()
+                .root(),
+            Some([7u8; 32])
+        );
+    }
+
+    #[test]
+    pub fn should_make_compact_proofs_that_verify_with_the_padding() {
+        let filler_leaf = Sha256::hash(b"filler");
+        let leaves = common::leaves(11);
+        for padding in [
+            Padding::<Sha256>::to_power_of_two(filler_leaf),
+            Padding::<Sha256>::to_limit(filler_leaf, 100).unwrap(),
+        ] {
+            let padded_leaves_count = padding.padded_leaves_count(leaves.len()).unwrap();
+            let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves)
+                .with_padding(padding.clone())
+                .unwrap();
+            let root = merkle_tree.root().unwrap();
+
+            let mut padded_leaves = leaves.clone();
+            padded_leaves.resize(padded_leaves_count, filler_leaf);
+            let explicitly_padded_tree = MerkleTree::<Sha256>::from_leaves(&padded_leaves);
+
+            for indices in [vec![0], vec![10], vec![3, 4, 9], vec![1, 10]] {
+                let leaves_to_prove: Vec<[u8; 32]> =
+                    indices.iter().map(|index| leaves[*index]).collect();
+                let proof = merkle_tree.proof(&indices);
+
+                assert!(proof.verify_padded(root, &indices, &leaves_to_prove, 11, &padding));
+                assert!(!proof.verify_padded(
+                    root,
+                    &indices,
+                    &vec![filler_leaf; indices.len()],
+                    11,
+                    &padding
+                ));
+                assert_eq!(
+                    proof
+                        .root_padded(&indices, &leaves_to_prove, 11, &padding)
+                        .ok(),
+                    Some(root)
+                );
+                assert!(
+                    proof.proof_hashes().len()
+                        <= explicitly_padded_tree.proof(&indices).proof_hashes().len()
+                );
+            }
+
+            // Padding nodes are left out of the proof
+            assert_eq!(merkle_tree.proof(&[10]).proof_hashes().len(), 2);
+        }
+    }
+
+    #[test]
+    pub fn should_commit_and_rollback_padded_leaves() {
+        let padding = Padding::<Sha256>::to_power_of_two([0u8; 32]);
+        let leaves = common::leaves(13);
+        let mut merkle_tree = MerkleTree::<Sha256>::new().with_padding(padding).unwrap();
+
+        let mut roots = Vec::new();
+        for chunk in [0..1, 1..2, 2..5, 5..8, 8..9, 9..13] {
+            merkle_tree.append(leaves[chunk.clone()].to_vec().as_mut());
+            let uncommitted_root = merkle_tree.uncommitted_root();
+            merkle_tree.commit();
+
+            let expected_root = explicitly_padded_root(
+                &leaves[..chunk.end],
+                chunk.end.next_power_of_two(),
+                [0u8; 32],
+            );
+            assert_eq!(merkle_tree.root(), Some(expected_root));
+            assert_eq!(uncommitted_root, Some(expected_root));
+            roots.push(expected_root);
+        }
+
+        for root in roots.iter().rev().skip(1) {
+            merkle_tree.rollback();
+            assert_eq!(merkle_tree.root(), Some(*root));
+        }
+    }
+
+    #[test]
+    pub fn should_pad_the_history_of_a_tree() {
+        let leaves = common::leaves(6);
+        let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves[..3]);
+        merkle_tree.append(leaves[3..].to_vec().as_mut()).commit();
+        merkle_tree.insert(leaves[0]);
+
+        let mut merkle_tree = merkle_tree
+            .with_padding(Padding::to_power_of_two([0u8; 32]))
+            .unwrap();
+        assert_eq!(
+            merkle_tree.root(),
+            Some(explicitly_padded_root(&leaves, 8, [0u8; 32]))
+        );
+
+        merkle_tree.commit();
+        assert_eq!(merkle_tree.leaves_len(), 7);
+
+        merkle_tree.rollback();
+        merkle_tree.rollback();
+        assert_eq!(
+            merkle_tree.root(),
+            Some(explicitly_padded_root(&leaves[..3], 4, [0u8; 32]))
+        );
+    }
+
+    #[test]
+    pub fn should_not_exceed_the_limit() {
+        let padding = Padding::<Sha256>::to_limit([0u8; 32], 4).unwrap();
+        let leaves = common::leaves(5);
+
+        assert_eq!(
+            MerkleTree::<Sha256>::from_leaves(&leaves)
+                .with_padding(padding.clone())
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::TreeIsFull)
+        );
+
+        let mut merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves[..3])
+            .with_padding(padding.clone())
+            .unwrap();
+        assert_eq!(
+            merkle_tree
+                .try_append(leaves[3..].to_vec().as_mut())
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::TreeIsFull)
+        );
+        merkle_tree.try_insert(leaves[3]).unwrap().commit();
+        assert_eq!(
+            merkle_tree
+                .try_insert(leaves[4])
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::TreeIsFull)
+        );
+
+        let proof = merkle_tree.proof(&[0]);
+        assert_eq!(
+            proof
+                .root_padded(&[0], &leaves[..1], 5, &padding)
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::TreeIsFull)
+        );
+        assert_eq!(
+            Padding::<Sha256>::to_limit([0u8; 32], usize::MAX)
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::InvalidDepth)
+        );
+    }
+}
+
+pub mod authenticated_map {
+    use rs_merkle::{
+        algorithms::Sha256, AuthenticatedMap, ErrorKind, MapProof, MerkleTree, NonMembershipProof,
+    };
+
+    type Map = AuthenticatedMap<String, Vec<u8>, Sha256>;
+
+    fn entries() -> Vec<(String, Vec<u8>)> {
+        ["d", "b", "a", "c", "e"]
+            .iter()
+            .map(|key| (key.to_string(), key.repeat(3).into_bytes()))
+            .collect()
+    }
+
+    #[test]
+    pub fn should_order_leaves_by_key() {
+        let map = Map::from_entries(entries());
+
+        let mut sorted_entries = entries();
+        sorted_entries.sort();
+        let leaves: Vec<[u8; 32]> = sorted_entries
+            .iter()
+            .map(|(key, value)| Map::leaf_hash(key, value))
+            .collect();
+
+        assert_eq!(map.len(), 5);
+        assert_eq!(
+            map.root(),
+            MerkleTree::<Sha256>::from_leaves(&leaves).root()
+        );
+        assert_eq!(
+            map.root(),
+            Map::from_entries(entries().into_iter().rev()).root()
+        );
+        assert_eq!(
+            map.iter().map(|(key, _)| key.as_str()).collect::<Vec<_>>(),
+            ["a", "b", "c", "d", "e"]
+        );
+    }
+
+    #[test]
+    pub fn should_prove_every_entry() {
+        let map = Map::from_entries(entries());
+        let root = map.root().unwrap();
+
+        for (key, value) in entries() {
+            let (proven_value, proof) = map.get_with_proof(&key).unwrap();
+            assert_eq!(proven_value, Some(&value));
+            assert!(Map::verify(root, 5, &key, Some(&value), &proof));
+            assert!(!Map::verify(
+                root,
+                5,
+                &key,
+                Some(&b"other".to_vec()),
+                &proof
+            ));
+            assert!(!Map::verify(root, 5, &key, None, &proof));
+            // The number of entries comes from the verifier, not from the proof
+            assert!(!Map::verify(root, 4, &key, Some(&value), &proof));
+        }
+    }
+
+    #[test]
+    pub fn should_prove_absence_of_missing_keys() {
+        let map = Map::from_entries(vec![
+            ("b".to_string(), b"1".to_vec()),
+            ("d".to_string(), b"2".to_vec()),
+            ("f".to_string(), b"3".to_vec()),
+        ]);
+        let root = map.root().unwrap();
+
+        for key in ["a", "c", "e", "g", "ca"] {
+            let key = key.to_string();
+            let (value, proof) = map.get_with_proof(&key).unwrap();
+            assert_eq!(value, None);
+            assert!(Map::verify(root, 3, &key, None, &proof));
+            assert!(!Map::verify(root, 3, &key, Some(&b"1".to_vec()), &proof));
+            assert!(!Map::verify(root, 4, &key, None, &proof));
+
+            // The proof doesn't work for keys outside of the gap between the neighbours
+            for present_key in ["b", "d", "f"] {
+                assert!(!Map::verify(
+                    root,
+                    3,
+                    &present_key.to_string(),
+                    None,
+                    &proof
+                ));
+            }
+        }
+
+        assert_eq!(
+            Map::new()
+                .get_with_proof(&"a".to_string())
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::EmptyTree)
+        );
+    }
+
+    #[test]
+    pub fn should_reject_absence_proofs_with_wrong_neighbours() {
+        let map = Map::from_entries(vec![
+            ("b".to_string(), b"1".to_vec()),
+            ("d".to_string(), b"2".to_vec()),
+            ("f".to_string(), b"3".to_vec()),
+        ]);
+        let root = map.root().unwrap();
+        let key = "c".to_string();
+
+        // Neighbours that are in the map, but not next to each other
+        let left = ("b".to_string(), b"1".to_vec());
+        let right = ("f".to_string(), b"3".to_vec());
+        let skipping_proof = MapProof::NonMembership {
+            proof: NonMembershipProof::new(
+                Some((0, Map::leaf_hash(&left.0, &left.1))),
+                Some((2, Map::leaf_hash(&right.0, &right.1))),
+                map.merkle_tree().proof(&[0, 2]),
+                3,
+            ),
+            left: Some(left),
+            right: Some(right),
+        };
+        assert!(!Map::verify(root, 3, &key, None, &skipping_proof));
+
+        // A neighbour with another value than the one in the map
+        let (_, proof) = map.get_with_proof(&key).unwrap();
+        let changed_proof = match proof {
+            MapProof::NonMembership { left, proof, .. } => MapProof::NonMembership {
+                left,
+                right: Some(("d".to_string(), b"changed".to_vec())),
+                proof,
+            },
+            MapProof::Membership { .. } => panic!("the key is not in the map"),
+        };
+        assert!(!Map::verify(root, 3, &key, None, &changed_proof));
+    }
+
+    #[test]
+    pub fn should_match_a_rebuilt_map_after_every_commit() {
+        let mut map = Map::new();
+        let mut expected = std::collections::BTreeMap::new();
+        let mut roots = vec![map.root()];
+        let mut state: u32 = 7;
+
+        for round in 0..60 {
+            for _ in 0..(round % 4 + 1) {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                let key = format!("{:02}", (state >> 16) % 24);
+                if (state >> 8) % 3 == 0 {
+                    map.remove(key.clone());
+                    expected.remove(&key);
+                } else {
+                    let value = format!("{}-{}", key, round).into_bytes();
+                    map.insert(key.clone(), value.clone());
+                    expected.insert(key, value);
+                }
+            }
+            // Removing everything from time to time
+            if round % 25 == 24 {
+                for key in expected.keys() {
+                    map.remove(key.clone());
+                }
+                expected.clear();
+            }
+
+            let uncommitted_root = map.uncommitted_root();
+            map.commit();
+            let rebuilt = Map::from_entries(expected.clone());
+            assert_eq!(map.root(), rebuilt.root());
+            assert_eq!(map.merkle_tree().leaves(), rebuilt.merkle_tree().leaves());
+            if !expected.is_empty() {
+                assert_eq!(map.merkle_tree().depth(), rebuilt.merkle_tree().depth());
+                assert_eq!(uncommitted_root, map.root());
+            }
+            roots.push(map.root());
+        }
+
+        // Rolling back restores every previous root and the entries behind it
+        while roots.len() > 1 {
+            roots.pop();
+            map.rollback();
+            assert_eq!(map.root(), *roots.last().unwrap());
+        }
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    pub fn should_not_confuse_key_and_value_boundaries() {
+        assert_ne!(
+            Map::leaf_hash(&"ab".to_string(), &b"c".to_vec()),
+            Map::leaf_hash(&"a".to_string(), &b"bc".to_vec())
+        );
+    }
+
+    #[test]
+    pub fn should_stage_changes_until_commit() {
+        let mut map = Map::from_entries(entries());
+        let root = map.root();
+        assert_eq!(map.uncommitted_root(), None);
+
+        map.insert("a".to_string(), b"updated".to_vec())
+            .remove("e".to_string())
+            .insert("f".to_string(), b"fff".to_vec());
+        assert_eq!(map.root(), root);
+        assert_eq!(map.get(&"a".to_string()), Some(&b"aaa".to_vec()));
+        let uncommitted_root = map.uncommitted_root();
+
+        map.commit();
+        assert_eq!(map.root(), uncommitted_root);
+        assert_eq!(map.get(&"a".to_string()), Some(&b"updated".to_vec()));
+        assert!(!map.contains_key(&"e".to_string()));
+        assert_eq!(map.len(), 5);
+
+        let mut expected_entries = entries();
+        expected_entries.retain(|(key, _)| key != "a" && key != "e");
+        expected_entries.push(("a".to_string(), b"updated".to_vec()));
+        expected_entries.push(("f".to_string(), b"fff".to_vec()));
+        assert_eq!(map.root(), Map::from_entries(expected_entries).root());
+    }
+
+    #[test]
+    pub fn should_rollback_commits() {
+        let mut map = Map::from_entries(entries());
+        let first_root = map.root();
+
+        map.insert("a".to_string(), b"updated".to_vec()).commit();
+        let second_root = map.root();
+        map.remove("a".to_string()).remove("b".to_string()).commit();
+        assert_eq!(map.len(), 3);
+
+        map.rollback();
+        assert_eq!(map.root(), second_root);
+        assert_eq!(map.get(&"b".to_string()), Some(&b"bbb".to_vec()));
+
+        map.rollback();
+        assert_eq!(map.root(), first_root);
+        assert_eq!(map.get(&"a".to_string()), Some(&b"aaa".to_vec()));
+
+        map.rollback();
+        assert!(map.is_empty());
+        assert_eq!(map.root(), None);
+    }
+
+    #[test]
+    pub fn should_abort_uncommitted_changes() {
+        let mut map = Map::from_entries(entries());
+        let root = map.root();
+
+        map.remove("a".to_string());
+        map.abort_uncommitted();
+        map.commit();
+
+        assert_eq!(map.root(), root);
+        assert_eq!(map.uncommitted_root(), None);
+    }
+}
+
+pub mod chunking {
+    use rs_merkle::{
+        algorithms::Sha256,
+        chunking::{ChunkedTree, Chunking, DirectoryTree},
+        ErrorKind, Hasher, MerkleTree,
+    };
+    use std::fs;
+
+    fn test_data(len: usize) -> Vec<u8> {
+        let mut state: u32 = 1;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                (state >> 16) as u8
+            })
+            .collect()
+    }
+
+    const CONTENT_DEFINED: Chunking = Chunking::ContentDefined {
+        min_size: 256,
+        average_size: 1024,
+        max_size: 4096,
+    };
+
+    #[test]
+    pub fn should_build_the_same_tree_as_from_leaves() {
+        let data = test_data(10_000);
+
+        for chunking in [
+            Chunking::Fixed(1000),
+            Chunking::Fixed(4096),
+            CONTENT_DEFINED,
+        ] {
+            // Reading byte by byte checks that chunks don't depend on read sizes
+            let reader = std::io::BufReader::with_capacity(1, data.as_slice());
+            let chunked_tree = ChunkedTree::<Sha256>::from_reader(reader, chunking).unwrap();
+            let chunks = chunking.split(&data).unwrap();
+            let leaves: Vec<[u8; 32]> = chunks.iter().map(|chunk| Sha256::hash(chunk)).collect();
+
+            assert_eq!(
+                chunked_tree.root(),
+                MerkleTree::<Sha256>::from_leaves(&leaves).root()
+            );
+            assert_eq!(chunked_tree.chunks_count(), chunks.len());
+            assert_eq!(
+                chunked_tree.chunk_lengths().iter().sum::<usize>(),
+                data.len()
+            );
+
+            let root = chunked_tree.root().unwrap();
+            for (index, chunk) in chunks.iter().enumerate() {
+                let proof = chunked_tree.chunk_proof(index).unwrap();
+                assert!(ChunkedTree::<Sha256>::verify_chunk(
+                    root,
+                    &proof,
+                    index,
+                    chunk,
+                    chunks.len()
+                ));
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_keep_content_defined_chunks_after_an_insertion() {
+        let data = test_data(50_000);
+        let mut changed_data = data[..100].to_vec();
+        changed_data.extend_from_slice(b"inserted");
+        changed_data.extend_from_slice(&data[100..]);
+
+        let chunks = CONTENT_DEFINED.split(&data).unwrap();
+        let changed_chunks = CONTENT_DEFINED.split(&changed_data).unwrap();
+        let (last_chunk, other_chunks) = chunks.split_last().unwrap();
+        assert!(last_chunk.len() <= 4096);
+        assert!(other_chunks
+            .iter()
+            .all(|chunk| chunk.len() >= 256 && chunk.len() <= 4096));
+
+        let common_chunks = chunks
+            .iter()
+            .skip(1)
+            .filter(|chunk| changed_chunks.contains(chunk))
+            .count();
+        assert!(common_chunks >= chunks.len() - 3);
+    }
+
+    #[test]
+    pub fn should_reject_invalid_chunking() {
+        let kind = |chunking: Chunking| {
+            ChunkedTree::<Sha256>::from_reader(&[1u8][..], chunking)
+                .err()
+                .map(|err| err.kind())
+        };
+
+        assert_eq!(kind(Chunking::Fixed(0)), Some(ErrorKind::InvalidChunking));
+        assert_eq!(
+            kind(Chunking::ContentDefined {
+                min_size: 16,
+                average_size: 1000,
+                max_size: 4096
+            }),
+            Some(ErrorKind::InvalidChunking)
+        );
+        assert_eq!(
+            ChunkedTree::<Sha256>::from_reader(&[][..], Chunking::Fixed(4))
+                .unwrap()
+                .root(),
+            None
+        );
+    }
+
+    #[test]
+    pub fn should_merkleize_directories() {
+        let directory = std::env::temp_dir().join(format!("rs-merkle-dir-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(directory.join("b/c")).unwrap();
+        fs::write(directory.join("a.txt"), b"first").unwrap();
+        fs::write(directory.join("b/c/d.txt"), test_data(5000)).unwrap();
+        fs::write(directory.join("b/empty"), b"").unwrap();
+
+        let chunking = Chunking::Fixed(1024);
+        let directory_tree = DirectoryTree::<Sha256>::from_path(&directory, chunking).unwrap();
+        assert_eq!(directory_tree.paths(), &["a.txt", "b/c/d.txt", "b/empty"]);
+
+        let root = directory_tree.root().unwrap();
+        let content_root = DirectoryTree::<Sha256>::content_root(
+            &ChunkedTree::from_reader(test_data(5000).as_slice(), chunking).unwrap(),
+        );
+        let leaf = DirectoryTree::<Sha256>::file_leaf("b/c/d.txt", content_root);
+        let (index, proof) = directory_tree.file_proof("b/c/d.txt").unwrap();
+        assert_eq!(index, 1);
+        assert!(proof.verify(root, &[index], &[leaf], 3));
+        assert!(directory_tree.file_proof("b/c").is_none());
+
+        // Changing a file changes the root
+        fs::write(directory.join("b/empty"), b"not empty").unwrap();
+        let changed_tree = DirectoryTree::<Sha256>::from_path(&directory, chunking).unwrap();
+        assert_ne!(changed_tree.root(), Some(root));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    pub fn should_hash_symlink_targets_instead_of_following_them() {
+        use std::os::unix::fs::symlink;
+
+        let directory =
+            std::env::temp_dir().join(format!("rs-merkle-links-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(directory.join("b")).unwrap();
+        fs::write(directory.join("a.txt"), b"first").unwrap();
+        // Both links point back to a directory that contains them
+        symlink("..", directory.join("b/parent")).unwrap();
+        symlink(".", directory.join("self")).unwrap();
+        symlink("a.txt", directory.join("z")).unwrap();
+
+        let chunking = Chunking::Fixed(1024);
+        let directory_tree = DirectoryTree::<Sha256>::from_path(&directory, chunking).unwrap();
+        assert_eq!(directory_tree.paths(), &["a.txt", "b/parent", "self", "z"]);
+
+        let root = directory_tree.root().unwrap();
+        let leaf = DirectoryTree::<Sha256>::symlink_leaf("b/parent", "..");
+        let (index, proof) = directory_tree.file_proof("b/parent").unwrap();
+        assert_eq!(index, 1);
+        assert!(proof.verify(root, &[index], &[leaf], 4));
+
+        // A link and a file with the target path as content have different leaves
+        let (index, proof) = directory_tree.file_proof("z").unwrap();
+        let link_leaf = DirectoryTree::<Sha256>::symlink_leaf("z", "a.txt");
+        let file_leaf = DirectoryTree::<Sha256>::file_leaf("z", Sha256::hash(b"a.txt"));
+        assert_ne!(link_leaf, file_leaf);
+        assert!(proof.verify(root, &[index], &[link_leaf], 4));
+
+        // Retargeting a link changes the root
+        fs::remove_file(directory.join("z")).unwrap();
+        symlink("b", directory.join("z")).unwrap();
+        let changed_tree = DirectoryTree::<Sha256>::from_path(&directory, chunking).unwrap();
+        assert_ne!(changed_tree.root(), Some(root));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
+
+#[cfg(feature = "wasm")]
+pub mod wasm {
+    use crate::common;
+    use rs_merkle::{
+        algorithms::{Keccak256, Sha256},
+        utils, wasm, ErrorKind, Hasher, MerkleTree,
+    };
+
+    #[test]
+    pub fn should_build_trees_and_verify_proofs() {
+        let test_data = common::setup();
+        let leaves: Vec<u8> = test_data.leaf_hashes.concat();
+
+        let tree = wasm::WasmMerkleTree::new("sha256", &leaves).unwrap();
+        assert_eq!(tree.root_hex(), Some(test_data.expected_root_hex.clone()));
+        assert_eq!(tree.leaves_len(), 6);
+
+        let root = tree.root().unwrap();
+        let proof = tree.proof(&[3, 4]).unwrap();
+        let proven_leaves = [test_data.leaf_hashes[3], test_data.leaf_hashes[4]].concat();
+        assert!(wasm::verify_proof("sha256", &proof, &root, &[3, 4], &proven_leaves, 6).unwrap());
+        assert!(!wasm::verify_proof("sha256", &proof, &root, &[3, 5], &proven_leaves, 6).unwrap());
+
+        assert!(wasm::verify_proof_hex(
+            "sha256",
+            &tree.proof_hex(&[3, 4]).unwrap(),
+            &test_data.expected_root_hex,
+            &[3, 4],
+            &utils::collections::to_hex_string(&proven_leaves),
+            6
+        )
+        .unwrap());
+    }
+
+    #[test]
+    pub fn should_match_the_rust_api() {
+        let leaves: Vec<[u8; 32]> = ["a", "b", "c"]
+            .iter()
+            .map(|x| Keccak256::hash(x.as_bytes()))
+            .collect();
+        let merkle_tree = MerkleTree::<Keccak256>::from_leaves(&leaves);
+
+        assert_eq!(wasm::hash("keccak256", b"a").unwrap(), leaves[0].to_vec());
+        let tree = wasm::WasmMerkleTree::new("keccak256", &leaves.concat()).unwrap();
+        assert_eq!(tree.root(), merkle_tree.root().map(|root| root.to_vec()));
+        assert_eq!(
+            tree.proof(&[1]).unwrap(),
+            merkle_tree.proof(&[1]).to_bytes()
+        );
+        assert_eq!(wasm::hash("sha384", b"a").unwrap().len(), 48);
+        assert_eq!(
+            wasm::hash("sha256", b"a").unwrap(),
+            Sha256::hash(b"a").to_vec()
+        );
+    }
+
+    #[test]
+    pub fn should_reject_bad_input() {
+        let kind = |result: Result<bool, rs_merkle::Error>| result.err().map(|err| err.kind());
+
+        assert_eq!(
+            wasm::WasmMerkleTree::new("md5", &[])
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::UnknownAlgorithm)
+        );
+        assert_eq!(
+            wasm::WasmMerkleTree::new("sha256", &[0; 33])
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::SerializedProofSizeIsIncorrect)
+        );
+        assert_eq!(
+            kind(wasm::verify_proof_hex("sha256", "zz", "", &[0], "", 1)),
+            Some(ErrorKind::HashConversionError)
+        );
+        assert_eq!(
+            kind(wasm::verify_proof(
+                "sha256",
+                &[],
+                &[0; 31],
+                &[0],
+                &[0; 32],
+                1
+            )),
+            Some(ErrorKind::HashConversionError)
+        );
+    }
+}
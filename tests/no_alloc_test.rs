@@ -0,0 +1,47 @@
+use rs_merkle::{algorithms::Sha256, Hasher, MerkleProofRef, MerkleTree};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Counts allocations made by the current thread, so tests running in parallel don't count
+/// each other's
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations() -> usize {
+    ALLOCATIONS.with(Cell::get)
+}
+
+#[test]
+pub fn should_verify_borrowed_proofs_without_allocating() {
+    let leaves: Vec<[u8; 32]> = (0..37u32).map(|i| Sha256::hash(&i.to_le_bytes())).collect();
+    let merkle_tree = MerkleTree::<Sha256>::from_leaves(&leaves);
+    let root = merkle_tree.root().unwrap();
+    let proofs: Vec<Vec<u8>> = (0..leaves.len())
+        .map(|index| merkle_tree.proof(&[index]).to_bytes())
+        .collect();
+
+    let allocations_before = allocations();
+    for (index, proof_bytes) in proofs.iter().enumerate() {
+        let proof = MerkleProofRef::<Sha256>::from_bytes(proof_bytes).unwrap();
+        assert!(proof.verify(root, index, leaves[index], leaves.len()));
+        assert!(!proof.verify(root, index, root, leaves.len()));
+    }
+    assert_eq!(allocations(), allocations_before);
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "python")]
+
+pub mod python {
+    use pyo3::{prelude::*, types::PyDict};
+    use rs_merkle::python::rs_merkle as module;
+    use std::{ffi::CString, sync::Once};
+
+    static INIT: Once = Once::new();
+
+    /// Runs Python code with the extension module imported as `rs_merkle`
+    fn run(code: &str) {
+        INIT.call_once(|| {
+            pyo3::append_to_inittab!(module);
+            pyo3::prepare_freethreaded_python();
+        });
+
+        Python::with_gil(|py| {
+            let globals = PyDict::new(py);
+            let code = CString::new(format!("import rs_merkle\n{}", code)).unwrap();
+            if let Err(error) = py.run(&code, Some(&globals), None) {
+                panic!("{}", error);
+            }
+        });
+    }
+
+    #[test]
+    pub fn should_build_a_tree_and_get_the_root() {
+        run(r#"
+from rs_merkle import MerkleTree, Sha256
+leaves = [Sha256.hash(x) for x in [b"a", b"b", b"c", b"d", b"e", b"f"]]
+tree = MerkleTree("sha256", leaves)
+root = "1f7379539707bcaea00564168d1d4d626b09b73f8a2a365234c62d763f854da2"
+assert tree.root_hex() == root
+assert tree.root() == bytes.fromhex(root)
+assert tree.leaves_len() == 6
+assert MerkleTree("sha256").root() is None
+"#);
+    }
+
+    #[test]
+    pub fn should_make_and_verify_a_proof() {
+        run(r#"
+from rs_merkle import MerkleTree, MerkleProof, Sha256
+leaves = [Sha256.hash(x) for x in [b"a", b"b", b"c", b"d", b"e", b"f"]]
+tree = MerkleTree("sha256", leaves)
+proof = tree.proof([3, 4])
+assert proof.verify(tree.root(), [3, 4], leaves[3:5], 6)
+assert not proof.verify(tree.root(), [3, 4], leaves[2:4], 6)
+assert proof.root([3, 4], leaves[3:5], 6) == tree.root()
+
+parsed = MerkleProof.from_bytes("sha256", proof.to_bytes())
+assert parsed.proof_hashes() == proof.proof_hashes()
+assert MerkleProof("sha256", proof.proof_hashes()).to_bytes() == proof.to_bytes()
+"#);
+    }
+
+    #[test]
+    pub fn should_match_the_odd_node_handling_of_the_tree() {
+        run(r#"
+from rs_merkle import MerkleTree, Keccak256
+leaves = [Keccak256.hash(x) for x in [b"a", b"b", b"c"]]
+ab = Keccak256.concat_and_hash(leaves[0], leaves[1])
+c = Keccak256.concat_and_hash(leaves[2])
+assert c == leaves[2]
+assert MerkleTree("keccak256", leaves).root() == Keccak256.concat_and_hash(ab, c)
+assert Keccak256.hash_size() == 32
+"#);
+    }
+
+    #[test]
+    pub fn should_commit_and_rollback() {
+        run(r#"
+from rs_merkle import MerkleTree, Sha384
+leaves = [Sha384.hash(x) for x in [b"a", b"b", b"c"]]
+tree = MerkleTree("sha384", leaves[:2])
+root = tree.root()
+tree.append(leaves[2:])
+assert tree.leaves_len() == 2
+tree.commit()
+assert tree.leaves_len() == 3
+tree.rollback()
+assert tree.root() == root
+"#);
+    }
+
+    #[test]
+    pub fn should_raise_value_errors() {
+        run(r#"
+from rs_merkle import MerkleTree, MerkleProof, Sha256
+
+def raises(function):
+    try:
+        function()
+    except ValueError:
+        return True
+    return False
+
+assert raises(lambda: MerkleTree("md5", []))
+assert raises(lambda: MerkleTree("sha256", [b"short"]))
+assert raises(lambda: MerkleTree("sha256", [Sha256.hash(b"a")]).proof([1]))
+assert raises(lambda: MerkleProof.from_bytes("sha256", b"\x00" * 33))
+assert raises(lambda: Sha256.concat_and_hash(b"short"))
+"#);
+    }
+}
@@ -0,0 +1,376 @@
+#![cfg(feature = "solidity")]
+
+pub mod solidity {
+    use rs_merkle::{
+        algorithms::Keccak256,
+        solidity::{self, OddNodePolicy, SolidityHasher, VerifierConfig},
+        Hasher, MerkleTree,
+    };
+
+    fn word(calldata: &[u8], position: usize) -> &[u8] {
+        &calldata[4 + position * 32..4 + (position + 1) * 32]
+    }
+
+    fn word_value(calldata: &[u8], position: usize) -> usize {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&word(calldata, position)[24..]);
+        u64::from_be_bytes(bytes) as usize
+    }
+
+    #[test]
+    pub fn should_compute_selectors() {
+        assert_eq!(
+            solidity::selector("transfer(address,uint256)"),
+            [0xa9, 0x05, 0x9c, 0xbb]
+        );
+        assert_eq!(
+            solidity::selector(solidity::VERIFY_SIGNATURE),
+            solidity::VERIFY_SELECTOR
+        );
+    }
+
+    #[test]
+    pub fn should_encode_verify_call() {
+        let leaves: Vec<[u8; 32]> = ["a", "b", "c", "d", "e", "f"]
+            .iter()
+            .map(|x| Keccak256::hash(x.as_bytes()))
+            .collect();
+        let merkle_tree = MerkleTree::<Keccak256>::from_leaves(&leaves);
+        let root = merkle_tree.root().unwrap();
+        let proof = merkle_tree.proof(&[1, 4]);
+
+        let calldata =
+            solidity::encode_verify_call(root, &proof, &[4, 1], &[leaves[4], leaves[1]], 6);
+        let proof_hashes_count = proof.proof_hashes().len();
+
+        // Selector, five head words and three arrays with their lengths
+        assert_eq!(
+            calldata.len(),
+            4 + 5 * 32 + (3 + proof_hashes_count + 2 + 2) * 32
+        );
+        assert_eq!(word(&calldata, 0), &root);
+        assert_eq!(word_value(&calldata, 1), 5 * 32);
+        assert_eq!(word_value(&calldata, 2), (6 + proof_hashes_count) * 32);
+        assert_eq!(word_value(&calldata, 3), (9 + proof_hashes_count) * 32);
+        assert_eq!(word_value(&calldata, 4), 6);
+
+        assert_eq!(word_value(&calldata, 5), proof_hashes_count);
+        for (position, hash) in proof.proof_hashes().iter().enumerate() {
+            assert_eq!(word(&calldata, 6 + position), hash);
+        }
+        // Indices and leaves are sorted
+        let indices_start = 6 + proof_hashes_count;
+        assert_eq!(word_value(&calldata, indices_start), 2);
+        assert_eq!(word_value(&calldata, indices_start + 1), 1);
+        assert_eq!(word_value(&calldata, indices_start + 2), 4);
+        assert_eq!(word(&calldata, indices_start + 4), &leaves[1]);
+        assert_eq!(word(&calldata, indices_start + 5), &leaves[4]);
+    }
+
+    #[test]
+    pub fn should_encode_standalone_arrays() {
+        assert_eq!(solidity::encode_indices(&[7]), {
+            let mut expected = vec![0u8; 96];
+            expected[31] = 32;
+            expected[63] = 1;
+            expected[95] = 7;
+            expected
+        });
+
+        let leaves = [Keccak256::hash(b"a"), Keccak256::hash(b"b")];
+        let proof = MerkleTree::<Keccak256>::from_leaves(&leaves).proof(&[0]);
+        let encoded = solidity::encode_proof(&proof);
+        assert_eq!(encoded.len(), 3 * 32);
+        assert_eq!(&encoded[64..], &leaves[1]);
+    }
+
+    #[test]
+    pub fn should_generate_source_for_the_configuration() {
+        let default_source = VerifierConfig::new(SolidityHasher::Keccak256).verifier_source();
+        assert!(default_source.contains("contract MerkleVerifier {"));
+        assert!(default_source.contains("return keccak256(abi.encodePacked(left, right));"));
+        assert!(default_source.contains("parent = node;"));
+
+        let custom_source = VerifierConfig::new(SolidityHasher::Sha256)
+            .with_contract_name("CustomVerifier")
+            .with_odd_node_policy(OddNodePolicy::Duplicate)
+            .with_node_prefix(&[1])
+            .verifier_source();
+        assert!(custom_source.contains("contract CustomVerifier {"));
+        assert!(custom_source.contains("return sha256(abi.encodePacked(hex\"01\", left, right));"));
+        assert!(custom_source.contains("parent = hashPair(node, node);"));
+    }
+}
+
+/// Compiles generated contracts with `solc` and runs them on the EVM. `solc` is taken from the
+/// `SOLC` environment variable or found on `PATH`, so the tests are ignored by default. Run them
+/// with `cargo test --features solidity --test solidity_test -- --ignored`.
+pub mod evm {
+    use revm::{
+        db::InMemoryDB,
+        primitives::{Address, Bytes, ExecutionResult, Output, TxKind},
+        Evm,
+    };
+    use rs_merkle::{
+        algorithms::{Keccak256, Sha256},
+        solidity::{self, OddNodePolicy, SolidityHasher, VerifierConfig},
+        Hasher, MerkleTree,
+    };
+    use std::{env, fs, process::Command};
+
+    /// Keccak256 that hashes the last node of an odd layer together with itself
+    #[derive(Clone)]
+    pub struct DuplicatingKeccak256 {}
+
+    impl Hasher for DuplicatingKeccak256 {
+        type Hash = [u8; 32];
+
+        fn hash(data: &[u8]) -> [u8; 32] {
+            Keccak256::hash(data)
+        }
+
+        fn concat_and_hash(left: &[u8; 32], right: Option<&[u8; 32]>) -> [u8; 32] {
+            Keccak256::concat_and_hash(left, Some(right.unwrap_or(left)))
+        }
+    }
+
+    /// Keccak256 that hashes a pair of nodes with a domain separation prefix
+    #[derive(Clone)]
+    pub struct PrefixedKeccak256 {}
+
+    impl Hasher for PrefixedKeccak256 {
+        type Hash = [u8; 32];
+
+        fn hash(data: &[u8]) -> [u8; 32] {
+            Keccak256::hash(data)
+        }
+
+        fn concat_and_hash(left: &[u8; 32], right: Option<&[u8; 32]>) -> [u8; 32] {
+            match right {
+                Some(right) => Keccak256::hash(&[&[1u8][..], left, right].concat()),
+                None => *left,
+            }
+        }
+    }
+
+    /// Compiles the contract and returns its creation bytecode
+    fn compile(config: &VerifierConfig) -> Vec<u8> {
+        let solc = env::var("SOLC").unwrap_or_else(|_| "solc".into());
+        let source = std::env::temp_dir().join(format!(
+            "rs-merkle-{}-{:?}.sol",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        fs::write(&source, config.verifier_source()).unwrap();
+
+        let output = Command::new(&solc)
+            .arg("--bin")
+            .arg("--optimize")
+            .arg(&source)
+            .output()
+            .unwrap_or_else(|err| panic!("couldn't run {}: {}", solc, err));
+        fs::remove_file(&source).unwrap();
+        assert!(
+            output.status.success(),
+            "solc failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let bytecode = stdout
+            .lines()
+            .skip_while(|line| !line.starts_with("Binary:"))
+            .nth(1)
+            .expect("solc didn't print the bytecode");
+        (0..bytecode.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&bytecode[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn deploy(db: &mut InMemoryDB, bytecode: Vec<u8>) -> Address {
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .modify_tx_env(|tx| {
+                tx.transact_to = TxKind::Create;
+                tx.data = Bytes::from(bytecode);
+            })
+            .build();
+        match evm.transact_commit().unwrap() {
+            ExecutionResult::Success {
+                output: Output::Create(_, Some(address)),
+                ..
+            } => address,
+            result => panic!("deployment failed: {:?}", result),
+        }
+    }
+
+    fn call_verify(db: &mut InMemoryDB, address: Address, calldata: Vec<u8>) -> bool {
+        let mut evm = Evm::builder()
+            .with_db(db)
+            .modify_tx_env(|tx| {
+                tx.transact_to = TxKind::Call(address);
+                tx.data = Bytes::from(calldata);
+            })
+            .build();
+        match evm.transact_commit().unwrap() {
+            ExecutionResult::Success {
+                output: Output::Call(output),
+                ..
+            } => {
+                assert_eq!(output.len(), 32);
+                output[31] == 1
+            }
+            result => panic!("call failed: {:?}", result),
+        }
+    }
+
+    /// Returns every single leaf, all the leaves, every third leaf and the last and the first
+    /// leaves in reverse order, without duplicate indices
+    fn index_sets(leaves_count: usize) -> Vec<Vec<usize>> {
+        let mut index_sets: Vec<Vec<usize>> = (0..leaves_count).map(|x| vec![x]).collect();
+        index_sets.push((0..leaves_count).collect());
+        index_sets.push((0..leaves_count).step_by(3).collect());
+        if leaves_count > 1 {
+            index_sets.push(vec![leaves_count - 1, 0]);
+        }
+        index_sets.dedup();
+        index_sets
+    }
+
+    /// Returns the indices in ascending order, which [`MerkleTree::proof`] expects
+    fn sorted(indices: &[usize]) -> Vec<usize> {
+        let mut sorted_indices = indices.to_vec();
+        sorted_indices.sort_unstable();
+        sorted_indices
+    }
+
+    /// Checks that the proofs sent to the EVM verify in Rust, which doesn't need solc
+    fn assert_verifies_in_rust<T: Hasher<Hash = [u8; 32]>>() {
+        for leaves_count in 1..=17usize {
+            let leaves: Vec<[u8; 32]> = (0..leaves_count)
+                .map(|x| T::hash(x.to_string().as_bytes()))
+                .collect();
+            let merkle_tree = MerkleTree::<T>::from_leaves(&leaves);
+            let root = merkle_tree.root().unwrap();
+
+            for indices in index_sets(leaves_count) {
+                let proof = merkle_tree.proof(&sorted(&indices));
+                let leaf_hashes: Vec<[u8; 32]> = indices.iter().map(|x| leaves[*x]).collect();
+                assert!(
+                    proof.verify(root, &indices, &leaf_hashes, leaves_count),
+                    "proof of {:?} out of {} leaves is rejected",
+                    indices,
+                    leaves_count
+                );
+            }
+        }
+    }
+
+    /// Checks that proofs of every leaf and of a few leaf sets verify on the EVM and that
+    /// tampered proofs don't, for trees of up to 17 leaves
+    fn assert_verifies_on_evm<T: Hasher<Hash = [u8; 32]>>(config: VerifierConfig) {
+        let mut db = InMemoryDB::default();
+        let address = deploy(&mut db, compile(&config));
+
+        for leaves_count in 1..=17usize {
+            let leaves: Vec<[u8; 32]> = (0..leaves_count)
+                .map(|x| T::hash(x.to_string().as_bytes()))
+                .collect();
+            let merkle_tree = MerkleTree::<T>::from_leaves(&leaves);
+            let root = merkle_tree.root().unwrap();
+
+            for indices in index_sets(leaves_count) {
+                let proof = merkle_tree.proof(&sorted(&indices));
+                let leaf_hashes: Vec<[u8; 32]> = indices.iter().map(|x| leaves[*x]).collect();
+                assert!(proof.verify(root, &indices, &leaf_hashes, leaves_count));
+
+                let calldata = solidity::encode_verify_call(
+                    root,
+                    &proof,
+                    &indices,
+                    &leaf_hashes,
+                    leaves_count,
+                );
+                assert!(
+                    call_verify(&mut db, address, calldata),
+                    "proof of {:?} out of {} leaves is rejected",
+                    indices,
+                    leaves_count
+                );
+
+                let wrong_root = T::hash(b"wrong root");
+                let calldata = solidity::encode_verify_call(
+                    wrong_root,
+                    &proof,
+                    &indices,
+                    &leaf_hashes,
+                    leaves_count,
+                );
+                assert!(!call_verify(&mut db, address, calldata));
+
+                let mut wrong_leaves = leaf_hashes.clone();
+                wrong_leaves[0][0] ^= 1;
+                let calldata = solidity::encode_verify_call(
+                    root,
+                    &proof,
+                    &indices,
+                    &wrong_leaves,
+                    leaves_count,
+                );
+                assert!(!call_verify(&mut db, address, calldata));
+
+                if let Some(first_hash) = proof.proof_hashes().first() {
+                    let mut proof_hashes = proof.proof_hashes().to_vec();
+                    proof_hashes[0] = T::hash(first_hash);
+                    let tampered_proof = rs_merkle::MerkleProof::<T>::new(proof_hashes);
+                    let calldata = solidity::encode_verify_call(
+                        root,
+                        &tampered_proof,
+                        &indices,
+                        &leaf_hashes,
+                        leaves_count,
+                    );
+                    assert!(!call_verify(&mut db, address, calldata));
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_make_proofs_that_verify_in_rust() {
+        assert_verifies_in_rust::<Keccak256>();
+        assert_verifies_in_rust::<DuplicatingKeccak256>();
+        assert_verifies_in_rust::<Sha256>();
+        assert_verifies_in_rust::<PrefixedKeccak256>();
+    }
+
+    #[test]
+    #[ignore = "requires solc"]
+    pub fn should_verify_keccak256_proofs_with_propagated_odd_nodes() {
+        assert_verifies_on_evm::<Keccak256>(VerifierConfig::new(SolidityHasher::Keccak256));
+    }
+
+    #[test]
+    #[ignore = "requires solc"]
+    pub fn should_verify_keccak256_proofs_with_duplicated_odd_nodes() {
+        assert_verifies_on_evm::<DuplicatingKeccak256>(
+            VerifierConfig::new(SolidityHasher::Keccak256)
+                .with_odd_node_policy(OddNodePolicy::Duplicate),
+        );
+    }
+
+    #[test]
+    #[ignore = "requires solc"]
+    pub fn should_verify_sha256_proofs() {
+        assert_verifies_on_evm::<Sha256>(VerifierConfig::new(SolidityHasher::Sha256));
+    }
+
+    #[test]
+    #[ignore = "requires solc"]
+    pub fn should_verify_proofs_with_node_prefix() {
+        assert_verifies_on_evm::<PrefixedKeccak256>(
+            VerifierConfig::new(SolidityHasher::Keccak256).with_node_prefix(&[1]),
+        );
+    }
+}
@@ -0,0 +1,454 @@
+pub mod ssz {
+    use rs_merkle::{
+        algorithms::Sha256,
+        ssz::{self, Chunk, ChunkTree},
+        utils, ErrorKind, IncrementalMerkleTree,
+    };
+    use std::{fs, path::Path};
+
+    fn hex(chunk: Chunk) -> String {
+        utils::collections::to_hex_string(&chunk)
+    }
+
+    fn chunks(count: u8) -> Vec<Chunk> {
+        (0..count).map(|x| [x; 32]).collect()
+    }
+
+    fn chunk(hex: &str) -> Chunk {
+        let mut chunk = ssz::ZERO_CHUNK;
+        chunk.copy_from_slice(&utils::collections::from_hex_string(hex).unwrap());
+        chunk
+    }
+
+    /// Root of an empty `List[T, limit]` of composite values
+    fn empty_list_root(limit: usize) -> Chunk {
+        ssz::mix_in_length(ssz::merkleize(&[], Some(limit)).unwrap(), 0)
+    }
+
+    /// Roots of the fields of the mainnet genesis `BeaconBlockHeader`: slot, proposer index,
+    /// parent root, state root and the root of the empty phase0 `BeaconBlockBody`
+    fn genesis_header_fields() -> Vec<Chunk> {
+        let randao_reveal = ssz::merkleize(&ssz::pack(&[0u8; 96]), None).unwrap();
+        let eth1_data = ssz::merkleize(&[ssz::ZERO_CHUNK; 3], None).unwrap();
+        let body_root = ssz::merkleize(
+            &[
+                randao_reveal,
+                eth1_data,
+                ssz::ZERO_CHUNK,
+                // Proposer slashings, attester slashings, attestations, deposits and exits
+                empty_list_root(16),
+                empty_list_root(2),
+                empty_list_root(128),
+                empty_list_root(16),
+                empty_list_root(16),
+            ],
+            None,
+        )
+        .unwrap();
+
+        vec![
+            ssz::ZERO_CHUNK,
+            ssz::ZERO_CHUNK,
+            ssz::ZERO_CHUNK,
+            chunk("7e76880eb67bbdc86250aa578958e9d0675e64e714337855204fb5abaaf82c2b"),
+            body_root,
+        ]
+    }
+
+    /// Reads the `value.yaml` and the root from `meta.yaml` of every valid case of an
+    /// `ssz_generic` handler vendored in tests/data
+    fn spec_cases(handler: &str) -> Vec<(String, String, Chunk)> {
+        let handler_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/data/ssz_generic")
+            .join(handler)
+            .join("valid");
+        let mut cases: Vec<(String, String, Chunk)> = fs::read_dir(handler_dir)
+            .unwrap()
+            .map(|entry| {
+                let case_dir = entry.unwrap().path();
+                let case = case_dir.file_name().unwrap().to_str().unwrap().to_string();
+                let value = fs::read_to_string(case_dir.join("value.yaml")).unwrap();
+                let meta = fs::read_to_string(case_dir.join("meta.yaml")).unwrap();
+                let root = meta.trim().trim_start_matches("{root: '0x");
+                (case, value, chunk(root.trim_end_matches("'}")))
+            })
+            .collect();
+        cases.sort_by(|a, b| a.0.cmp(&b.0));
+        assert!(!cases.is_empty());
+        cases
+    }
+
+    /// Returns the value of a field of a container from its `value.yaml`
+    fn spec_field<'a>(value: &'a str, field: &str) -> &'a str {
+        value
+            .lines()
+            .find_map(|line| line.strip_prefix(field)?.strip_prefix(": "))
+            .unwrap()
+    }
+
+    /// Root of a basic value, such as an integer
+    fn basic_root(bytes: &[u8]) -> Chunk {
+        ssz::merkleize(&ssz::pack(bytes), None).unwrap()
+    }
+
+    #[test]
+    pub fn should_compute_zero_hashes() {
+        let zero_hashes: Vec<String> = ssz::zero_hashes(4).into_iter().map(hex).collect();
+        assert_eq!(
+            zero_hashes,
+            vec![
+                "0000000000000000000000000000000000000000000000000000000000000000",
+                "f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a92759fb4b",
+                "db56114e00fdd4c1f85c892bf35ac9a89289aaecb1ebd0a96cde606a748b5d71",
+                "c78009fdf07fc56a11f122370658a353aaa542ed63e44c4bc15ff4cd105ab33c",
+            ]
+        );
+
+        // A `BeaconBlockHeader` of zeros has 5 fields, padded to 8
+        assert_eq!(
+            ssz::merkleize(&[ssz::ZERO_CHUNK; 5], None).unwrap(),
+            ssz::zero_hashes(4)[3]
+        );
+        assert_eq!(
+            ssz::merkleize(&[], Some(4)).unwrap(),
+            ssz::zero_hashes(3)[2]
+        );
+    }
+
+    #[test]
+    pub fn should_pack_values_into_chunks() {
+        let values: Vec<u8> = [1u64, 2, 3, 4, 5]
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect();
+        let chunks = ssz::pack(&values);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(&chunks[0][..8], &1u64.to_le_bytes());
+        assert_eq!(&chunks[1][..8], &5u64.to_le_bytes());
+        assert_eq!(&chunks[1][8..], &[0u8; 24]);
+        assert!(ssz::pack(&[]).is_empty());
+
+        let chunks = ssz::pack_bits(&[true, false, true, true, false, false, false, false, true]);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(&chunks[0][..3], &[0b0000_1101, 0b0000_0001, 0]);
+
+        assert_eq!(ssz::chunk_count(100, 8).unwrap(), 25);
+        assert_eq!(ssz::chunk_count(2048 / 8, 1).unwrap(), 8);
+        assert_eq!(
+            ssz::chunk_count(usize::MAX, 8).err().map(|err| err.kind()),
+            Some(ErrorKind::InvalidDepth)
+        );
+    }
+
+    #[test]
+    pub fn should_match_the_reference_merkleization() {
+        // `List[uint64, 100]` of [1, 2, 3]
+        let values: Vec<u8> = [1u64, 2, 3].iter().flat_map(|x| x.to_le_bytes()).collect();
+        let root =
+            ssz::merkleize(&ssz::pack(&values), Some(ssz::chunk_count(100, 8).unwrap())).unwrap();
+        assert_eq!(
+            hex(ssz::mix_in_length(root, 3)),
+            "7fae6fdfa89a6996cb08047fc214d50987e74292f93920274b4b9f3a05bb9898"
+        );
+
+        // Empty `List[uint64, 100]`
+        let root = ssz::merkleize(&[], Some(ssz::chunk_count(100, 8).unwrap())).unwrap();
+        assert_eq!(
+            hex(ssz::mix_in_length(root, 0)),
+            "52e2647abc3d0c9d3be0387f3f0d925422c7a4e98cf4489066f0f43281a899f3"
+        );
+
+        // `Bitlist[2048]` of 9 bits
+        let bits = [true, false, true, true, false, false, false, false, true];
+        let root = ssz::merkleize(
+            &ssz::pack_bits(&bits),
+            Some(ssz::chunk_count(256, 1).unwrap()),
+        )
+        .unwrap();
+        assert_eq!(
+            hex(ssz::mix_in_length(root, bits.len())),
+            "845e79797966e05ba7a5abf30d918bb817df1bfd838b204dec8782e8c0cce464"
+        );
+
+        // `Bitvector[512]` of ones
+        assert_eq!(
+            hex(ssz::merkleize(&ssz::pack_bits(&[true; 512]), None).unwrap()),
+            "8667e718294e9e0df1d30600ba3eeb201f764aad2dad72748643e4a285e1d1f7"
+        );
+
+        // `Vector[Bytes32, 5]`
+        assert_eq!(
+            hex(ssz::merkleize(&chunks(5), None).unwrap()),
+            "26b864a5fd6483296b66858580164a884e7ba8797ebf4c4a2500843b354f438d"
+        );
+
+        assert_eq!(
+            ssz::merkleize(&chunks(5), Some(4))
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::TreeIsFull)
+        );
+    }
+
+    #[test]
+    pub fn should_match_mainnet_roots() {
+        // The genesis block root of the beacon chain mainnet
+        let header = ChunkTree::new(&genesis_header_fields(), None).unwrap();
+        assert_eq!(
+            hex(header.root()),
+            "4d611d5b93fdab69013a7f0a2f961caca0c853f87cfe9595fe50038163079360"
+        );
+        assert_eq!(
+            hex(genesis_header_fields()[4]),
+            "ccb62460692be0ec813b56be97f68a82cf57abc102e27bf49ebf4190ff22eedd"
+        );
+
+        // `get_deposit_root` of the deposit contract before the first deposit
+        assert_eq!(
+            hex(empty_list_root(1 << 32)),
+            "d70a234731285c6804c2a4f56711ddb8c82c99740f207854891028af34e27e5e"
+        );
+    }
+
+    #[test]
+    pub fn should_match_the_spec_tests_bitlist_roots() {
+        for (case, value, root) in spec_cases("bitlist") {
+            // Cases are named `bitlist_{limit}_{kind}_{n}`
+            let limit: usize = case.split('_').nth(1).unwrap().parse().unwrap();
+            let value = value.trim().trim_matches('\'').trim_start_matches("0x");
+            let bytes = utils::collections::from_hex_string(value).unwrap();
+
+            // The highest set bit of the last byte marks the length of the bitlist
+            let last_byte = *bytes.last().unwrap();
+            let len = (bytes.len() - 1) * 8 + 7 - last_byte.leading_zeros() as usize;
+            let bits: Vec<bool> = (0..len)
+                .map(|position| bytes[position / 8] & (1 << (position % 8)) != 0)
+                .collect();
+
+            let chunk_limit = ssz::chunk_count(limit.div_ceil(8), 1).unwrap();
+            let bits_root = ssz::merkleize(&ssz::pack_bits(&bits), Some(chunk_limit)).unwrap();
+            assert_eq!(
+                hex(ssz::mix_in_length(bits_root, len)),
+                hex(root),
+                "{}",
+                case
+            );
+        }
+    }
+
+    #[test]
+    pub fn should_match_the_spec_tests_container_roots() {
+        for (case, value, root) in spec_cases("containers") {
+            let field = |name| spec_field(&value, name);
+            let field_roots = match case.split('_').next().unwrap() {
+                "SmallTestStruct" => vec![
+                    basic_root(&field("A").parse::<u16>().unwrap().to_le_bytes()),
+                    basic_root(&field("B").parse::<u16>().unwrap().to_le_bytes()),
+                ],
+                "FixedTestStruct" => vec![
+                    basic_root(&field("A").parse::<u8>().unwrap().to_le_bytes()),
+                    basic_root(&field("B").parse::<u64>().unwrap().to_le_bytes()),
+                    basic_root(&field("C").parse::<u32>().unwrap().to_le_bytes()),
+                ],
+                "VarTestStruct" => {
+                    // `B` is a `List[uint16, 1024]`
+                    let list: Vec<u8> = field("B")
+                        .trim_matches(|c| c == '[' || c == ']')
+                        .split(", ")
+                        .filter(|element| !element.is_empty())
+                        .flat_map(|element| element.parse::<u16>().unwrap().to_le_bytes())
+                        .collect();
+                    let chunk_limit = ssz::chunk_count(1024, 2).unwrap();
+                    let list_root = ssz::merkleize(&ssz::pack(&list), Some(chunk_limit)).unwrap();
+
+                    vec![
+                        basic_root(&field("A").parse::<u16>().unwrap().to_le_bytes()),
+                        ssz::mix_in_length(list_root, list.len() / 2),
+                        basic_root(&field("C").parse::<u8>().unwrap().to_le_bytes()),
+                    ]
+                }
+                container => panic!("unexpected container {}", container),
+            };
+
+            let container_root = ssz::merkleize(&field_roots, None).unwrap();
+            assert_eq!(hex(container_root), hex(root), "{}", case);
+        }
+    }
+
+    #[test]
+    pub fn should_verify_branches_against_the_mainnet_genesis_block_root() {
+        let fields = genesis_header_fields();
+        let header = ChunkTree::new(&fields, None).unwrap();
+        let block_root = chunk("4d611d5b93fdab69013a7f0a2f961caca0c853f87cfe9595fe50038163079360");
+
+        // The state root and the body root of a header, as light clients prove them
+        for (field, gindex) in [(3, 11), (4, 12)] {
+            let branch = header.proof(gindex).unwrap();
+            assert_eq!(branch.len(), 3);
+            assert!(ssz::verify_merkle_proof(
+                fields[field],
+                &branch,
+                gindex,
+                block_root
+            ));
+            assert!(ssz::is_valid_merkle_branch(
+                fields[field],
+                &branch,
+                3,
+                gindex - 8,
+                block_root
+            ));
+            assert!(!ssz::verify_merkle_proof(
+                ssz::ZERO_CHUNK,
+                &branch,
+                gindex,
+                block_root
+            ));
+        }
+
+        let multiproof = header.multiproof(&[11, 12]).unwrap();
+        assert!(ssz::verify_merkle_multiproof(
+            &[fields[3], fields[4]],
+            &multiproof,
+            &[11, 12],
+            block_root
+        ));
+    }
+
+    #[test]
+    pub fn should_compute_generalized_indices() {
+        // Light client gindices of `BeaconState` fields, Altair has 24 fields and Electra 37
+        let altair_finalized_root = ssz::concat_generalized_indices(&[32 + 20, 3]);
+        assert_eq!(altair_finalized_root, 105);
+        assert_eq!(ssz::concat_generalized_indices(&[32 + 22]), 54);
+        assert_eq!(ssz::concat_generalized_indices(&[32 + 23]), 55);
+        assert_eq!(ssz::concat_generalized_indices(&[64 + 20, 3]), 169);
+        assert_eq!(ssz::concat_generalized_indices(&[64 + 22]), 86);
+
+        assert_eq!(ssz::generalized_index_length(105), 6);
+        assert_eq!(ssz::subtree_index(105), 41);
+        assert_eq!(ssz::generalized_index_parent(105), 52);
+        assert_eq!(ssz::generalized_index_sibling(105), 104);
+        assert_eq!(ssz::generalized_index_child(52, true), 105);
+        assert!(ssz::generalized_index_bit(105, 0));
+        assert!(!ssz::generalized_index_bit(105, 1));
+
+        assert_eq!(ssz::helper_indices(&[105]), vec![104, 53, 27, 12, 7, 2]);
+        assert_eq!(ssz::helper_indices(&[9, 10, 14]), vec![15, 11, 8, 6]);
+    }
+
+    #[test]
+    pub fn should_make_and_verify_proofs_by_generalized_index() {
+        let chunks = chunks(5);
+        let tree = ChunkTree::new(&chunks, Some(8)).unwrap().with_length(5);
+        let root = tree.root();
+        assert_eq!(
+            hex(root),
+            "e2c20d632fa8ef0d567a62661a330610f8e182efede4ab2d5d40f4b595e1bafd"
+        );
+        assert_eq!(tree.depth(), 4);
+        assert_eq!(tree.node(3), Some(ssz::pack(&5u64.to_le_bytes())[0]));
+        assert_eq!(
+            tree.node(2),
+            Some(ssz::merkleize(&chunks, Some(8)).unwrap())
+        );
+        assert_eq!(tree.node(32), None);
+
+        for (chunk_index, chunk) in chunks.iter().enumerate() {
+            let gindex = tree.chunk_generalized_index(chunk_index);
+            let proof = tree.proof(gindex).unwrap();
+
+            assert_eq!(proof.len(), 4);
+            assert!(ssz::verify_merkle_proof(*chunk, &proof, gindex, root));
+            assert!(ssz::is_valid_merkle_branch(
+                *chunk,
+                &proof,
+                4,
+                chunk_index,
+                root
+            ));
+            assert!(!ssz::verify_merkle_proof(*chunk, &proof, gindex + 1, root));
+            assert_eq!(
+                ssz::calculate_merkle_root(*chunk, &proof[1..], gindex)
+                    .err()
+                    .map(|err| err.kind()),
+                Some(ErrorKind::NotEnoughHashesToCalculateRoot)
+            );
+        }
+
+        // Two chunks and the length
+        let indices = [17, 20, 3];
+        let leaves: Vec<Chunk> = indices
+            .iter()
+            .map(|index| tree.node(*index).unwrap())
+            .collect();
+        let proof = tree.multiproof(&indices).unwrap();
+        assert_eq!(proof.len(), 4);
+        assert_eq!(proof[2], ssz::zero_hashes(2)[1]);
+        assert!(ssz::verify_merkle_multiproof(
+            &leaves, &proof, &indices, root
+        ));
+        assert!(!ssz::verify_merkle_multiproof(
+            &leaves,
+            &proof,
+            &[17, 21, 3],
+            root
+        ));
+        assert_eq!(
+            ssz::calculate_multi_merkle_root(&leaves, &proof[1..], &indices)
+                .err()
+                .map(|err| err.kind()),
+            Some(ErrorKind::NotEnoughHashesToCalculateRoot)
+        );
+
+        assert_eq!(
+            tree.proof(0).err().map(|err| err.kind()),
+            Some(ErrorKind::InvalidGeneralizedIndex)
+        );
+        assert_eq!(
+            tree.proof(64).err().map(|err| err.kind()),
+            Some(ErrorKind::InvalidGeneralizedIndex)
+        );
+    }
+
+    #[test]
+    pub fn should_verify_deposit_proofs() {
+        // Deposit proofs have the number of deposits as the last node of the branch
+        let deposits = chunks(7);
+        let mut deposit_tree = IncrementalMerkleTree::<Sha256>::new(32).unwrap();
+        for deposit in &deposits {
+            deposit_tree.append(*deposit).unwrap();
+        }
+        let deposit_root = deposit_tree.deposit_root();
+
+        let mut branch = deposit_tree
+            .proof(&deposits, 3)
+            .unwrap()
+            .proof_hashes()
+            .to_vec();
+        branch.push(ssz::pack(&7u64.to_le_bytes())[0]);
+
+        assert!(ssz::is_valid_merkle_branch(
+            deposits[3],
+            &branch,
+            33,
+            3,
+            deposit_root
+        ));
+        assert!(!ssz::is_valid_merkle_branch(
+            deposits[4],
+            &branch,
+            33,
+            3,
+            deposit_root
+        ));
+        assert!(!ssz::is_valid_merkle_branch(
+            deposits[3],
+            &branch,
+            33,
+            4,
+            deposit_root
+        ));
+    }
+}